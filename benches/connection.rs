@@ -0,0 +1,72 @@
+//! Benchmarks for decoding responses, rewriting links, and saving documents in bulk through the
+//! connection layer. Run with `cargo bench --features bench --bench connection`.
+
+use std::fs;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use get_outline::fixtures::{self, responses, MockServer};
+use get_outline::logic::documents::{self, ContentSource, Document, SearchResult};
+use get_outline::logic::parsers;
+
+/// How many documents the bulk save fetches and writes per iteration.
+const BULK_DOCUMENTS: u64 = 50;
+
+fn serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialization");
+    group.bench_function("documents.info", |b| {
+        b.iter(|| fixtures::decode::<Document>(black_box(responses::DOCUMENT_INFO)))
+    });
+    group.bench_function("documents.search", |b| {
+        b.iter(|| fixtures::decode::<Vec<SearchResult>>(black_box(responses::DOCUMENT_SEARCH)))
+    });
+    group.finish();
+}
+
+fn link_rewriting(c: &mut Criterion) {
+    let markdown: String = (0..1_000)
+        .map(|n| {
+            format!(
+                "Paragraph {n} links to [a page](https://old.example.com/doc/{n}), \
+                 [elsewhere](https://other.example.com/{n}), and `old.example.com` in code.\n\n"
+            )
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("rewrite_links");
+    group.throughput(Throughput::Bytes(markdown.len() as u64));
+    group.bench_function("1000 paragraphs", |b| {
+        b.iter(|| {
+            parsers::rewrite_links(black_box(&markdown), "old.example.com", "new.example.com")
+        })
+    });
+    group.finish();
+}
+
+fn bulk_save(c: &mut Criterion) {
+    let server = MockServer::start(&[("documents.info", responses::DOCUMENT_INFO)])
+        .expect("could not start the mock server");
+    let client = fixtures::client(server.url()).expect("could not build a client");
+    let dir = std::env::temp_dir().join(format!("get_outline-bench-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("could not create the output directory");
+
+    let mut group = c.benchmark_group("bulk_save");
+    group.throughput(Throughput::Elements(BULK_DOCUMENTS));
+    group.bench_function("documents.info to files", |b| {
+        b.iter(|| {
+            for n in 0..BULK_DOCUMENTS {
+                let document =
+                    documents::fetch_document(&client, &format!("doc-{n}"), ContentSource::Info)
+                        .expect("the mock server should answer");
+                let name = format!("{}-{n}.md", parsers::file_name_for(&document.title));
+                fs::write(dir.join(name), document.text).expect("could not write the document");
+            }
+        })
+    });
+    group.finish();
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+criterion_group!(benches, serialization, link_rewriting, bulk_save);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+//! Unpacking the zip archives getOutline delivers exports in.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use anyhow::Context;
+use zip::ZipArchive;
+
+use super::errors;
+
+/// Makes sure an archive can be extracted into `dir`: unless `overwrite` is set, it must not exist
+/// yet (or be empty), so an export can't silently mix with files which were already there.
+///
+/// This is worth checking before starting an export, which can take a while.
+pub(super) fn check_destination(dir: &Path, overwrite: bool) -> Result<(), errors::FileExists> {
+    let occupied = fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_some());
+    if occupied && !overwrite {
+        return Err(errors::FileExists {
+            path: dir.to_path_buf(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Extracts `archive` into `dir`, returning how many files it held.
+pub(super) fn extract(archive: &[u8], dir: &Path) -> anyhow::Result<usize> {
+    let mut zip =
+        ZipArchive::new(Cursor::new(archive)).context("the export isn't a zip archive")?;
+    fs::create_dir_all(dir).with_context(|| format!("could not create {}", dir.display()))?;
+    // Entries which would land outside `dir` (like `../x`) are refused by the zip crate
+    zip.extract(dir)
+        .with_context(|| format!("could not extract the export into {}", dir.display()))?;
+
+    Ok(zip
+        .file_names()
+        .filter(|name| name.as_ref().is_ok_and(|name| !name.ends_with('/')))
+        .count())
+}
@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::documents::{self, Recent};
+use super::output::{print_json, Output};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::attachments::{self as logic, NewAttachment};
+
+#[derive(Subcommand)]
+pub enum AttachmentsCommand {
+    /// Uploads a file, printing the markdown to paste into a document to embed or link to it.
+    Upload {
+        /// The file to upload.
+        file: PathBuf,
+        /// The document the file is for. Only people who can read the document can download it.
+        #[arg(long, value_parser = documents::document_id)]
+        document: Option<String>,
+        /// The file's MIME type, e.g. `image/png`. Guessed from its extension if not given.
+        #[arg(long)]
+        content_type: Option<String>,
+    },
+}
+
+pub fn exec(
+    cmd: AttachmentsCommand,
+    client: &GetOutlineClient,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    match cmd {
+        AttachmentsCommand::Upload {
+            file,
+            document,
+            content_type,
+        } => {
+            let document = match document {
+                Some(document) => Some(documents::resolve_reference(
+                    &Recent::load(profile),
+                    &document,
+                )?),
+                None => None,
+            };
+            upload(
+                client,
+                file,
+                document.as_deref(),
+                content_type.as_deref(),
+                output,
+            )
+        }
+    }
+}
+
+fn upload(
+    client: &GetOutlineClient,
+    file: PathBuf,
+    document_id: Option<&str>,
+    content_type: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let content = fs::read(&file).with_context(|| format!("could not read {}", file.display()))?;
+    let name = file
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .with_context(|| format!("{} isn't a file", file.display()))?;
+
+    let attachment = logic::upload_attachment(
+        client,
+        &NewAttachment {
+            name: &name,
+            content_type: content_type.unwrap_or_else(|| logic::content_type_for(&name)),
+            content: &content,
+            document_id,
+        },
+    )?;
+    if output.json {
+        return print_json(&json!({
+            "id": attachment.id,
+            "name": attachment.name,
+            "url": format!("{}{}", client.base_url(), attachment.url),
+            "contentType": attachment.content_type,
+            "markdown": logic::markdown_for(&attachment),
+        }));
+    }
+
+    // Only the markdown goes to stdout, so scripts can capture it
+    eprintln!(
+        "Uploaded {} to {}{}",
+        file.display(),
+        client.base_url(),
+        attachment.url
+    );
+    println!("{}", logic::markdown_for(&attachment));
+
+    Ok(())
+}
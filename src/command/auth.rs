@@ -0,0 +1,244 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use anyhow::Context;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::errors::{InvalidArguments, InvalidSetting, StdoutError};
+use super::output::{print_json, Output};
+use super::{confirm, HIDE_SECRETS};
+use crate::config;
+use crate::getoutline_connection::{ConfigError, GetOutlineClient};
+use crate::logic::api_keys::{self as logic, ApiKeyDeleter};
+use crate::logic::workspace::WorkspaceReader;
+
+#[derive(Subcommand)]
+pub enum AuthCommand {
+    /// Replaces the API key in use with a new one: creates the new key, checks it works, saves it
+    /// in place of the old one, and then revokes the old one.
+    ///
+    /// The new key is saved by changing the line which sets the old one in the `.env` file. Keys
+    /// set some other way can be rotated with `--print`, which prints the new key instead.
+    Rotate {
+        /// What to call the new key. Defaults to the old key's name.
+        #[arg(long)]
+        name: Option<String>,
+        /// The ID of the key in use, in case several of your keys end in the same four
+        /// characters.
+        #[arg(long)]
+        key_id: Option<String>,
+        /// The file the key in use is set in, like `GETOUTLINE_API_KEY=...` (or the profile's
+        /// own setting).
+        #[arg(long, value_name = "FILE", default_value = ".env")]
+        env_file: PathBuf,
+        /// Prints the new key on stdout instead of saving it in a file.
+        #[arg(long, conflicts_with = "env_file")]
+        print: bool,
+        /// Leaves the old key working, to be revoked once nothing uses it anymore.
+        #[arg(long)]
+        keep_old: bool,
+        /// Revokes the old key without asking first.
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+pub fn exec(
+    cmd: AuthCommand,
+    client: &GetOutlineClient,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    match cmd {
+        AuthCommand::Rotate {
+            name,
+            key_id,
+            env_file,
+            print,
+            keep_old,
+            yes,
+        } => {
+            let destination = (!print).then_some(env_file.as_path());
+            let options = RotateOptions {
+                name,
+                key_id,
+                keep_old,
+                yes,
+            };
+            rotate(client, profile, destination, options, output)
+        }
+    }
+}
+
+/// What `auth rotate` was asked to do, besides where to save the new key.
+struct RotateOptions {
+    name: Option<String>,
+    key_id: Option<String>,
+    keep_old: bool,
+    yes: bool,
+}
+
+/// Rotates the API key `client` connects with, saving the new one in `env_file`, or printing it
+/// if there's no file to save it in.
+fn rotate(
+    client: &GetOutlineClient,
+    profile: Option<&str>,
+    env_file: Option<&Path>,
+    options: RotateOptions,
+    output: Output,
+) -> anyhow::Result<()> {
+    let var = config::var_name(profile, "API_KEY");
+    let secret = config::get(profile, "API_KEY")
+        .ok_or_else(|| ConfigError::MissingApiKey { var: var.clone() })?;
+    if env_file.is_none() && HIDE_SECRETS.load(Ordering::Relaxed) {
+        return Err(InvalidArguments {
+            message: "--print can't show the new key while secrets are hidden from output"
+                .to_string(),
+        }
+        .into());
+    }
+
+    // Anything which would keep the new key from being saved is found before it's created
+    let contents = env_file
+        .map(|path| read_env_file(path, &var, &secret))
+        .transpose()?;
+    let owner = client.auth_info()?;
+    let old = logic::find_api_key(client, &secret, options.key_id.as_deref())?;
+    let old_last4 = old
+        .last4
+        .clone()
+        .unwrap_or_else(|| logic::last4(&secret).to_string());
+    if !options.keep_old && !options.yes {
+        let prompt = format!(
+            "Replace the API key \"{}\" ending in {old_last4} with a new one? The old key will stop \
+             working, so anything else using it will need the new one.",
+            old.name
+        );
+        if !confirm(&prompt)? {
+            eprintln!("Aborted, nothing was changed.");
+            return Ok(());
+        }
+    }
+
+    let name = options.name.unwrap_or_else(|| old.name.clone());
+    let new = logic::create_api_key(client, &name)?;
+    let saved = client
+        .with_api_key(&new.value)
+        .map_err(anyhow::Error::from)
+        .and_then(|new_client| {
+            logic::verify_api_key(&new_client, &owner)?;
+            if let (Some(path), Some(contents)) = (env_file, &contents) {
+                let updated = config::set_env_file_value(contents, &var, &new.value);
+                write_env_file(path, &updated)
+                    .with_context(|| format!("could not save the new key in {}", path.display()))?;
+            }
+            Ok(new_client)
+        });
+    let new_client = match saved {
+        Ok(new_client) => new_client,
+        Err(err) => {
+            // The .env file is only replaced once the new key is fully written, so until then the old
+            // key is still the one in use, and the new one would only be left lying around
+            let _ = client.delete(&new.key.id);
+            return Err(err.context("the old API key is still in use"));
+        }
+    };
+    // Commands run after this one in `shell` connect with the new key
+    std::env::set_var(&var, &new.value);
+    if env_file.is_none() && !output.json {
+        println!("{}", new.value);
+    }
+
+    let new_last4 = logic::last4(&new.value);
+    let status = |message: String| -> Result<(), StdoutError> {
+        match env_file {
+            Some(_) => output.status(message)?,
+            None => eprintln!("{message}"),
+        }
+        Ok(())
+    };
+    status(format!(
+        "Created the API key \"{name}\" ending in {new_last4}"
+    ))?;
+    if let Some(path) = env_file {
+        status(format!("Saved it as {var} in {}", path.display()))?;
+    }
+    if options.keep_old {
+        status(format!(
+            "The old key ending in {old_last4} still works; revoke it in getOutline's settings \
+             once nothing uses it"
+        ))?;
+    } else {
+        new_client
+            .delete(&old.id)
+            .context("the new API key is in use, but the old one couldn't be revoked")?;
+        status(format!("Revoked the old key ending in {old_last4}"))?;
+    }
+
+    if output.json {
+        return print_json(&json!({
+            "id": new.key.id,
+            "name": name,
+            "value": env_file.is_none().then_some(&new.value),
+            "savedTo": env_file,
+            "revokedId": (!options.keep_old).then_some(&old.id),
+        }));
+    }
+
+    Ok(())
+}
+
+/// Reads the `.env` file at `path`, checking it sets `var` to `secret`, the API key in use, so
+/// the new key can be saved in its place.
+/// Replaces the `.env` file at `path` with `contents`, keeping its permissions.
+///
+/// The new contents are written to a temporary file next to it first, which is then renamed over
+/// it, so a failed write leaves the original as it was.
+fn write_env_file(path: &Path, contents: &str) -> io::Result<()> {
+    let permissions = fs::metadata(path)?.permissions();
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    let temp = path.with_file_name(file_name);
+
+    let written = File::create(&temp).and_then(|mut file| {
+        file.set_permissions(permissions)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()
+    });
+    match written.and_then(|()| fs::rename(&temp, path)) {
+        Ok(()) => Ok(()),
+        Err(err) => {
+            let _ = fs::remove_file(&temp);
+            Err(err)
+        }
+    }
+}
+
+fn read_env_file(path: &Path, var: &str, secret: &str) -> anyhow::Result<String> {
+    let contents = fs::read_to_string(path).with_context(|| {
+        format!(
+            "could not read {}; pass --print to print the new key instead",
+            path.display()
+        )
+    })?;
+    let message = match config::env_file_value(&contents, var) {
+        Some(value) if value == secret => return Ok(contents),
+        Some(_) => format!(
+            "is set to a different key in {} than the one in use, which comes from the \
+             environment; pass --print to print the new key instead",
+            path.display()
+        ),
+        None => format!(
+            "isn't set in {}, so the new key can't be saved there; pass --env-file or --print",
+            path.display()
+        ),
+    };
+    Err(InvalidSetting {
+        var: var.to_string(),
+        message,
+    }
+    .into())
+}
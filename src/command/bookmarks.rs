@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+
+use anyhow::Context;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::documents::{self, Recent};
+use super::output::{print_json, Output};
+use crate::config;
+use crate::logic::bookmarks::Bookmarks;
+
+/// The bookmarks file looked for in the working directory and its parents.
+const FILE_NAME: &str = ".getoutline-bookmarks";
+
+#[derive(Subcommand)]
+pub enum BookmarkCommand {
+    /// Gives a document a short name which can be used wherever its ID is expected.
+    Add {
+        /// The name to use, e.g. `runbook`.
+        name: String,
+        /// The ID of the document to bookmark.
+        #[arg(value_parser = documents::document_id)]
+        doc_id: String,
+    },
+    /// Forgets a bookmark. The document isn't affected.
+    Remove {
+        /// The name of the bookmark.
+        name: String,
+    },
+    /// Lists every bookmark, and which file they come from.
+    List,
+}
+
+pub fn exec(cmd: BookmarkCommand, profile: Option<&str>, output: Output) -> anyhow::Result<()> {
+    let path = file_path();
+    let mut bookmarks = load(&path)?;
+
+    match cmd {
+        BookmarkCommand::Add { name, doc_id } => {
+            let doc_id = documents::resolve_reference(&Recent::load(profile), &doc_id)?;
+            let old_id = bookmarks.add(&name, &doc_id)?;
+            save(&path, &bookmarks)?;
+            if output.json {
+                return print_json(&json!({
+                    "name": name,
+                    "documentId": doc_id,
+                    "previousDocumentId": old_id,
+                }));
+            }
+            match old_id {
+                Some(old_id) if old_id != doc_id => {
+                    println!("Moved bookmark {name} from {old_id} to {doc_id}")
+                }
+                _ => println!("Bookmarked {doc_id} as {name}"),
+            }
+            Ok(())
+        }
+        BookmarkCommand::Remove { name } => {
+            let doc_id = bookmarks.remove(&name)?;
+            save(&path, &bookmarks)?;
+            if output.json {
+                return print_json(&json!({ "name": name, "documentId": doc_id }));
+            }
+            println!("Removed bookmark {name} (it pointed at {doc_id})");
+            Ok(())
+        }
+        BookmarkCommand::List if output.json => {
+            let entries: Vec<_> = bookmarks
+                .iter()
+                .map(|(name, doc_id)| json!({ "name": name, "documentId": doc_id }))
+                .collect();
+            print_json(&entries)
+        }
+        BookmarkCommand::List => {
+            let mut entries = bookmarks.iter().peekable();
+            if entries.peek().is_none() {
+                println!("There aren't any bookmarks in {}", path.display());
+                return Ok(());
+            }
+
+            for (name, doc_id) in entries {
+                println!("{name} = {doc_id}");
+            }
+            println!("(from {})", path.display());
+            Ok(())
+        }
+    }
+}
+
+/// The document bookmarked as `name`, if there is one.
+pub(super) fn lookup(name: &str) -> anyhow::Result<Option<String>> {
+    let bookmarks = load(&file_path())?;
+    Ok(bookmarks.get(name).map(str::to_string))
+}
+
+/// Where bookmarks are kept: `GETOUTLINE_BOOKMARKS_FILE` if it's set, otherwise the nearest
+/// `.getoutline-bookmarks` in the working directory or one of its parents (like `.gitignore`), or
+/// a new one in the working directory if there isn't one yet.
+fn file_path() -> PathBuf {
+    if let Some(path) = config::get(None, "BOOKMARKS_FILE") {
+        return PathBuf::from(path);
+    }
+
+    let working_dir = env::current_dir().unwrap_or_default();
+    working_dir
+        .ancestors()
+        .map(|dir| dir.join(FILE_NAME))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| working_dir.join(FILE_NAME))
+}
+
+fn load(path: &Path) -> anyhow::Result<Bookmarks> {
+    let text = match fs::read_to_string(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Bookmarks::default()),
+        result => result.with_context(|| format!("could not read {}", path.display()))?,
+    };
+
+    Bookmarks::parse(&text)
+        .with_context(|| format!("could not read bookmarks from {}", path.display()))
+}
+
+fn save(path: &Path, bookmarks: &Bookmarks) -> anyhow::Result<()> {
+    fs::write(path, bookmarks.render())
+        .with_context(|| format!("could not write {}", path.display()))
+}
@@ -0,0 +1,347 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use std::{fs, io};
+
+use anyhow::Context;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::output::{age, print_json, size, Output};
+use super::{byte_size, completion, duration, errors};
+use crate::config;
+use crate::logic::cache::{self as logic, CacheEntry, CachedDocument, DocumentCache, PrunePolicy};
+use crate::logic::documents::ContentSource;
+use crate::state;
+
+/// The settings limiting how much the cache keeps, checked after `documents cat` and by
+/// `cache prune`.
+const MAX_SIZE: &str = "CACHE_MAX_SIZE";
+const MAX_AGE: &str = "CACHE_MAX_AGE";
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Shows where the copies of documents `documents cat` keeps are, how many there are, how
+    /// much room they take up, and how old they are.
+    Status,
+    /// Deletes the copies of documents `documents cat` keeps for the profile in use, and the list
+    /// of IDs shell completion offers, e.g. before handing the machine over, or when a document's
+    /// copy should be fetched again right away.
+    Clear,
+    /// Deletes the oldest copies of documents until the cache is within its limits.
+    ///
+    /// The limits are `GETOUTLINE_CACHE_MAX_SIZE` and `GETOUTLINE_CACHE_MAX_AGE` (or
+    /// `GETOUTLINE_<PROFILE>_CACHE_MAX_SIZE` and so on for a profile), which `documents cat` also
+    /// keeps to once it's done.
+    Prune {
+        /// Deletes the oldest copies until the rest take up no more than this, e.g. `500K` or
+        /// `20M`, instead of the configured maximum size.
+        #[arg(long, value_parser = byte_size, value_name = "SIZE")]
+        max_size: Option<u64>,
+        /// Deletes the copies fetched longer ago than this, e.g. `12h`, `7d`, or `4w`, instead of
+        /// the configured maximum age.
+        #[arg(long, value_parser = duration, value_name = "DURATION")]
+        max_age: Option<Duration>,
+    },
+}
+
+pub fn exec(cmd: CacheCommand, profile: Option<&str>, output: Output) -> anyhow::Result<()> {
+    let cache = Cache::open(profile);
+    match cmd {
+        CacheCommand::Status => status(cache, prune_policy(profile)?, output),
+        CacheCommand::Clear => {
+            let deleted = match cache {
+                Some(cache) => cache.clear().with_context(|| {
+                    format!("could not clear the cache in {}", cache.dir.display())
+                })?,
+                None => 0,
+            };
+            if let Some(path) = completion::list_path(profile) {
+                match fs::remove_file(&path) {
+                    Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                        return Err(err)
+                            .with_context(|| format!("could not delete {}", path.display()));
+                    }
+                    _ => {}
+                }
+            }
+            if output.json {
+                return print_json(&json!({ "deleted": deleted }));
+            }
+            match deleted {
+                0 => println!("The cache was already empty."),
+                1 => println!("Deleted 1 cached document."),
+                deleted => println!("Deleted {deleted} cached documents."),
+            }
+            Ok(())
+        }
+        CacheCommand::Prune { max_size, max_age } => {
+            let configured = prune_policy(profile)?;
+            let policy = PrunePolicy {
+                max_bytes: max_size.or(configured.max_bytes),
+                max_age: max_age.or(configured.max_age),
+            };
+            prune(cache, policy, output)
+        }
+    }
+}
+
+fn status(cache: Option<Cache>, policy: PrunePolicy, output: Output) -> anyhow::Result<()> {
+    let Some(cache) = cache else {
+        anyhow::bail!("there's no directory to keep a cache in, set GETOUTLINE_CACHE_DIR");
+    };
+    let copies = cache
+        .copies()
+        .with_context(|| format!("could not read the cache in {}", cache.dir.display()))?;
+    let mut documents: Vec<_> = copies.iter().map(|copy| copy.id.as_str()).collect();
+    documents.sort();
+    documents.dedup();
+    let bytes: u64 = copies.iter().map(|copy| copy.entry.bytes).sum();
+    let oldest = copies.iter().map(|copy| copy.entry.age).max();
+    let newest = copies.iter().map(|copy| copy.entry.age).min();
+
+    if output.json {
+        return print_json(&json!({
+            "directory": cache.dir,
+            "documents": documents.len(),
+            "copies": copies.len(),
+            "bytes": bytes,
+            "oldestAgeSeconds": oldest.map(|age| age.as_secs()),
+            "newestAgeSeconds": newest.map(|age| age.as_secs()),
+            "maxBytes": policy.max_bytes,
+            "maxAgeSeconds": policy.max_age.map(|age| age.as_secs()),
+        }));
+    }
+
+    let ago = |copy_age: Option<Duration>| {
+        copy_age.map_or("-".to_string(), |copy_age| format!("{} ago", age(copy_age)))
+    };
+    let limits = match (policy.max_bytes, policy.max_age) {
+        (None, None) => "none".to_string(),
+        (Some(max_bytes), None) => size(max_bytes),
+        (None, Some(max_age)) => format!("{} old", age(max_age)),
+        (Some(max_bytes), Some(max_age)) => {
+            format!("{}, {} old", size(max_bytes), age(max_age))
+        }
+    };
+    let fields = [
+        ("Directory", cache.dir.display().to_string()),
+        (
+            "Documents",
+            format!("{} ({} copies)", documents.len(), copies.len()),
+        ),
+        ("Size", size(bytes)),
+        ("Oldest", ago(oldest)),
+        ("Newest", ago(newest)),
+        ("Limits", limits),
+    ];
+    for (key, value) in fields {
+        println!("{:<10} {value}", format!("{key}:"));
+    }
+
+    Ok(())
+}
+
+fn prune(cache: Option<Cache>, policy: PrunePolicy, output: Output) -> anyhow::Result<()> {
+    if !policy.is_set() {
+        return Err(errors::InvalidArguments {
+            message: format!(
+                "there's nothing to prune the cache by, pass --max-size or --max-age, or set {} or {}",
+                config::var_name(None, MAX_SIZE),
+                config::var_name(None, MAX_AGE)
+            ),
+        }
+        .into());
+    }
+
+    let (deleted, bytes) = match cache {
+        Some(cache) => cache
+            .prune(policy)
+            .with_context(|| format!("could not prune the cache in {}", cache.dir.display()))?,
+        None => (0, 0),
+    };
+    if output.json {
+        return print_json(&json!({ "deleted": deleted, "bytes": bytes }));
+    }
+    match deleted {
+        0 => println!("The cache is already within its limits."),
+        1 => println!("Deleted 1 cached copy, freeing {}.", size(bytes)),
+        deleted => println!("Deleted {deleted} cached copies, freeing {}.", size(bytes)),
+    }
+
+    Ok(())
+}
+
+/// The limits on the cache set for `profile`.
+pub(super) fn prune_policy(profile: Option<&str>) -> anyhow::Result<PrunePolicy> {
+    fn setting<T>(
+        profile: Option<&str>,
+        name: &str,
+        parse: fn(&str) -> Result<T, String>,
+    ) -> Result<Option<T>, errors::InvalidSetting> {
+        config::get(profile, name)
+            .map(|value| {
+                parse(&value).map_err(|message| errors::InvalidSetting {
+                    var: config::var_name(profile, name),
+                    message,
+                })
+            })
+            .transpose()
+    }
+
+    let max_bytes = setting(profile, MAX_SIZE, byte_size)?;
+    let max_age = setting(profile, MAX_AGE, duration)?;
+
+    Ok(PrunePolicy { max_bytes, max_age })
+}
+
+/// One cached copy of a document, as found in the cache's directory.
+struct CachedFile {
+    id: String,
+    path: PathBuf,
+    entry: CacheEntry,
+}
+
+/// The copies of documents kept for one profile, as a file per document and source in a
+/// directory of their own.
+pub(super) struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// The cache for `profile`, or `None` if there's nowhere to keep one.
+    pub(super) fn open(profile: Option<&str>) -> Option<Self> {
+        let name = match profile {
+            Some(profile) => format!("documents-{profile}"),
+            None => "documents".to_string(),
+        };
+        Some(Self {
+            dir: state::cache_dir()?.join(name),
+        })
+    }
+
+    /// The file document `id`'s copy from `source` is kept in, or `None` if the ID couldn't
+    /// safely be part of a file name.
+    fn path(&self, id: &str, source: ContentSource) -> Option<PathBuf> {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return None;
+        }
+        let source = match source {
+            ContentSource::Info => "info",
+            ContentSource::Export => "export",
+        };
+        Some(self.dir.join(format!("{id}.{source}.json")))
+    }
+
+    /// Lists every cached copy, with its size and how long ago it was written.
+    fn copies(&self) -> io::Result<Vec<CachedFile>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            entries => entries?,
+        };
+
+        let mut copies = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            let Some(id) = path
+                .file_name()
+                .and_then(|name| name.to_str()?.strip_suffix(".json")?.split('.').next())
+            else {
+                continue;
+            };
+            let metadata = entry.metadata()?;
+            let age = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .unwrap_or_default();
+            copies.push(CachedFile {
+                id: id.to_string(),
+                entry: CacheEntry {
+                    bytes: metadata.len(),
+                    age,
+                },
+                path,
+            });
+        }
+
+        Ok(copies)
+    }
+
+    /// Deletes the copies `policy` doesn't leave room for, returning how many were deleted and
+    /// how many bytes that freed.
+    fn prune(&self, policy: PrunePolicy) -> io::Result<(usize, u64)> {
+        let copies = self.copies()?;
+        let entries: Vec<_> = copies.iter().map(|copy| copy.entry).collect();
+
+        let pruned = logic::entries_to_prune(&entries, policy);
+        let mut bytes = 0;
+        for &index in &pruned {
+            fs::remove_file(&copies[index].path)?;
+            bytes += copies[index].entry.bytes;
+        }
+        Ok((pruned.len(), bytes))
+    }
+
+    /// Prunes the cache to the limits configured for `profile` if there are any, as a command
+    /// which added to it finishes. Failing to only warrants a warning, since the command worked.
+    pub(super) fn keep_within_limits(&self, profile: Option<&str>) {
+        let result = prune_policy(profile).and_then(|policy| {
+            if policy.is_set() {
+                self.prune(policy)?;
+            }
+            Ok(())
+        });
+        if let Err(err) = result {
+            tracing::warn!("could not prune the cache: {err:#}");
+        }
+    }
+
+    /// Deletes every cached copy, returning how many documents they were for.
+    fn clear(&self) -> io::Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            entries => entries?,
+        };
+
+        let mut documents = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if let Some(id) = path
+                .file_name()
+                .and_then(|name| name.to_str()?.split('.').next())
+            {
+                documents.push(id.to_string());
+            }
+            fs::remove_file(&path)?;
+        }
+        fs::remove_dir(&self.dir)?;
+
+        documents.sort();
+        documents.dedup();
+        Ok(documents.len())
+    }
+}
+
+impl DocumentCache for Cache {
+    fn get(&self, id: &str, source: ContentSource) -> Option<CachedDocument> {
+        let contents = fs::read(self.path(id, source)?).ok()?;
+        serde_json::from_slice(&contents).ok()
+    }
+
+    fn put(&self, id: &str, source: ContentSource, cached: &CachedDocument) {
+        let Some(path) = self.path(id, source) else {
+            return;
+        };
+        let write = || -> io::Result<()> {
+            fs::create_dir_all(&self.dir)?;
+            // Write to a temporary file first so an interrupted run can't leave a half-written copy
+            let temp = path.with_extension("tmp");
+            fs::write(&temp, serde_json::to_vec(cached)?)?;
+            fs::rename(temp, &path)
+        };
+        if let Err(err) = write() {
+            tracing::warn!("could not cache document {id}: {err}");
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::documents::write_new_file;
+use super::output::{print_json, Output};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::catalog as logic;
+
+#[derive(Subcommand)]
+pub enum CatalogCommand {
+    /// Writes the IDs, titles, hierarchy, timestamps, and authors of every collection and
+    /// document as JSON, without any document content.
+    Export {
+        /// The file to write the catalog to. Defaults to printing it.
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+        /// Replaces the file if it already exists.
+        #[arg(long, requires = "out")]
+        overwrite: bool,
+    },
+}
+
+pub fn exec(cmd: CatalogCommand, client: &GetOutlineClient, output: Output) -> anyhow::Result<()> {
+    match cmd {
+        CatalogCommand::Export { out, overwrite } => export(client, out, overwrite, output),
+    }
+}
+
+fn export(
+    client: &GetOutlineClient,
+    out: Option<PathBuf>,
+    overwrite: bool,
+    output: Output,
+) -> anyhow::Result<()> {
+    let catalog = logic::build_catalog(client)?;
+    let mut json =
+        serde_json::to_string_pretty(&catalog).context("could not encode the catalog")?;
+    json.push('\n');
+
+    match out {
+        Some(path) => {
+            write_new_file(&path, json.as_bytes(), overwrite)?;
+            if output.json {
+                return print_json(&json!({
+                    "path": path,
+                    "collections": catalog.collections.len(),
+                    "documents": catalog.documents.len(),
+                }));
+            }
+            println!(
+                "Saved {} collections and {} documents to {}",
+                catalog.collections.len(),
+                catalog.documents.len(),
+                path.display()
+            );
+        }
+        None => print!("{json}"),
+    }
+
+    Ok(())
+}
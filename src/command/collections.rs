@@ -0,0 +1,551 @@
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Subcommand, ValueEnum};
+use serde_json::json;
+
+use super::errors::StdoutError;
+use super::output::table::{Column, Table};
+use super::output::{print_json, Output};
+use super::{archive, ask, ListingArgs};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::collections as logic;
+use crate::logic::file_operations::FileOperationState;
+use crate::logic::{memberships, parsers};
+
+#[derive(Subcommand)]
+pub enum CollectionsCommand {
+    /// Lists the collections in the workspace with how many documents each one holds.
+    List {
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Shows a collection's description, sharing settings, and size.
+    Info {
+        /// The ID of the collection.
+        id: String,
+    },
+    /// Prints the nested structure of a collection's documents, with their IDs.
+    Tree {
+        /// The ID of the collection.
+        id: String,
+    },
+    /// Exports a collection through getOutline and extracts the archive into a local directory.
+    Export {
+        /// The ID of the collection to export.
+        id: String,
+        /// The format to export the documents in.
+        #[arg(long, value_enum, default_value_t = ExportFormat::OutlineMarkdown)]
+        format: ExportFormat,
+        /// The directory to extract the export into. Defaults to one named after the collection.
+        #[arg(long, short, visible_alias = "extract", value_name = "DIR")]
+        output: Option<PathBuf>,
+        /// Extracts into the directory even if it already has files in it, replacing any with the
+        /// same names.
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Creates a new, empty collection.
+    Create {
+        /// The collection's name.
+        #[arg(long)]
+        name: String,
+        /// What the collection is for, shown at the top of it.
+        #[arg(long)]
+        description: Option<String>,
+        /// Only let the people it's shared with see the collection, instead of every member of
+        /// the workspace.
+        #[arg(long)]
+        private: bool,
+    },
+    /// Changes a collection's name, description, or color.
+    #[command(group(
+        ArgGroup::new("changes")
+            .required(true)
+            .multiple(true)
+            .args(["name", "description", "color"])
+    ))]
+    Update {
+        /// The ID of the collection to change.
+        id: String,
+        /// A new name for the collection.
+        #[arg(long)]
+        name: Option<String>,
+        /// A new description for the collection. Pass an empty string to remove it.
+        #[arg(long)]
+        description: Option<String>,
+        /// A new icon color, as a hex code like `#4e5c6e`.
+        #[arg(long, value_parser = hex_color)]
+        color: Option<String>,
+    },
+    /// Deletes a collection and every document in it, after asking you to type its name.
+    Delete {
+        /// The ID of the collection to delete.
+        id: String,
+        /// Skips retyping the collection's name.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Gives a user access to a collection, replacing any access they had to it before.
+    AddUser {
+        /// The ID of the collection.
+        id: String,
+        /// The ID of the user to add.
+        #[arg(long)]
+        user: String,
+        /// What the user is allowed to do in the collection.
+        #[arg(long, value_enum, default_value_t = Permission::ReadWrite)]
+        permission: Permission,
+    },
+    /// Removes a user from a collection. Access they have through a group isn't affected.
+    RemoveUser {
+        /// The ID of the collection.
+        id: String,
+        /// The ID of the user to remove.
+        #[arg(long)]
+        user: String,
+    },
+    /// Gives every member of a group access to a collection.
+    AddGroup {
+        /// The ID of the collection.
+        id: String,
+        /// The ID of the group to add.
+        #[arg(long)]
+        group: String,
+        /// What the group's members are allowed to do in the collection.
+        #[arg(long, value_enum, default_value_t = Permission::ReadWrite)]
+        permission: Permission,
+    },
+    /// Takes away the access a group was given to a collection.
+    RemoveGroup {
+        /// The ID of the collection.
+        id: String,
+        /// The ID of the group to remove.
+        #[arg(long)]
+        group: String,
+    },
+    /// Duplicates a collection's entire document tree into a new collection.
+    Copy {
+        /// The ID of the collection to copy.
+        src_id: String,
+        /// The name of the collection to create.
+        #[arg(long)]
+        to: String,
+    },
+}
+
+/// A format collections can be exported in.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    /// A markdown file per document, with attachments alongside.
+    #[value(alias = "markdown")]
+    OutlineMarkdown,
+    /// getOutline's JSON format, which can be imported into another workspace.
+    Json,
+    /// An HTML file per document.
+    Html,
+}
+
+impl From<ExportFormat> for logic::ExportFormat {
+    fn from(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::OutlineMarkdown => logic::ExportFormat::OutlineMarkdown,
+            ExportFormat::Json => logic::ExportFormat::Json,
+            ExportFormat::Html => logic::ExportFormat::Html,
+        }
+    }
+}
+
+/// An access level a collection can be shared with.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Permission {
+    /// View the collection's documents.
+    Read,
+    /// View and edit the collection's documents.
+    #[value(name = "read_write", alias = "read-write")]
+    ReadWrite,
+    /// Edit the collection's documents and manage the collection, including who it's shared with.
+    Admin,
+}
+
+impl From<Permission> for memberships::CollectionPermission {
+    fn from(permission: Permission) -> Self {
+        match permission {
+            Permission::Read => memberships::CollectionPermission::Read,
+            Permission::ReadWrite => memberships::CollectionPermission::ReadWrite,
+            Permission::Admin => memberships::CollectionPermission::Admin,
+        }
+    }
+}
+
+/// Runs a collections command. `--plain` draws trees with indentation instead of box-drawing
+/// characters.
+pub fn exec(
+    cmd: CollectionsCommand,
+    client: &GetOutlineClient,
+    output: Output,
+) -> anyhow::Result<()> {
+    match cmd {
+        CollectionsCommand::List { listing } => list(client, listing, output),
+        CollectionsCommand::Info { id } => info(client, &id, output),
+        CollectionsCommand::Tree { id } => tree(client, &id, output),
+        CollectionsCommand::Export {
+            id,
+            format,
+            output: directory,
+            overwrite,
+        } => export(client, &id, format, directory, overwrite, output),
+        CollectionsCommand::Update {
+            id,
+            name,
+            description,
+            color,
+        } => update(
+            client,
+            &logic::CollectionUpdate {
+                id: &id,
+                name: name.as_deref(),
+                description: description.as_deref(),
+                color: color.as_deref(),
+            },
+            output,
+        ),
+        CollectionsCommand::Delete { id, force } => delete(client, &id, force, output),
+        CollectionsCommand::Create {
+            name,
+            description,
+            private,
+        } => create(client, &name, description.as_deref(), private, output),
+        CollectionsCommand::AddUser {
+            id,
+            user,
+            permission,
+        } => add_user(client, &id, &user, permission, output),
+        CollectionsCommand::RemoveUser { id, user } => remove_user(client, &id, &user, output),
+        CollectionsCommand::AddGroup {
+            id,
+            group,
+            permission,
+        } => add_group(client, &id, &group, permission, output),
+        CollectionsCommand::RemoveGroup { id, group } => remove_group(client, &id, &group, output),
+        CollectionsCommand::Copy { src_id, to } => copy(client, &src_id, &to, output),
+    }
+}
+
+fn list(client: &GetOutlineClient, listing: ListingArgs, output: Output) -> anyhow::Result<()> {
+    let listings = logic::list_collections(client)?;
+    if output.json {
+        return print_json(&listings);
+    }
+    if listings.is_empty() {
+        println!("There aren't any collections you can see.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Shrink("Name"),
+        Column::Full("ID"),
+        Column::Full("Documents"),
+    ]);
+    for listing in listings {
+        table.row([
+            listing.collection.name,
+            listing.collection.id,
+            listing.document_count.to_string(),
+        ]);
+    }
+    table.print(output, listing.into())?;
+
+    Ok(())
+}
+
+fn info(client: &GetOutlineClient, id: &str, output: Output) -> anyhow::Result<()> {
+    let stats = logic::collection_info(client, id)?;
+    if output.json {
+        return print_json(&stats);
+    }
+    let logic::CollectionStats {
+        collection,
+        document_count: count,
+    } = stats;
+
+    let access = match collection.permission.as_deref() {
+        Some("read_write") => "every member can edit",
+        Some("read") => "every member can view",
+        Some(other) => other,
+        None => "private to its members",
+    };
+    let sharing = if collection.sharing {
+        "allowed"
+    } else {
+        "not allowed"
+    };
+    let description = collection
+        .description
+        .as_deref()
+        .filter(|description| !description.trim().is_empty())
+        .unwrap_or("(none)");
+
+    let fields = [
+        ("Name", collection.name.as_str()),
+        ("ID", &collection.id),
+        ("Description", description),
+        ("Access", access),
+        ("Public sharing", sharing),
+        ("Documents", &count.to_string()),
+        (
+            "Created",
+            collection.created_at.as_deref().unwrap_or("unknown"),
+        ),
+        (
+            "Updated",
+            collection.updated_at.as_deref().unwrap_or("unknown"),
+        ),
+    ];
+    for (key, value) in fields {
+        println!("{:<15} {value}", format!("{key}:"));
+    }
+
+    Ok(())
+}
+
+fn export(
+    client: &GetOutlineClient,
+    id: &str,
+    format: ExportFormat,
+    directory: Option<PathBuf>,
+    overwrite: bool,
+    output: Output,
+) -> anyhow::Result<()> {
+    let collection = logic::CollectionReader::info(client, id)?;
+    let directory =
+        directory.unwrap_or_else(|| PathBuf::from(parsers::file_name_for(&collection.name)));
+    archive::check_destination(&directory, overwrite)?;
+
+    output.status(format_args!("Exporting \"{}\"...", collection.name))?;
+    let archive = logic::export_collection(client, id, format.into(), |state| {
+        print_export_state(state, output)
+    })?;
+    let files = archive::extract(&archive, &directory)?;
+    if output.json {
+        return print_json(&json!({ "directory": directory, "files": files }));
+    }
+    println!("Extracted {files} files into {}", directory.display());
+
+    Ok(())
+}
+
+/// Prints how an export is getting on. If stdout has closed, the export carries on regardless,
+/// and the command stops when it next prints something.
+pub(super) fn print_export_state(state: FileOperationState, output: Output) {
+    let _ = output.status(format_args!("  Export is {}", state.description()));
+}
+
+fn tree(client: &GetOutlineClient, id: &str, output: Output) -> anyhow::Result<()> {
+    let collection = logic::CollectionReader::info(client, id)?;
+    let nodes = logic::CollectionTreeReader::document_tree(client, id)?;
+    if output.json {
+        return print_json(&json!({
+            "id": collection.id,
+            "name": collection.name,
+            "documents": nodes,
+        }));
+    }
+    let plain = output.plain;
+
+    println!("{} ({})", collection.name, collection.id);
+    if nodes.is_empty() {
+        println!("{}(no documents)", if plain { "  " } else { "└── " });
+    }
+    print_nodes(&nodes, "", plain)?;
+
+    Ok(())
+}
+
+/// Prints `nodes` and their children like `tree` does, with `prefix` in front of each line to
+/// continue the lines of the levels above. `plain` shows each level by indenting it instead, for
+/// screen readers.
+fn print_nodes(
+    nodes: &[logic::DocumentNode],
+    prefix: &str,
+    plain: bool,
+) -> Result<(), StdoutError> {
+    for (index, node) in nodes.iter().enumerate() {
+        let last = index + 1 == nodes.len();
+        let (branch, continuation) = if plain {
+            ("  ", "  ")
+        } else if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        println!("{prefix}{branch}{} ({})", node.title, node.id);
+        print_nodes(&node.children, &format!("{prefix}{continuation}"), plain)?;
+    }
+
+    Ok(())
+}
+
+fn document_count(count: usize) -> String {
+    if count == 1 {
+        "1 document".to_string()
+    } else {
+        format!("{count} documents")
+    }
+}
+
+fn create(
+    client: &GetOutlineClient,
+    name: &str,
+    description: Option<&str>,
+    private: bool,
+    output: Output,
+) -> anyhow::Result<()> {
+    let collection = logic::create_collection(client, name, description, private)?;
+    if output.json {
+        return print_json(&collection);
+    }
+    println!("Created \"{}\" ({})", collection.name, collection.id);
+
+    Ok(())
+}
+
+fn update(
+    client: &GetOutlineClient,
+    update: &logic::CollectionUpdate,
+    output: Output,
+) -> anyhow::Result<()> {
+    let collection = logic::update_collection(client, update)?;
+    if output.json {
+        return print_json(&collection);
+    }
+    println!("Updated \"{}\" ({})", collection.name, collection.id);
+
+    Ok(())
+}
+
+fn delete(client: &GetOutlineClient, id: &str, force: bool, output: Output) -> anyhow::Result<()> {
+    let logic::CollectionStats {
+        collection,
+        document_count: count,
+    } = logic::collection_info(client, id)?;
+
+    if !force {
+        output.status(format_args!(
+            "This deletes \"{}\" and the {} in it.",
+            collection.name,
+            document_count(count)
+        ))?;
+        let answer = ask("Type the collection's name to confirm:")?;
+        if answer != collection.name {
+            if output.json {
+                return print_json(&json!({ "id": id, "deleted": false }));
+            }
+            println!("The name didn't match, nothing was deleted.");
+            return Ok(());
+        }
+    }
+
+    logic::delete_collection(client, id)?;
+    if output.json {
+        return print_json(&json!({ "id": id, "deleted": true, "documentCount": count }));
+    }
+    println!(
+        "Deleted \"{}\" ({}) and {}",
+        collection.name,
+        collection.id,
+        document_count(count)
+    );
+
+    Ok(())
+}
+
+fn add_user(
+    client: &GetOutlineClient,
+    id: &str,
+    user_id: &str,
+    permission: Permission,
+    output: Output,
+) -> anyhow::Result<()> {
+    let permission = permission.into();
+    memberships::add_collection_user(client, id, user_id, permission)?;
+    if output.json {
+        return print_json(&json!({ "id": id, "userId": user_id, "permission": permission }));
+    }
+    println!("Added {user_id} to collection {id}");
+
+    Ok(())
+}
+
+fn remove_user(
+    client: &GetOutlineClient,
+    id: &str,
+    user_id: &str,
+    output: Output,
+) -> anyhow::Result<()> {
+    memberships::remove_collection_user(client, id, user_id)?;
+    if output.json {
+        return print_json(&json!({ "id": id, "userId": user_id, "removed": true }));
+    }
+    println!("Removed {user_id} from collection {id}");
+
+    Ok(())
+}
+
+fn add_group(
+    client: &GetOutlineClient,
+    id: &str,
+    group_id: &str,
+    permission: Permission,
+    output: Output,
+) -> anyhow::Result<()> {
+    let permission = permission.into();
+    memberships::add_collection_group(client, id, group_id, permission)?;
+    if output.json {
+        return print_json(&json!({ "id": id, "groupId": group_id, "permission": permission }));
+    }
+    println!("Added group {group_id} to collection {id}");
+
+    Ok(())
+}
+
+fn remove_group(
+    client: &GetOutlineClient,
+    id: &str,
+    group_id: &str,
+    output: Output,
+) -> anyhow::Result<()> {
+    memberships::remove_collection_group(client, id, group_id)?;
+    if output.json {
+        return print_json(&json!({ "id": id, "groupId": group_id, "removed": true }));
+    }
+    println!("Removed group {group_id} from collection {id}");
+
+    Ok(())
+}
+
+/// Accepts colors written as `#rrggbb`, adding the `#` if it was left off.
+fn hex_color(value: &str) -> Result<String, String> {
+    let digits = value.strip_prefix('#').unwrap_or(value);
+    if digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(format!("#{}", digits.to_ascii_lowercase()))
+    } else {
+        Err(format!("{value} isn't a hex color like #4e5c6e"))
+    }
+}
+
+fn copy(client: &GetOutlineClient, src_id: &str, to: &str, output: Output) -> anyhow::Result<()> {
+    let copied = logic::copy_collection(client, client, src_id, to, |node| {
+        // A closed stdout stops the command once the copy is done, rather than halfway through
+        let _ = output.status(format_args!("  Copied \"{}\"", node.title));
+    })?;
+    if output.json {
+        return print_json(&copied);
+    }
+    println!(
+        "Copied {} documents into \"{}\" ({})",
+        copied.documents_copied, copied.collection.name, copied.collection.id
+    );
+
+    Ok(())
+}
@@ -0,0 +1,284 @@
+//! Tab completion for shells. The scripts printed by `completions` run the hidden `__complete`
+//! command with the words typed so far, which prints one candidate per line, followed by a tab and
+//! a description if there is one.
+//!
+//! Document and collection IDs are completed from a list of the workspace's documents and
+//! collections kept in the cache directory, which is fetched again once it's an hour old.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::{Arg, ArgAction, Args, Command, CommandFactory, ValueEnum};
+
+use super::{CLIArgs, Connector};
+use crate::logic::completion::{self as logic, Candidate, CompletionList};
+use crate::state;
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// The shell to print the script for.
+    shell: Shell,
+}
+
+#[derive(Args)]
+pub struct CompleteArgs {
+    /// The words on the command line after the program's name, up to and including the one being
+    /// completed.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    words: Vec<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Shell {
+    /// Add `source <(get_outline completions bash)` to `~/.bashrc`.
+    Bash,
+    /// Add `source <(get_outline completions zsh)` to `~/.zshrc`, after `compinit`.
+    Zsh,
+    /// Save it as `~/.config/fish/completions/get_outline.fish`.
+    Fish,
+}
+
+const BASH: &str = r#"_{bin}() {
+    local IFS=$'\n'
+    COMPREPLY=($({bin} __complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null | cut -f1))
+}
+complete -o default -F _{bin} {bin}
+"#;
+
+const ZSH: &str = r#"_{bin}() {
+    local -a candidates
+    local line
+    for line in ${(f)"$({bin} __complete -- "${(@)words[2,CURRENT]}" 2>/dev/null)"}; do
+        if [[ $line == *$'\t'* ]]; then
+            candidates+=("${${line%%$'\t'*}//:/\\:}:${line#*$'\t'}")
+        else
+            candidates+=("${line//:/\\:}")
+        fi
+    done
+    _describe '{bin}' candidates || _files
+}
+compdef _{bin} {bin}
+"#;
+
+const FISH: &str = r#"complete -c {bin} -a '({bin} __complete -- (commandline -opc)[2..-1] (commandline -ct) 2>/dev/null)'
+"#;
+
+/// Kinds of IDs which can be completed from the [CompletionList].
+#[derive(Clone, Copy)]
+enum IdKind {
+    Document,
+    Collection,
+}
+
+pub fn print_script(args: CompletionsArgs) -> anyhow::Result<()> {
+    let script = match args.shell {
+        Shell::Bash => BASH,
+        Shell::Zsh => ZSH,
+        Shell::Fish => FISH,
+    };
+    print!("{}", script.replace("{bin}", CLIArgs::command().get_name()));
+    Ok(())
+}
+
+/// Prints the candidates for the last of `args`'s words. Nothing is ever reported as an error,
+/// since the shell has nowhere to show it: a list which can't be fetched just means IDs aren't
+/// completed.
+pub fn complete(
+    args: CompleteArgs,
+    connector: &Connector,
+    profile: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut cli = CLIArgs::command();
+    cli.build();
+
+    let (typed, before) = match args.words.split_last() {
+        Some((typed, before)) => (typed.as_str(), before),
+        None => ("", &[][..]),
+    };
+    let profile = before
+        .windows(2)
+        .rev()
+        .find(|pair| pair[0] == "--profile")
+        .map(|pair| pair[1].as_str())
+        .or(profile);
+    let list = || completion_list(connector, profile);
+
+    for (candidate, description) in candidates(&cli, before, typed, list) {
+        match description {
+            Some(description) => println!("{candidate}\t{description}"),
+            None => println!("{candidate}"),
+        }
+    }
+    Ok(())
+}
+
+/// Works out where on the command line `typed` is from the words `before` it, and what it could
+/// be completed to, fetching the list of IDs with `list` only if an ID is expected.
+fn candidates(
+    cli: &Command,
+    before: &[String],
+    typed: &str,
+    list: impl FnOnce() -> CompletionList,
+) -> Vec<(String, Option<String>)> {
+    let mut command = cli;
+    let mut path = Vec::new();
+    let mut positionals = 0;
+    let mut value_of: Option<&Arg> = None;
+    for word in before {
+        if value_of.take().is_some() {
+            continue;
+        }
+        let option = match word.strip_prefix("--") {
+            Some(long) => command
+                .get_arguments()
+                .find(|arg| arg.get_long() == Some(long)),
+            None if word.len() > 1 && word.starts_with('-') => command
+                .get_arguments()
+                .find(|arg| arg.get_short().is_some_and(|short| word.ends_with(short))),
+            None => None,
+        };
+        if word.starts_with('-') {
+            value_of = option.filter(|arg| arg.get_action().takes_values());
+        } else if let Some(subcommand) = command.find_subcommand(word) {
+            command = subcommand;
+            path.push(subcommand.get_name());
+            positionals = 0;
+        } else {
+            positionals += 1;
+        }
+    }
+
+    let arg = match value_of {
+        Some(arg) => Some(arg),
+        None if typed.starts_with('-') => {
+            return command
+                .get_arguments()
+                .filter(|arg| !arg.is_hide_set())
+                .filter_map(|arg| Some((format!("--{}", arg.get_long()?), help(arg))))
+                .filter(|(flag, _)| flag.starts_with(typed))
+                .collect();
+        }
+        None if command.has_subcommands() => {
+            return command
+                .get_subcommands()
+                .filter(|subcommand| !subcommand.is_hide_set())
+                .map(|subcommand| {
+                    let about = subcommand
+                        .get_about()
+                        .map(|about| first_line(&about.to_string()));
+                    (subcommand.get_name().to_string(), about)
+                })
+                .filter(|(name, _)| name.starts_with(typed))
+                .collect();
+        }
+        None => {
+            // A positional which takes a list, like `documents cat`'s IDs, takes every word left
+            let positional_args: Vec<_> = command.get_positionals().collect();
+            positional_args
+                .get(positionals)
+                .or_else(|| {
+                    positional_args
+                        .last()
+                        .filter(|arg| matches!(arg.get_action(), ArgAction::Append))
+                })
+                .copied()
+        }
+    };
+    let Some(arg) = arg else {
+        return Vec::new();
+    };
+
+    let possible_values: Vec<_> = arg
+        .get_possible_values()
+        .into_iter()
+        .filter(|value| !value.is_hide_set() && value.get_name().starts_with(typed))
+        .map(|value| {
+            let help = value.get_help().map(|help| first_line(&help.to_string()));
+            (value.get_name().to_string(), help)
+        })
+        .collect();
+    if !possible_values.is_empty() {
+        return possible_values;
+    }
+
+    let candidates = match id_kind(arg, &path) {
+        Some(IdKind::Document) => list().documents,
+        Some(IdKind::Collection) => list().collections,
+        None => return Vec::new(),
+    };
+    candidates
+        .into_iter()
+        .filter(|candidate| candidate.matches(typed))
+        .map(|Candidate { id, title }| (id, Some(title)))
+        .collect()
+}
+
+/// Which kind of ID `arg` of the command at `path` takes, if it's one which can be completed.
+fn id_kind(arg: &Arg, path: &[&str]) -> Option<IdKind> {
+    let value_name = arg
+        .get_value_names()
+        .and_then(|names| names.first())
+        .map(|name| name.as_str());
+    match (arg.get_id().as_str(), value_name) {
+        ("collection", _) | (_, Some("COLLECTION_ID")) => Some(IdKind::Collection),
+        ("id", _) if path.first() == Some(&"collections") => Some(IdKind::Collection),
+        ("doc_id" | "doc_ids" | "document" | "doc", _) => Some(IdKind::Document),
+        _ => None,
+    }
+}
+
+/// The first line of `arg`'s help, to show next to it.
+fn help(arg: &Arg) -> Option<String> {
+    arg.get_help().map(|help| first_line(&help.to_string()))
+}
+
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().to_string()
+}
+
+/// Where the list of IDs to complete is kept for `profile`.
+pub(super) fn list_path(profile: Option<&str>) -> Option<PathBuf> {
+    Some(state::cache_dir()?.join(state::file_name(profile, "completion")))
+}
+
+/// The list of IDs to complete for `profile`, fetched again first if it's too old. If it can't be
+/// fetched, the old one is used, or an empty one if there isn't one.
+fn completion_list(connector: &Connector, profile: Option<&str>) -> CompletionList {
+    let path = list_path(profile);
+    let cached: CompletionList = path
+        .as_ref()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+        .unwrap_or_default();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs());
+    if !cached.is_stale(now) {
+        return cached;
+    }
+
+    let Some(fetched) = connector
+        .connect(profile)
+        .ok()
+        .and_then(|client| logic::fetch_completion_list(&client, now).ok())
+    else {
+        return cached;
+    };
+    if let Some(path) = path {
+        // The list is fetched again next time if it can't be saved
+        let _ = save_list(&path, &fetched);
+    }
+    fetched
+}
+
+fn save_list(path: &Path, list: &CompletionList) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Write to a temporary file first so an interrupted run can't leave a half-written list
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, serde_json::to_vec(list)?)?;
+    fs::rename(temp, path)
+}
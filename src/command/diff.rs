@@ -0,0 +1,65 @@
+//! Renders line-based diffs of markdown for the terminal.
+
+use std::fmt::Write;
+use std::io::IsTerminal;
+
+use similar::{ChangeTag, TextDiff};
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// How many unchanged lines to show around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Whether diffs written to stdout should be colored, following the `NO_COLOR` convention. They
+/// never are with `--plain`.
+pub fn use_color(plain: bool) -> bool {
+    !plain && std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Renders the changes from `old` to `new` as a unified diff, like `diff -u` or `git diff`.
+pub fn render_unified(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    color: bool,
+) -> String {
+    let diff = TextDiff::from_lines(old, new);
+    let paint = |style: &str, text: &str| {
+        if color {
+            format!("{style}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    };
+
+    let mut out = String::new();
+    if diff.ratio() == 1.0 {
+        return out;
+    }
+
+    writeln!(out, "{}", paint(BOLD, &format!("--- {old_label}"))).unwrap();
+    writeln!(out, "{}", paint(BOLD, &format!("+++ {new_label}"))).unwrap();
+    for hunk in diff
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .iter_hunks()
+    {
+        writeln!(out, "{}", paint(CYAN, &hunk.header().to_string())).unwrap();
+        for change in hunk.iter_changes() {
+            let line = change.value().trim_end_matches('\n');
+            let rendered = match change.tag() {
+                ChangeTag::Equal => format!(" {line}"),
+                ChangeTag::Delete => paint(RED, &format!("-{line}")),
+                ChangeTag::Insert => paint(GREEN, &format!("+{line}")),
+            };
+            writeln!(out, "{rendered}").unwrap();
+        }
+    }
+
+    out
+}
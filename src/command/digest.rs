@@ -0,0 +1,63 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+
+use super::events::utc_timestamp;
+use super::output::{print_json, Output};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::{digest as logic, documents, time};
+
+#[derive(Args)]
+pub struct DigestArgs {
+    /// Covers what changed from this long ago until now, like `24h`, `7d`, or `2w`, or from a UTC
+    /// date or time like `2024-05-01` or `2024-05-01T09:00:00Z`.
+    #[arg(long, value_parser = since, default_value = "7d")]
+    since: String,
+    /// Only covers the documents in this collection, instead of the whole workspace.
+    #[arg(long, value_name = "COLLECTION_ID")]
+    collection: Option<String>,
+    /// Publishes the digest as a new document in this collection instead of printing it.
+    #[arg(long, value_name = "COLLECTION_ID")]
+    publish: Option<String>,
+    /// The published document's title. Defaults to "What changed since" and the date.
+    #[arg(long, requires = "publish")]
+    title: Option<String>,
+}
+
+pub fn exec(args: DigestArgs, client: &GetOutlineClient, output: Output) -> anyhow::Result<()> {
+    let digest = logic::build_digest(client, args.collection.as_deref(), &args.since)?;
+    let markdown = logic::digest_markdown(&digest, client.base_url());
+
+    let Some(collection) = args.publish else {
+        if output.json {
+            return print_json(&digest);
+        }
+        print!("{markdown}");
+        return Ok(());
+    };
+
+    let title = args.title.unwrap_or_else(|| logic::digest_title(&digest));
+    let published = documents::create_document(client, &collection, &markdown, Some(&title), "")?;
+    if output.json {
+        return print_json(&published);
+    }
+    println!("Published \"{}\" ({})", published.title, published.id);
+
+    Ok(())
+}
+
+/// Accepts a length of time before now, like `7d`, or a UTC date or time, and gives the UTC
+/// timestamp it starts at.
+fn since(value: &str) -> Result<String, String> {
+    if let Ok(timestamp) = utc_timestamp(value) {
+        return Ok(timestamp);
+    }
+
+    let ago = super::duration(value).map_err(|_| {
+        format!("{value} isn't a length of time like 7d or a UTC date like 2024-05-01")
+    })?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs());
+    Ok(time::timestamp_before(now, ago))
+}
@@ -0,0 +1,1606 @@
+use std::convert::Infallible;
+use std::fs::{self, OpenOptions};
+#[cfg(unix)]
+use std::io::{self, IsTerminal};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use clap::{ArgGroup, Args, Subcommand, ValueEnum};
+use regex::RegexBuilder;
+use serde::Serialize;
+use serde_json::json;
+
+use super::cache::Cache;
+use super::output::table::{Column, Table};
+use super::output::{print_json, Output};
+#[cfg(unix)]
+use super::picker;
+use super::{bookmarks, confirm, diff, errors, replace, ListingArgs, PaginationArgs};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::documents as logic;
+use crate::logic::recent::RecentDocuments;
+use crate::logic::{
+    attachments, cache, comments, find, memberships, parsers, pins, revisions, shares, stars,
+    suggestions, views,
+};
+use crate::state;
+
+#[derive(Subcommand)]
+pub enum DocumentsCommand {
+    /// Downloads a document to a local file.
+    Save(SaveArgs),
+    /// Prints the markdown of one or more documents, one after another.
+    Cat(CatArgs),
+    /// Searches document titles and content, printing matches with a snippet of context.
+    Search {
+        /// The text to search for.
+        query: String,
+        /// Shows every matching document instead of a single page.
+        #[arg(long, conflicts_with_all = ["page", "results_per_page"])]
+        all: bool,
+        /// A directory of documents saved with `documents save`. Matches which have a copy there
+        /// also get a link to the section in the copy.
+        #[arg(long, value_name = "DIR")]
+        mirror: Option<PathBuf>,
+        #[command(flatten)]
+        pagination: PaginationArgs,
+    },
+    /// Prints the lines of one document which match a regular expression, with their line numbers.
+    FindIn {
+        /// The ID of the document to search.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The regular expression to look for in each line.
+        pattern: String,
+        /// Matches regardless of case.
+        #[arg(long, short)]
+        ignore_case: bool,
+        /// How many lines to print after each match.
+        #[arg(long, short = 'A', value_name = "NUM")]
+        after_context: Option<usize>,
+        /// How many lines to print before each match.
+        #[arg(long, short = 'B', value_name = "NUM")]
+        before_context: Option<usize>,
+        /// How many lines to print before and after each match, unless `-A` or `-B` say otherwise.
+        #[arg(long, short = 'C', value_name = "NUM", default_value_t = 0)]
+        context: usize,
+    },
+    /// Publishes a local markdown file as a new document.
+    Create {
+        /// The markdown file to publish (`-` reads it from stdin).
+        #[arg(long)]
+        file: PathBuf,
+        /// The ID of the collection to create the document in.
+        #[arg(long)]
+        collection: String,
+        /// The document's title. Defaults to the file's leading `# Heading`, or its file name
+        /// (`Untitled` when it's read from stdin).
+        #[arg(long)]
+        title: Option<String>,
+    },
+    /// Pushes the contents of a local markdown file to an existing document.
+    Update {
+        /// The ID of the document to update.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The markdown file containing the new content (`-` reads it from stdin).
+        #[arg(long)]
+        file: PathBuf,
+        /// Adds the file's content to the end of the document instead of replacing it.
+        #[arg(long)]
+        append: bool,
+        /// A new title for the document.
+        #[arg(long)]
+        title: Option<String>,
+        /// Refuses the update if the document has been saved since this revision, as printed by
+        /// `documents save`, so edits made in the meantime aren't overwritten.
+        #[arg(long)]
+        expected_revision: Option<u64>,
+    },
+    /// Deletes a document, moving it to the trash unless `--permanent` is given.
+    Delete {
+        /// The ID of the document to delete.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// Destroys the document instead of moving it to the trash. This can't be undone.
+        #[arg(long)]
+        permanent: bool,
+        /// Skips the confirmation prompt.
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Moves a published document back to your drafts.
+    Unpublish {
+        /// The ID of the document to unpublish.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+    },
+    /// Rolls a document back to one of its previous revisions.
+    Restore {
+        /// The ID of the document to restore.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The ID of the revision to restore it to.
+        #[arg(long)]
+        revision: String,
+    },
+    /// Lists a document's revision history, newest first.
+    Revisions {
+        /// The ID of the document to list revisions for.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        #[command(flatten)]
+        pagination: PaginationArgs,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Shows what the readers of a document's share link are missing: the changes from the
+    /// document as it's seen through the link to how it is now, to check before sharing it again.
+    Diff {
+        /// The ID of the document to compare.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The share link to compare against, or its ID. A link shared for a document this one is
+        /// nested under works too.
+        #[arg(long, value_name = "SHARE_URL")]
+        against_share: String,
+    },
+    /// Shows what changed in a document between two of its revisions.
+    RevisionDiff {
+        /// The ID of the document the revisions belong to.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The ID of the older revision.
+        #[arg(long)]
+        from: String,
+        /// The ID of the newer revision.
+        #[arg(long)]
+        to: String,
+    },
+    /// Applies a unified diff to a document's current content and pushes the result.
+    Patch {
+        /// The ID of the document to patch.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The unified diff to apply, e.g. from `diff -u` or `git diff`.
+        #[arg(long)]
+        patch: PathBuf,
+    },
+    /// Finds and replaces a regular expression across many documents, previewing the changes first.
+    Replace(replace::ReplaceArgs),
+    /// Stars a document so it shows up in `documents starred`.
+    Star {
+        /// The ID of the document to star.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+    },
+    /// Removes the star from a document.
+    Unstar {
+        /// The ID of the document to unstar.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+    },
+    /// Lists the documents you've starred.
+    Starred,
+    /// Pins a document to the top of its collection's page, or of the home page, for everyone.
+    Pin {
+        /// The ID of the document to pin.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The collection to pin the document in. Without it, the document is pinned to the
+        /// workspace's home page.
+        #[arg(long)]
+        collection: Option<String>,
+    },
+    /// Unpins a document from a collection, or from the home page.
+    Unpin {
+        /// The ID of the document to unpin.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The collection the document is pinned in. Without it, the document is unpinned from
+        /// the home page.
+        #[arg(long)]
+        collection: Option<String>,
+    },
+    /// Lists the documents pinned in a collection, or to the home page.
+    Pinned {
+        /// The collection to list pins for. Without it, the home page's pins are listed.
+        #[arg(long)]
+        collection: Option<String>,
+    },
+    /// Opens a document in the web browser.
+    Open {
+        /// The ID of the document to open.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+    },
+    /// Lists the documents you've saved or opened recently, which can be referred to as `@1`,
+    /// `@2`, and so on (or `@last` for the latest).
+    Recent,
+    /// Lists the users a document has been shared with and what they're allowed to do.
+    Users {
+        /// The ID of the document to check.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+    },
+    /// Lists who has viewed a document, how often, and when they last did, most recent first.
+    Views {
+        /// The ID of the document to check.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Gives a user access to a document, replacing any access they were given before.
+    ShareWith {
+        /// The ID of the document to share.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The ID of the user to share it with.
+        #[arg(long)]
+        user: String,
+        /// What the user is allowed to do with the document.
+        #[arg(long, value_enum)]
+        permission: Permission,
+    },
+    /// Takes away the access a user was given to a document. Access through the document's
+    /// collection isn't affected.
+    RevokeAccess {
+        /// The ID of the document.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The ID of the user to remove.
+        #[arg(long)]
+        user: String,
+    },
+    /// Prints a read-only share link for a document, creating one if it hasn't been shared yet.
+    PublishLink {
+        /// The ID of the document to share.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// Lets anyone with the link read the document. Without this, it only works for members
+        /// of the workspace.
+        #[arg(long)]
+        published: bool,
+    },
+    /// Leaves a comment on a document, such as a review note from a CI pipeline.
+    Comment {
+        /// The ID of the document to comment on.
+        #[arg(value_parser = document_id)]
+        doc_id: String,
+        /// The comment, in markdown. Read from stdin if it isn't given.
+        #[arg(long, short)]
+        message: Option<String>,
+    },
+}
+
+#[derive(Args)]
+pub struct SaveArgs {
+    /// The ID of the document to save. Left out on a terminal, the document is picked from a
+    /// list of them all by typing part of its title.
+    #[arg(value_parser = document_id)]
+    doc_id: Option<String>,
+    /// Where to write the document. Defaults to `<title>.<format>` in the current directory.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+    /// A shell command to feed the document to on its standard input, instead of writing a file,
+    /// such as `pandoc -f markdown -o notes.docx`.
+    #[arg(long, value_name = "COMMAND", conflicts_with_all = ["output", "overwrite"])]
+    pipe: Option<String>,
+    /// The file format to save the document in. Anything other than markdown is rendered by the
+    /// server through `documents.export`.
+    #[arg(long, value_enum, default_value_t = Format::Markdown)]
+    format: Format,
+    /// Which API endpoint to read markdown from. Only applies to `--format markdown`.
+    #[arg(long, value_enum, default_value_t = Source::Info)]
+    source: Source,
+    /// How to break the lines of paragraphs. Code blocks, tables, and other structure are left
+    /// alone. Only applies to `--format markdown`.
+    #[arg(long, value_enum, default_value_t = Wrap::Preserve)]
+    wrap: Wrap,
+    /// Replaces the output file if it already exists.
+    #[arg(long)]
+    overwrite: bool,
+    /// Downloads the files the document links to into an `assets` directory next to it, and
+    /// points the links at the downloaded copies. Only applies to `--format markdown`.
+    #[arg(long, conflicts_with = "pipe")]
+    with_attachments: bool,
+}
+
+#[derive(Args)]
+#[command(group(
+    ArgGroup::new("documents")
+        .required(true)
+        .multiple(true)
+        .args(["doc_ids", "from_file"])
+))]
+pub struct CatArgs {
+    /// The IDs of the documents to print, in order.
+    #[arg(value_parser = document_id)]
+    doc_ids: Vec<String>,
+    /// Reads more document IDs from a file, one per line (`-` reads them from stdin). Blank lines
+    /// and lines starting with `#` are skipped.
+    #[arg(long)]
+    from_file: Option<PathBuf>,
+    /// The line printed between documents, surrounded by blank lines.
+    #[arg(long, default_value = "---")]
+    separator: String,
+    /// A line printed before each document, where `{title}`, `{id}`, and `{url}` are replaced by
+    /// the document's title, ID, and link, e.g. `# [{title}]({url})`.
+    #[arg(long)]
+    header: Option<String>,
+    /// Which endpoint to read the markdown from.
+    #[arg(long, value_enum, default_value_t = Source::Info)]
+    source: Source,
+    /// Records a view of each document, as opening it in the web app would, so it counts
+    /// towards `documents views` and shows as read.
+    #[arg(long)]
+    mark_viewed: bool,
+    /// How long a document's cached copy is printed instead of fetching it again, e.g. `30s`,
+    /// `10m`, or `1h`.
+    #[arg(long, value_parser = super::duration, default_value = "5m", value_name = "DURATION")]
+    cache_ttl: Duration,
+    /// Always fetches the documents from getOutline, without reading or updating the cache.
+    #[arg(long, conflicts_with = "cache_ttl")]
+    no_cache: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Markdown,
+    Html,
+    Pdf,
+}
+
+impl Format {
+    /// The format the server should render the document into, or `None` for plain markdown.
+    fn file_format(self) -> Option<logic::FileFormat> {
+        match self {
+            Format::Markdown => None,
+            Format::Html => Some(logic::FileFormat::Html),
+            Format::Pdf => Some(logic::FileFormat::Pdf),
+        }
+    }
+}
+
+/// The endpoint to read a document's markdown from.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Source {
+    /// The document's stored text, from `documents.info`.
+    Info,
+    /// Markdown rendered by the server, from `documents.export`. Embeds and attachments can come
+    /// out differently than with `info`.
+    Export,
+}
+
+impl From<Source> for logic::ContentSource {
+    fn from(source: Source) -> Self {
+        match source {
+            Source::Info => logic::ContentSource::Info,
+            Source::Export => logic::ContentSource::Export,
+        }
+    }
+}
+
+/// How to lay out the lines of a saved document's paragraphs.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Wrap {
+    /// One line per paragraph, which renderers and word processors handle best.
+    None,
+    /// Lines of at most 80 characters, which make for readable diffs.
+    #[value(name = "80")]
+    Eighty,
+    /// The line breaks the document already has.
+    Preserve,
+}
+
+impl From<Wrap> for parsers::Wrap {
+    fn from(wrap: Wrap) -> Self {
+        match wrap {
+            Wrap::None => parsers::Wrap::Unwrap,
+            Wrap::Eighty => parsers::Wrap::Width(80),
+            Wrap::Preserve => parsers::Wrap::Preserve,
+        }
+    }
+}
+
+/// An access level a document can be shared with.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Permission {
+    /// View and comment on the document.
+    Read,
+    /// View and edit the document.
+    #[value(name = "read_write", alias = "read-write")]
+    ReadWrite,
+}
+
+impl From<Permission> for memberships::Permission {
+    fn from(permission: Permission) -> Self {
+        match permission {
+            Permission::Read => memberships::Permission::Read,
+            Permission::ReadWrite => memberships::Permission::ReadWrite,
+        }
+    }
+}
+
+/// Accepts a link to a document, as copied from the browser, wherever a document ID is expected.
+pub(super) fn document_id(value: &str) -> Result<String, Infallible> {
+    Ok(parsers::document_id_from_url(value).to_string())
+}
+
+impl DocumentsCommand {
+    /// The document the command works on, if it works on a single one.
+    fn doc_id_mut(&mut self) -> Option<&mut String> {
+        match self {
+            DocumentsCommand::Save(args) => args.doc_id.as_mut(),
+            DocumentsCommand::Update { doc_id, .. }
+            | DocumentsCommand::Delete { doc_id, .. }
+            | DocumentsCommand::Unpublish { doc_id }
+            | DocumentsCommand::Restore { doc_id, .. }
+            | DocumentsCommand::Revisions { doc_id, .. }
+            | DocumentsCommand::Diff { doc_id, .. }
+            | DocumentsCommand::RevisionDiff { doc_id, .. }
+            | DocumentsCommand::Patch { doc_id, .. }
+            | DocumentsCommand::FindIn { doc_id, .. }
+            | DocumentsCommand::Star { doc_id }
+            | DocumentsCommand::Unstar { doc_id }
+            | DocumentsCommand::Pin { doc_id, .. }
+            | DocumentsCommand::Unpin { doc_id, .. }
+            | DocumentsCommand::Open { doc_id }
+            | DocumentsCommand::Users { doc_id }
+            | DocumentsCommand::Views { doc_id, .. }
+            | DocumentsCommand::ShareWith { doc_id, .. }
+            | DocumentsCommand::RevokeAccess { doc_id, .. }
+            | DocumentsCommand::PublishLink { doc_id, .. }
+            | DocumentsCommand::Comment { doc_id, .. } => Some(doc_id),
+            DocumentsCommand::Cat(_)
+            | DocumentsCommand::Search { .. }
+            | DocumentsCommand::Create { .. }
+            | DocumentsCommand::Replace(_)
+            | DocumentsCommand::Starred
+            | DocumentsCommand::Pinned { .. }
+            | DocumentsCommand::Recent => None,
+        }
+    }
+}
+
+/// Runs a documents command, suggesting similar documents if the one asked for doesn't exist.
+///
+/// `@` shortcuts for recently used documents are resolved using `profile`'s history.
+pub fn exec(
+    mut cmd: DocumentsCommand,
+    client: &GetOutlineClient,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let recent = Recent::load(profile);
+    let doc_id = match cmd.doc_id_mut() {
+        Some(doc_id) => {
+            *doc_id = resolve_reference(&recent, doc_id)?;
+            Some(doc_id.clone())
+        }
+        None => None,
+    };
+
+    run(cmd, client, recent, profile, output).map_err(|err| match doc_id {
+        Some(doc_id) if errors::classify(&err) == errors::ErrorCode::NotFound => {
+            with_suggestions(client, &doc_id, err)
+        }
+        _ => err,
+    })
+}
+
+/// Turns what was given in place of a document ID (an `@` shortcut for a recent document, a
+/// bookmark name, or just the ID) into the ID.
+pub(super) fn resolve_reference(recent: &Recent, reference: &str) -> anyhow::Result<String> {
+    if reference.starts_with('@') {
+        return Ok(recent.documents.resolve(reference)?.to_string());
+    }
+
+    Ok(bookmarks::lookup(reference)?.unwrap_or_else(|| reference.to_string()))
+}
+
+/// The recently used documents for one profile, and where they're kept.
+pub(super) struct Recent {
+    file_name: String,
+    documents: RecentDocuments,
+}
+
+impl Recent {
+    pub(super) fn load(profile: Option<&str>) -> Self {
+        let file_name = state::file_name(profile, "recent");
+        Self {
+            documents: state::load(&file_name),
+            file_name,
+        }
+    }
+
+    /// Puts document `id` at the top of the list. Failing to save the list only warrants a
+    /// warning, since the command itself worked.
+    pub(super) fn remember(mut self, id: &str, title: &str) {
+        self.documents.record(id, title);
+        if let Err(err) = state::save(&self.file_name, &self.documents) {
+            tracing::warn!("could not remember {id} as a recent document: {err}");
+        }
+    }
+}
+
+/// Adds "did you mean" guesses for `doc_id` to `err`, leaving it alone if there aren't any (or
+/// they can't be fetched).
+fn with_suggestions(client: &GetOutlineClient, doc_id: &str, err: anyhow::Error) -> anyhow::Error {
+    match suggestions::suggest_documents(client, doc_id) {
+        Ok(documents) if !documents.is_empty() => errors::DidYouMean {
+            error: err,
+            suggestions: documents
+                .iter()
+                .map(|document| format!("{} ({})", document.title, document.id))
+                .collect(),
+        }
+        .into(),
+        _ => err,
+    }
+}
+
+fn run(
+    cmd: DocumentsCommand,
+    client: &GetOutlineClient,
+    recent: Recent,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    match cmd {
+        DocumentsCommand::Save(args) => save(client, args, recent, output),
+        DocumentsCommand::Cat(args) => cat(client, args, &recent, profile, output),
+        DocumentsCommand::Search {
+            query,
+            all,
+            mirror,
+            pagination,
+        } => search(client, &query, all, mirror.as_deref(), pagination, output),
+        DocumentsCommand::FindIn {
+            doc_id,
+            pattern,
+            ignore_case,
+            after_context,
+            before_context,
+            context,
+        } => find_in(
+            client,
+            &doc_id,
+            &pattern,
+            ignore_case,
+            before_context.unwrap_or(context),
+            after_context.unwrap_or(context),
+            output,
+        ),
+        DocumentsCommand::Create {
+            file,
+            collection,
+            title,
+        } => create(client, &file, &collection, title.as_deref(), output),
+        DocumentsCommand::Update {
+            doc_id,
+            file,
+            append,
+            title,
+            expected_revision,
+        } => update(
+            client,
+            &doc_id,
+            &file,
+            append,
+            title.as_deref(),
+            expected_revision,
+            output,
+        ),
+        DocumentsCommand::Patch { doc_id, patch } => {
+            patch_document(client, &doc_id, &patch, output)
+        }
+        DocumentsCommand::Replace(args) => replace::exec(args, client, profile, output),
+        DocumentsCommand::Star { doc_id } => star(client, &doc_id, output),
+        DocumentsCommand::Unstar { doc_id } => unstar(client, &doc_id, output),
+        DocumentsCommand::Starred => starred(client, output),
+        DocumentsCommand::Pin { doc_id, collection } => {
+            pin(client, &doc_id, collection.as_deref(), output)
+        }
+        DocumentsCommand::Unpin { doc_id, collection } => {
+            unpin(client, &doc_id, collection.as_deref(), output)
+        }
+        DocumentsCommand::Pinned { collection } => pinned(client, collection.as_deref(), output),
+        DocumentsCommand::Open { doc_id } => open(client, &doc_id, recent, output),
+        DocumentsCommand::Recent => list_recent(&recent, output),
+        DocumentsCommand::Users { doc_id } => users(client, &doc_id, output),
+        DocumentsCommand::Views { doc_id, listing } => views(client, &doc_id, listing, output),
+        DocumentsCommand::ShareWith {
+            doc_id,
+            user,
+            permission,
+        } => share_with(client, &doc_id, &user, permission, output),
+        DocumentsCommand::RevokeAccess { doc_id, user } => {
+            revoke_access(client, &doc_id, &user, output)
+        }
+        DocumentsCommand::PublishLink { doc_id, published } => {
+            publish_link(client, &doc_id, published, output)
+        }
+        DocumentsCommand::Comment { doc_id, message } => comment(client, &doc_id, message, output),
+        DocumentsCommand::Delete {
+            doc_id,
+            permanent,
+            yes,
+        } => delete(client, &doc_id, permanent, yes, output),
+        DocumentsCommand::Unpublish { doc_id } => unpublish(client, &doc_id, output),
+        DocumentsCommand::Restore { doc_id, revision } => {
+            restore(client, &doc_id, &revision, output)
+        }
+        DocumentsCommand::Revisions {
+            doc_id,
+            pagination,
+            listing,
+        } => list_revisions(client, &doc_id, pagination, listing, output),
+        DocumentsCommand::Diff {
+            doc_id,
+            against_share,
+        } => diff_against_share(client, &doc_id, &against_share, output),
+        DocumentsCommand::RevisionDiff { doc_id, from, to } => {
+            revision_diff(client, &doc_id, &from, &to, output)
+        }
+    }
+}
+
+/// Lists every document to pick one from by typing part of its title, for commands run without a
+/// document ID. Returns `None` if nothing was picked.
+fn pick_document(
+    client: &GetOutlineClient,
+    prompt: &str,
+) -> anyhow::Result<Option<logic::DocumentSummary>> {
+    #[cfg(unix)]
+    if io::stdin().is_terminal() && io::stderr().is_terminal() {
+        let mut documents = logic::all_documents(client)?;
+        let titles: Vec<_> = documents
+            .iter()
+            .map(|document| document.title.clone())
+            .collect();
+        let picked = picker::pick(prompt, &titles)?;
+        return Ok(picked.map(|index| documents.swap_remove(index)));
+    }
+
+    #[cfg(not(unix))]
+    let _ = (client, prompt);
+    Err(errors::InvalidArguments {
+        message: "a document ID is needed when the command isn't run on a terminal".to_string(),
+    }
+    .into())
+}
+
+/// The directory next to a saved document which `--with-attachments` downloads files into.
+const ASSETS_DIR: &str = "assets";
+
+fn save(
+    client: &GetOutlineClient,
+    args: SaveArgs,
+    recent: Recent,
+    output: Output,
+) -> anyhow::Result<()> {
+    let doc_id = match &args.doc_id {
+        Some(doc_id) => doc_id.clone(),
+        None => match pick_document(client, "Save which document? ")? {
+            Some(document) => document.id,
+            None => {
+                eprintln!("Aborted, nothing was saved.");
+                return Ok(());
+            }
+        },
+    };
+    let (title, content, extension, revision) = match args.format.file_format() {
+        None => {
+            let document = logic::fetch_document(client, &doc_id, args.source.into())?;
+            let revision = document.revision;
+            let text = parsers::rewrap(&document.text, args.wrap.into());
+            (document.title, text.into_bytes(), "md", revision)
+        }
+        Some(format) => {
+            let exported = logic::export_file(client, &doc_id, format)?;
+            (exported.title, exported.content, format.extension(), None)
+        }
+    };
+
+    if let Some(command) = &args.pipe {
+        pipe_to(command, &content)?;
+        eprintln!("Piped \"{title}\" into `{command}`");
+        recent.remember(&doc_id, &title);
+        if output.json {
+            return print_json(&json!({ "title": title, "command": command }));
+        }
+        return Ok(());
+    }
+
+    let path = args.output.unwrap_or_else(|| {
+        PathBuf::from(format!("{}.{extension}", parsers::file_name_for(&title)))
+    });
+    let (content, files) = if args.with_attachments && args.format.file_format().is_none() {
+        let local = attachments::localize_attachments(
+            client,
+            &String::from_utf8_lossy(&content),
+            ASSETS_DIR,
+        )?;
+        (local.markdown.into_bytes(), local.files)
+    } else {
+        (content, Vec::new())
+    };
+
+    write_new_file(&path, &content, args.overwrite)?;
+    match revision {
+        Some(revision) => output.status(format_args!(
+            "Saved \"{title}\" (revision {revision}) to {}",
+            path.display()
+        )),
+        None => output.status(format_args!("Saved \"{title}\" to {}", path.display())),
+    }?;
+    recent.remember(&doc_id, &title);
+
+    let assets = path.parent().unwrap_or(Path::new("")).join(ASSETS_DIR);
+    if !files.is_empty() {
+        fs::create_dir_all(&assets)
+            .with_context(|| format!("could not create {}", assets.display()))?;
+        for (name, content) in &files {
+            let file = assets.join(name);
+            // Attachments never change, so one saved alongside another document can be kept
+            if !args.overwrite && file.exists() {
+                continue;
+            }
+            write_new_file(&file, content, true)?;
+        }
+        output.status(format_args!(
+            "Saved {} attachments to {}",
+            files.len(),
+            assets.display()
+        ))?;
+    }
+
+    if output.json {
+        return print_json(&json!({
+            "path": path,
+            "title": title,
+            "revision": revision,
+            "attachments": files.len(),
+            "attachmentsDirectory": (!files.is_empty()).then_some(&assets),
+        }));
+    }
+
+    Ok(())
+}
+
+/// A document printed by `cat --json`, which unlike [logic::Document] says which one it is.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CatDocument {
+    id: String,
+    #[serde(flatten)]
+    document: logic::Document,
+    web_url: String,
+}
+
+fn cat(
+    client: &GetOutlineClient,
+    args: CatArgs,
+    recent: &Recent,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let mut references = args.doc_ids;
+    if let Some(file) = &args.from_file {
+        references.extend(read_id_list(file)?);
+    }
+    let cache = Cache::open(profile).filter(|_| !args.no_cache);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs());
+
+    let mut documents = Vec::new();
+    for (index, reference) in references.iter().enumerate() {
+        let doc_id = resolve_reference(recent, parsers::document_id_from_url(reference))?;
+        let document = match &cache {
+            Some(cache) => cache::fetch_document_cached(
+                client,
+                cache,
+                &doc_id,
+                args.source.into(),
+                args.cache_ttl,
+                now,
+            ),
+            None => logic::fetch_document(client, &doc_id, args.source.into()),
+        }
+        .with_context(|| format!("could not read document {doc_id}"))?;
+        if args.mark_viewed {
+            views::mark_viewed(client, &doc_id)
+                .with_context(|| format!("could not mark document {doc_id} as viewed"))?;
+        }
+
+        if output.json {
+            let web_url = web_url(client, &logic::web_path(document.url.as_deref(), &doc_id));
+            documents.push(CatDocument {
+                id: doc_id,
+                document,
+                web_url,
+            });
+            continue;
+        }
+        if index > 0 {
+            println!("\n{}\n", args.separator);
+        }
+        if let Some(header) = &args.header {
+            let header = header
+                .replace("{title}", &document.title)
+                .replace("{id}", &doc_id)
+                .replace(
+                    "{url}",
+                    &web_url(client, &logic::web_path(document.url.as_deref(), &doc_id)),
+                );
+            println!("{header}\n");
+        }
+        print!("{}", document.text);
+        if !document.text.ends_with('\n') {
+            println!();
+        }
+    }
+
+    if let Some(cache) = &cache {
+        cache.keep_within_limits(profile);
+    }
+    if output.json {
+        return print_json(&documents);
+    }
+
+    Ok(())
+}
+
+/// Reads a list of document IDs (or anything else a document argument accepts) from `file`, or
+/// from stdin if it's `-`.
+fn read_id_list(file: &Path) -> anyhow::Result<Vec<String>> {
+    let text = if file == Path::new("-") {
+        std::io::read_to_string(std::io::stdin()).context("could not read IDs from stdin")?
+    } else {
+        read_text_file(file)?
+    };
+
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+fn open(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    recent: Recent,
+    output: Output,
+) -> anyhow::Result<()> {
+    let document = logic::fetch_document(client, doc_id, logic::ContentSource::Info)?;
+    let url = web_url(client, &logic::web_path(document.url.as_deref(), doc_id));
+
+    output.status(format_args!("Opening \"{}\" at {url}", document.title))?;
+    open_in_browser(&url).context("could not start a web browser")?;
+    recent.remember(doc_id, &document.title);
+
+    if output.json {
+        return print_json(&json!({ "id": doc_id, "title": document.title, "webUrl": url }));
+    }
+
+    Ok(())
+}
+
+/// The full link to `path` (like a document's [logic::web_path]) in the workspace `client` talks
+/// to.
+pub(super) fn web_url(client: &GetOutlineClient, path: &str) -> String {
+    format!("{}{path}", client.base_url())
+}
+
+/// Opens `url` with the browser named by `$BROWSER`, or the platform's default.
+pub(super) fn open_in_browser(url: &str) -> std::io::Result<()> {
+    let mut command = match std::env::var_os("BROWSER") {
+        Some(browser) => process::Command::new(browser),
+        None if cfg!(target_os = "macos") => process::Command::new("open"),
+        None if cfg!(windows) => {
+            let mut command = process::Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        None => process::Command::new("xdg-open"),
+    };
+
+    let status = command.arg(url).status()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!(
+            "the browser exited with {status}"
+        )));
+    }
+
+    Ok(())
+}
+
+fn list_recent(recent: &Recent, output: Output) -> anyhow::Result<()> {
+    let documents = recent.documents.documents();
+    if output.json {
+        return print_json(&documents);
+    }
+    if documents.is_empty() {
+        println!("You haven't saved or opened any documents yet.");
+        return Ok(());
+    }
+
+    for (position, document) in documents.iter().enumerate() {
+        println!("@{} {} ({})", position + 1, document.title, document.id);
+    }
+
+    Ok(())
+}
+
+/// Runs `command` through the platform's shell with `content` on its standard input, leaving its
+/// output going to the terminal.
+fn pipe_to(command: &str, content: &[u8]) -> anyhow::Result<()> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+
+    let mut child = shell
+        .arg(command)
+        .stdin(process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("could not run `{command}`"))?;
+
+    let mut stdin = child.stdin.take().expect("the child's stdin was piped");
+    let written = stdin.write_all(content);
+    drop(stdin);
+    let status = child
+        .wait()
+        .with_context(|| format!("could not wait for `{command}` to finish"))?;
+
+    // A command which stops reading early closes the pipe, so its exit status says more about
+    // what went wrong than the failed write does.
+    if !status.success() {
+        return Err(errors::PipeFailed {
+            command: command.to_string(),
+            status,
+        }
+        .into());
+    }
+    match written {
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => Ok(()),
+        result => result.with_context(|| format!("could not write to `{command}`")),
+    }
+}
+
+/// Writes `content` to `path`, refusing to replace an existing file unless `overwrite` is set.
+pub(super) fn write_new_file(path: &Path, content: &[u8], overwrite: bool) -> anyhow::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+
+    let mut file = match options.open(path) {
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+            return Err(errors::FileExists {
+                path: path.to_path_buf(),
+            }
+            .into())
+        }
+        result => result.with_context(|| format!("could not create {}", path.display()))?,
+    };
+    file.write_all(content)
+        .with_context(|| format!("could not write {}", path.display()))
+}
+
+/// A search result printed with `--json`, with the full link to where it matched.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LinkedResult {
+    #[serde(flatten)]
+    result: logic::SearchResult,
+    web_url: String,
+}
+
+/// A document listed with `--json`, with its full link rather than only the one relative to the
+/// workspace's URL.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LinkedDocument {
+    #[serde(flatten)]
+    document: logic::DocumentSummary,
+    web_url: String,
+}
+
+impl LinkedDocument {
+    fn new(client: &GetOutlineClient, document: logic::DocumentSummary) -> Self {
+        Self {
+            web_url: web_url(client, &document.web_path()),
+            document,
+        }
+    }
+}
+
+/// The link to where search result `result` matched, at its section if it has one.
+fn result_url(client: &GetOutlineClient, result: &logic::SearchResult) -> String {
+    let url = web_url(client, &result.document.web_path());
+    match &result.section {
+        Some(section) => format!("{url}#{}", section.anchor),
+        None => url,
+    }
+}
+
+fn search(
+    client: &GetOutlineClient,
+    query: &str,
+    all: bool,
+    mirror: Option<&Path>,
+    pagination: PaginationArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let results = if all {
+        logic::search_every_page(client, query)?
+    } else {
+        logic::search_documents(client, query, pagination.into())?
+    };
+    if output.json {
+        let results = results
+            .into_iter()
+            .map(|result| LinkedResult {
+                web_url: result_url(client, &result),
+                result,
+            })
+            .collect::<Vec<_>>();
+        return print_json(&results);
+    }
+    if results.is_empty() {
+        println!("No documents matched \"{query}\".");
+        return Ok(());
+    }
+
+    for result in results {
+        let document = &result.document;
+        let url = result_url(client, &result);
+        println!("{} ({}) {url}", document.title, document.id);
+        if let Some(section) = &result.section {
+            println!("    In \"{}\"", section.heading);
+        }
+        if !result.context.is_empty() {
+            println!("    {}", result.context);
+        }
+
+        let saved = mirror
+            .map(|dir| dir.join(format!("{}.md", parsers::file_name_for(&document.title))))
+            .filter(|path| path.is_file());
+        if let Some(path) = saved {
+            match &result.section {
+                Some(section) => println!("    {}#{}", path.display(), section.file_anchor),
+                None => println!("    {}", path.display()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn find_in(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    pattern: &str,
+    ignore_case: bool,
+    before: usize,
+    after: usize,
+    output: Output,
+) -> anyhow::Result<()> {
+    let pattern = RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .context("the pattern is not a valid regular expression")?;
+    let document = logic::fetch_document(client, doc_id, logic::ContentSource::Info)?;
+
+    let groups = find::find_lines(&document.text, &pattern, before, after);
+    if output.json {
+        return print_json(&groups);
+    }
+    if groups.is_empty() {
+        println!("No lines in \"{}\" matched.", document.title);
+        return Ok(());
+    }
+
+    for (index, group) in groups.iter().enumerate() {
+        if index > 0 {
+            println!("--");
+        }
+        for line in group {
+            let separator = if line.is_match { ':' } else { '-' };
+            println!("{}{separator}{}", line.number, line.text);
+        }
+    }
+
+    Ok(())
+}
+
+fn create(
+    client: &GetOutlineClient,
+    file: &Path,
+    collection: &str,
+    title: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let markdown = read_markdown(file)?;
+    let fallback_title = if file == Path::new("-") {
+        "Untitled".into()
+    } else {
+        file.file_stem()
+            .map(|stem| stem.to_string_lossy())
+            .unwrap_or_default()
+    };
+
+    let created = logic::create_document(client, collection, &markdown, title, &fallback_title)?;
+    if output.json {
+        return print_json(&created);
+    }
+    println!("Created \"{}\" ({})", created.title, created.id);
+
+    Ok(())
+}
+
+fn update(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    file: &Path,
+    append: bool,
+    title: Option<&str>,
+    expected_revision: Option<u64>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let markdown = read_markdown(file)?;
+
+    let updated =
+        logic::update_document(client, doc_id, &markdown, title, append, expected_revision)?;
+    if output.json {
+        return print_json(&updated);
+    }
+    println!("Updated \"{}\" ({})", updated.title, updated.id);
+
+    Ok(())
+}
+
+fn patch_document(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    patch: &Path,
+    output: Output,
+) -> anyhow::Result<()> {
+    let patch = read_text_file(patch)?;
+
+    let outcome = logic::patch_document(client, doc_id, &patch)?;
+    if output.json {
+        return print_json(&match outcome {
+            logic::PatchOutcome::Updated(updated) => {
+                json!({ "id": updated.id, "title": updated.title, "updated": true })
+            }
+            logic::PatchOutcome::Unchanged { title } => {
+                json!({ "id": doc_id, "title": title, "updated": false })
+            }
+        });
+    }
+    match outcome {
+        logic::PatchOutcome::Updated(updated) => {
+            println!("Patched \"{}\" ({})", updated.title, updated.id)
+        }
+        logic::PatchOutcome::Unchanged { title } => {
+            println!("The patch didn't change \"{title}\", so nothing was updated.")
+        }
+    }
+
+    Ok(())
+}
+
+fn delete(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    permanent: bool,
+    yes: bool,
+    output: Output,
+) -> anyhow::Result<()> {
+    let prompt = if permanent {
+        format!("Permanently delete document {doc_id}? This can't be undone.")
+    } else {
+        format!("Move document {doc_id} to the trash?")
+    };
+    if !yes && !confirm(&prompt)? {
+        if output.json {
+            return print_json(&json!({ "id": doc_id, "deleted": false }));
+        }
+        println!("Aborted, nothing was deleted.");
+        return Ok(());
+    }
+
+    logic::delete_document(client, doc_id, permanent)?;
+    if output.json {
+        return print_json(&json!({ "id": doc_id, "deleted": true, "permanent": permanent }));
+    }
+    if permanent {
+        println!("Permanently deleted {doc_id}");
+    } else {
+        println!("Moved {doc_id} to the trash");
+    }
+
+    Ok(())
+}
+
+fn unpublish(client: &GetOutlineClient, doc_id: &str, output: Output) -> anyhow::Result<()> {
+    let document = logic::unpublish_document(client, doc_id)?;
+    if output.json {
+        return print_json(&document);
+    }
+    println!(
+        "Moved \"{}\" ({}) back to drafts",
+        document.title, document.id
+    );
+
+    Ok(())
+}
+
+fn restore(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    revision: &str,
+    output: Output,
+) -> anyhow::Result<()> {
+    let restored = revisions::restore_revision(client, doc_id, revision)?;
+    if output.json {
+        return print_json(&restored);
+    }
+    println!(
+        "Restored \"{}\" ({}) to revision {revision}",
+        restored.title, restored.id
+    );
+
+    Ok(())
+}
+
+fn list_revisions(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    pagination: PaginationArgs,
+    listing: ListingArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let revisions = revisions::list_revisions(client, doc_id, pagination.into())?;
+    if output.json {
+        return print_json(&revisions);
+    }
+    if revisions.is_empty() {
+        println!("No revisions found.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("ID"),
+        Column::Full("Created"),
+        Column::Shrink("Author"),
+    ]);
+    for revision in revisions {
+        table.row([revision.id, revision.created_at, revision.created_by.name]);
+    }
+    table.print(output, listing.into())?;
+
+    Ok(())
+}
+
+fn diff_against_share(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    share: &str,
+    output: Output,
+) -> anyhow::Result<()> {
+    let share_id = parsers::share_id_from_url(share);
+    let (latest, shared) = shares::document_and_shared(client, doc_id, share_id)?;
+    let rendered = diff::render_unified(
+        &shared.text,
+        &latest.text,
+        &format!("{} (shared as {share_id})", shared.title),
+        &format!("{} (latest)", latest.title),
+        diff::use_color(output.plain) && !output.json,
+    );
+    if output.json {
+        return print_json(&json!({ "id": doc_id, "shareId": share_id, "diff": rendered }));
+    }
+
+    if rendered.is_empty() {
+        println!("Readers of the share link see the document as it is now.");
+    } else {
+        print!("{rendered}");
+    }
+
+    Ok(())
+}
+
+fn revision_diff(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    from: &str,
+    to: &str,
+    output: Output,
+) -> anyhow::Result<()> {
+    let (old, new) = revisions::revision_pair(client, doc_id, from, to)?;
+    let rendered = diff::render_unified(
+        &old.text,
+        &new.text,
+        &format!("{} ({})", old.id, old.created_at),
+        &format!("{} ({})", new.id, new.created_at),
+        diff::use_color(output.plain) && !output.json,
+    );
+    if output.json {
+        return print_json(&json!({ "from": old.id, "to": new.id, "diff": rendered }));
+    }
+
+    if rendered.is_empty() {
+        println!("The revisions are identical.");
+    } else {
+        print!("{rendered}");
+    }
+
+    Ok(())
+}
+
+fn star(client: &GetOutlineClient, doc_id: &str, output: Output) -> anyhow::Result<()> {
+    stars::star_document(client, doc_id)?;
+    if output.json {
+        return print_json(&json!({ "id": doc_id, "starred": true }));
+    }
+    println!("Starred {doc_id}");
+
+    Ok(())
+}
+
+fn unstar(client: &GetOutlineClient, doc_id: &str, output: Output) -> anyhow::Result<()> {
+    stars::unstar_document(client, doc_id)?;
+    if output.json {
+        return print_json(&json!({ "id": doc_id, "starred": false }));
+    }
+    println!("Unstarred {doc_id}");
+
+    Ok(())
+}
+
+fn starred(client: &GetOutlineClient, output: Output) -> anyhow::Result<()> {
+    let documents = stars::starred_documents(client)?;
+    if output.json {
+        let documents = documents
+            .into_iter()
+            .map(|document| LinkedDocument::new(client, document))
+            .collect::<Vec<_>>();
+        return print_json(&documents);
+    }
+    if documents.is_empty() {
+        println!("You haven't starred any documents.");
+        return Ok(());
+    }
+
+    for document in documents {
+        let url = web_url(client, &document.web_path());
+        println!("{} ({}) {url}", document.title, document.id);
+    }
+
+    Ok(())
+}
+
+fn pin(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    collection: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    pins::pin_document(client, doc_id, collection)?;
+    if output.json {
+        return print_json(&json!({ "id": doc_id, "collectionId": collection, "pinned": true }));
+    }
+    match collection {
+        Some(collection) => println!("Pinned {doc_id} in collection {collection}"),
+        None => println!("Pinned {doc_id} to the home page"),
+    }
+
+    Ok(())
+}
+
+fn unpin(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    collection: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    pins::unpin_document(client, doc_id, collection)?;
+    if output.json {
+        return print_json(&json!({ "id": doc_id, "collectionId": collection, "pinned": false }));
+    }
+    match collection {
+        Some(collection) => println!("Unpinned {doc_id} from collection {collection}"),
+        None => println!("Unpinned {doc_id} from the home page"),
+    }
+
+    Ok(())
+}
+
+fn pinned(
+    client: &GetOutlineClient,
+    collection: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let documents = pins::pinned_documents(client, collection)?;
+    if output.json {
+        let documents = documents
+            .into_iter()
+            .map(|document| LinkedDocument::new(client, document))
+            .collect::<Vec<_>>();
+        return print_json(&documents);
+    }
+    if documents.is_empty() {
+        match collection {
+            Some(collection) => println!("No documents are pinned in collection {collection}."),
+            None => println!("No documents are pinned to the home page."),
+        }
+        return Ok(());
+    }
+
+    for document in documents {
+        let url = web_url(client, &document.web_path());
+        println!("{} ({}) {url}", document.title, document.id);
+    }
+
+    Ok(())
+}
+
+fn users(client: &GetOutlineClient, doc_id: &str, output: Output) -> anyhow::Result<()> {
+    let access = memberships::document_access(client, doc_id)?;
+    if output.json {
+        return print_json(&access);
+    }
+    if access.is_empty() {
+        println!("{doc_id} hasn't been shared with anyone outside its collection.");
+        return Ok(());
+    }
+
+    for entry in access {
+        match entry.user.email {
+            Some(email) => println!("{} <{email}>: {}", entry.user.name, entry.permission),
+            None => println!(
+                "{} ({}): {}",
+                entry.user.name, entry.user.id, entry.permission
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+fn views(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    listing: ListingArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let views = views::document_views(client, doc_id)?;
+    if output.json {
+        return print_json(&views);
+    }
+    if views.is_empty() {
+        println!("Nobody has viewed {doc_id} yet.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("User ID"),
+        Column::Shrink("Name"),
+        Column::Full("Views"),
+        Column::Full("Last viewed"),
+    ]);
+    for view in views {
+        table.row([
+            view.user.id,
+            view.user.name,
+            view.count.to_string(),
+            view.last_viewed_at.unwrap_or_else(|| "unknown".to_string()),
+        ]);
+    }
+    table.print(output, listing.into())?;
+
+    Ok(())
+}
+
+fn share_with(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    user_id: &str,
+    permission: Permission,
+    output: Output,
+) -> anyhow::Result<()> {
+    let permission = memberships::Permission::from(permission);
+    memberships::share_document(client, doc_id, user_id, permission)?;
+    if output.json {
+        return print_json(&json!({ "id": doc_id, "userId": user_id, "permission": permission }));
+    }
+    println!("Shared {doc_id} with {user_id}");
+
+    Ok(())
+}
+
+fn revoke_access(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    user_id: &str,
+    output: Output,
+) -> anyhow::Result<()> {
+    memberships::revoke_access(client, doc_id, user_id)?;
+    if output.json {
+        return print_json(&json!({ "id": doc_id, "userId": user_id, "removed": true }));
+    }
+    println!("Removed {user_id}'s access to {doc_id}");
+
+    Ok(())
+}
+
+fn publish_link(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    published: bool,
+    output: Output,
+) -> anyhow::Result<()> {
+    let share = shares::share_link(client, doc_id, published)?;
+    if output.json {
+        return print_json(&share);
+    }
+    // Only the link goes to stdout, so scripts can capture it
+    if !share.published {
+        eprintln!("Only members of the workspace can follow this link, pass --published to make it public");
+    }
+    println!("{}", share.url);
+
+    Ok(())
+}
+
+fn comment(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    message: Option<String>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let text = match message {
+        Some(message) => message,
+        None => std::io::read_to_string(std::io::stdin())
+            .context("could not read the comment from stdin")?,
+    };
+    let comment = comments::comment_on_document(client, doc_id, &text)?;
+    if output.json {
+        return print_json(&comment);
+    }
+    println!("Commented on {doc_id}");
+
+    Ok(())
+}
+
+/// Reads the markdown in `file`, or from stdin if it's `-`.
+fn read_markdown(file: &Path) -> anyhow::Result<String> {
+    if file == Path::new("-") {
+        return std::io::read_to_string(std::io::stdin())
+            .context("could not read the markdown from stdin");
+    }
+    read_text_file(file)
+}
+
+fn read_text_file(file: &Path) -> anyhow::Result<String> {
+    fs::read_to_string(file).with_context(|| format!("could not read {}", file.display()))
+}
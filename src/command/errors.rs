@@ -0,0 +1,382 @@
+//! Stable, machine-readable codes for the errors the CLI can report, so scripts can tell failures
+//! apart by exit status or code instead of by message.
+//!
+//! Codes and exit statuses are part of the CLI's interface: once released, a code keeps its name
+//! and exit status, and new failure types get new codes.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+
+use crate::getoutline_connection::ConfigError;
+use crate::logic::api_keys::ApiKeyError;
+use crate::logic::bookmarks::BookmarkError;
+use crate::logic::comments::CommentError;
+use crate::logic::documents::{PatchError, UnpublishError};
+use crate::logic::file_operations::FileOperationError;
+use crate::logic::pins::PinError;
+use crate::logic::policy::CommandNotAllowed;
+use crate::logic::progress::ProgressError;
+use crate::logic::recent::RecentError;
+use crate::logic::revisions::RevisionError;
+use crate::logic::stars::StarError;
+use crate::logic::users::UserError;
+use crate::logic::ConnectionError;
+
+/// A file the CLI was asked to create is already there.
+#[derive(Debug, thiserror::Error)]
+#[error("{} already exists, pass --overwrite to replace it", path.display())]
+pub struct FileExists {
+    pub path: PathBuf,
+}
+
+/// A command the CLI handed output to didn't succeed.
+#[derive(Debug, thiserror::Error)]
+#[error("`{command}` failed with {status}")]
+pub struct PipeFailed {
+    pub command: String,
+    pub status: ExitStatus,
+}
+
+/// Output which couldn't be written to stdout.
+#[derive(Debug, thiserror::Error)]
+pub enum StdoutError {
+    /// Whatever was reading stdout, like `head` at the other end of a pipe, stopped reading it.
+    #[error("stdout was closed before all the output was written")]
+    Closed,
+    /// Writing failed some other way, like the disk a redirected stdout goes to filling up.
+    #[error("could not write to stdout")]
+    Write(#[source] io::Error),
+}
+
+/// Arguments which are each fine on their own, but don't make sense together.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct InvalidArguments {
+    pub message: String,
+}
+
+/// A setting read from the environment which has a value it can't have.
+#[derive(Debug, thiserror::Error)]
+#[error("{var}: {message}")]
+pub struct InvalidSetting {
+    pub var: String,
+    pub message: String,
+}
+
+/// An error along with some guesses at what the user meant to ask for.
+#[derive(Debug)]
+pub struct DidYouMean {
+    pub error: anyhow::Error,
+    /// Each guess, ready to print.
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for DidYouMean {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}\n\nDid you mean:", self.error)?;
+        for suggestion in &self.suggestions {
+            write!(f, "\n  {suggestion}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for DidYouMean {}
+
+/// The kinds of failure the CLI reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// getOutline rejected the API key.
+    Auth401,
+    /// The API key isn't allowed to do what was asked.
+    Auth403,
+    /// The document, collection, or other object doesn't exist (or isn't visible to the key).
+    NotFound,
+    RevisionNotFound,
+    NotStarred,
+    NotPinned,
+    DocumentAlreadyDraft,
+    PatchInvalid,
+    PatchConflict,
+    /// A document was saved by someone else between being read and being updated.
+    RemoteChanged,
+    InvalidRegex,
+    /// An `@` shortcut for a recent document isn't one which makes sense.
+    InvalidShortcut,
+    /// An `@` shortcut points further back than the documents which have been remembered.
+    NoRecentDocument,
+    SaveExists,
+    /// The bookmarks file has a line which isn't a bookmark.
+    InvalidBookmarks,
+    InvalidBookmarkName,
+    UnknownBookmark,
+    /// A background job like an export failed on getOutline's side, or its file expired.
+    ExportFailed,
+    /// A background job took too long to finish.
+    ExportTimedOut,
+    /// A downloaded archive couldn't be unpacked.
+    InvalidArchive,
+    /// getOutline returned an error not covered by a more specific code.
+    Api,
+    /// getOutline couldn't be reached, or its response couldn't be read.
+    Network,
+    MissingApiKey,
+    InvalidConfig,
+    PolicyBlocked,
+    /// Reading or writing a local file failed.
+    Io,
+    /// A command given output through `--pipe` exited unsuccessfully.
+    PipeFailed,
+    /// The command stopped because it reached `--max-requests`, `--max-bytes`, or
+    /// `--max-duration`.
+    BudgetExhausted,
+    /// `--resume` was passed, but there's no saved progress for the same operation.
+    NothingToResume,
+    /// `documents comment` was given nothing to say.
+    CommentEmpty,
+    /// The API key in use isn't among the user's keys, so it can't be rotated.
+    ApiKeyNotFound,
+    /// A new API key didn't work, so it was revoked again.
+    KeyRotationFailed,
+    /// Arguments clap accepted don't fit together, e.g. more `--name`s than `--email`s.
+    InvalidArguments,
+    /// Whatever was reading stdout stopped reading it, so there's no point printing anything.
+    StdoutClosed,
+    /// Anything else.
+    Unknown,
+}
+
+impl ErrorCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::Auth401 => "E_AUTH_401",
+            ErrorCode::Auth403 => "E_AUTH_403",
+            ErrorCode::NotFound => "E_NOT_FOUND",
+            ErrorCode::RevisionNotFound => "E_REVISION_NOT_FOUND",
+            ErrorCode::NotStarred => "E_NOT_STARRED",
+            ErrorCode::NotPinned => "E_NOT_PINNED",
+            ErrorCode::DocumentAlreadyDraft => "E_DOC_ALREADY_DRAFT",
+            ErrorCode::PatchInvalid => "E_PATCH_INVALID",
+            ErrorCode::PatchConflict => "E_PATCH_CONFLICT",
+            ErrorCode::RemoteChanged => "E_REMOTE_CHANGED",
+            ErrorCode::InvalidRegex => "E_INVALID_REGEX",
+            ErrorCode::InvalidShortcut => "E_INVALID_SHORTCUT",
+            ErrorCode::NoRecentDocument => "E_NO_RECENT_DOCUMENT",
+            ErrorCode::SaveExists => "E_SAVE_EXISTS",
+            ErrorCode::InvalidBookmarks => "E_BOOKMARKS_INVALID",
+            ErrorCode::InvalidBookmarkName => "E_INVALID_BOOKMARK_NAME",
+            ErrorCode::UnknownBookmark => "E_UNKNOWN_BOOKMARK",
+            ErrorCode::ExportFailed => "E_EXPORT_FAILED",
+            ErrorCode::ExportTimedOut => "E_EXPORT_TIMEOUT",
+            ErrorCode::InvalidArchive => "E_ARCHIVE_INVALID",
+            ErrorCode::Api => "E_API",
+            ErrorCode::Network => "E_NETWORK",
+            ErrorCode::MissingApiKey => "E_CONFIG_MISSING_API_KEY",
+            ErrorCode::InvalidConfig => "E_CONFIG_INVALID",
+            ErrorCode::PolicyBlocked => "E_POLICY_BLOCKED",
+            ErrorCode::Io => "E_IO",
+            ErrorCode::PipeFailed => "E_PIPE_FAILED",
+            ErrorCode::BudgetExhausted => "E_BUDGET_EXHAUSTED",
+            ErrorCode::NothingToResume => "E_NOTHING_TO_RESUME",
+            ErrorCode::CommentEmpty => "E_COMMENT_EMPTY",
+            ErrorCode::ApiKeyNotFound => "E_API_KEY_NOT_FOUND",
+            ErrorCode::KeyRotationFailed => "E_KEY_ROTATION_FAILED",
+            ErrorCode::InvalidArguments => "E_INVALID_ARGUMENTS",
+            ErrorCode::StdoutClosed => "E_STDOUT_CLOSED",
+            ErrorCode::Unknown => "E_UNKNOWN",
+        }
+    }
+
+    /// The process exit status for this kind of failure. Related codes share a status, and 2 is
+    /// shared with clap's usage errors.
+    ///
+    /// A closed stdout exits with what shells report for a program killed by `SIGPIPE`, as C
+    /// programs are.
+    pub fn exit_status(self) -> i32 {
+        match self {
+            ErrorCode::Unknown => 1,
+            ErrorCode::PatchInvalid
+            | ErrorCode::InvalidRegex
+            | ErrorCode::InvalidShortcut
+            | ErrorCode::InvalidBookmarkName
+            | ErrorCode::CommentEmpty
+            | ErrorCode::InvalidArguments => 2,
+            ErrorCode::Auth401 | ErrorCode::Auth403 => 3,
+            ErrorCode::NotFound
+            | ErrorCode::RevisionNotFound
+            | ErrorCode::NotStarred
+            | ErrorCode::NotPinned
+            | ErrorCode::NoRecentDocument
+            | ErrorCode::UnknownBookmark
+            | ErrorCode::NothingToResume
+            | ErrorCode::ApiKeyNotFound => 4,
+            ErrorCode::DocumentAlreadyDraft
+            | ErrorCode::PatchConflict
+            | ErrorCode::RemoteChanged
+            | ErrorCode::SaveExists => 5,
+            ErrorCode::Api
+            | ErrorCode::Network
+            | ErrorCode::ExportFailed
+            | ErrorCode::ExportTimedOut
+            | ErrorCode::InvalidArchive
+            | ErrorCode::KeyRotationFailed => 6,
+            ErrorCode::MissingApiKey | ErrorCode::InvalidConfig | ErrorCode::InvalidBookmarks => 7,
+            ErrorCode::PolicyBlocked => 8,
+            ErrorCode::Io => 9,
+            ErrorCode::PipeFailed => 10,
+            ErrorCode::BudgetExhausted => 11,
+            ErrorCode::StdoutClosed => 128 + 13,
+        }
+    }
+}
+
+/// Works out which kind of failure `err` is, from the most specific error in its chain of causes.
+pub fn classify(err: &anyhow::Error) -> ErrorCode {
+    err.chain()
+        .find_map(classify_cause)
+        .unwrap_or(ErrorCode::Unknown)
+}
+
+fn classify_cause(cause: &(dyn std::error::Error + 'static)) -> Option<ErrorCode> {
+    if let Some(err) = cause.downcast_ref::<DidYouMean>() {
+        return Some(classify(&err.error));
+    }
+    if let Some(err) = cause.downcast_ref::<ConnectionError>() {
+        return Some(classify_connection(err));
+    }
+    if let Some(err) = cause.downcast_ref::<ConfigError>() {
+        return Some(match err {
+            ConfigError::MissingApiKey { .. } => ErrorCode::MissingApiKey,
+            ConfigError::InvalidApiKey
+            | ConfigError::InvalidAuditActor { .. }
+            | ConfigError::InvalidRateLimit { .. }
+            | ConfigError::Client(_)
+            | ConfigError::Tls(_) => ErrorCode::InvalidConfig,
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<PatchError>() {
+        return Some(match err {
+            PatchError::InvalidPatch(_) => ErrorCode::PatchInvalid,
+            PatchError::DoesNotApply(_) => ErrorCode::PatchConflict,
+            PatchError::Connection(err) => classify_connection(err),
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<UnpublishError>() {
+        return Some(match err {
+            UnpublishError::AlreadyDraft { .. } => ErrorCode::DocumentAlreadyDraft,
+            UnpublishError::Connection(err) => classify_connection(err),
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<RevisionError>() {
+        return Some(match err {
+            RevisionError::RevisionNotFound { .. } => ErrorCode::RevisionNotFound,
+            RevisionError::Connection(err) => classify_connection(err),
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<PinError>() {
+        return Some(match err {
+            PinError::NotPinned { .. } => ErrorCode::NotPinned,
+            PinError::Connection(err) => classify_connection(err),
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<StarError>() {
+        return Some(match err {
+            StarError::NotStarred(_) => ErrorCode::NotStarred,
+            StarError::Connection(err) => classify_connection(err),
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<CommentError>() {
+        return Some(match err {
+            CommentError::Empty => ErrorCode::CommentEmpty,
+            CommentError::Connection(err) => classify_connection(err),
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<UserError>() {
+        return Some(match err {
+            UserError::UnknownEmail(_) => ErrorCode::NotFound,
+            UserError::Connection(err) => classify_connection(err),
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<ApiKeyError>() {
+        return Some(match err {
+            ApiKeyError::NotFound { .. } | ApiKeyError::UnknownId { .. } => {
+                ErrorCode::ApiKeyNotFound
+            }
+            ApiKeyError::Ambiguous { .. } => ErrorCode::InvalidArguments,
+            ApiKeyError::Rejected { .. } => ErrorCode::KeyRotationFailed,
+            ApiKeyError::Connection(err) => classify_connection(err),
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<RecentError>() {
+        return Some(match err {
+            RecentError::InvalidShortcut(_) => ErrorCode::InvalidShortcut,
+            RecentError::NoSuchDocument { .. } => ErrorCode::NoRecentDocument,
+        });
+    }
+    if cause.is::<ProgressError>() {
+        return Some(ErrorCode::NothingToResume);
+    }
+    if let Some(err) = cause.downcast_ref::<BookmarkError>() {
+        return Some(match err {
+            BookmarkError::InvalidLine { .. } => ErrorCode::InvalidBookmarks,
+            BookmarkError::InvalidName(_) => ErrorCode::InvalidBookmarkName,
+            BookmarkError::UnknownBookmark(_) => ErrorCode::UnknownBookmark,
+        });
+    }
+    if let Some(err) = cause.downcast_ref::<FileOperationError>() {
+        return Some(match err {
+            FileOperationError::Failed { .. } | FileOperationError::Expired => {
+                ErrorCode::ExportFailed
+            }
+            FileOperationError::TimedOut => ErrorCode::ExportTimedOut,
+            FileOperationError::Connection(err) => classify_connection(err),
+        });
+    }
+    if cause.is::<zip::result::ZipError>() {
+        return Some(ErrorCode::InvalidArchive);
+    }
+    if cause.is::<CommandNotAllowed>() {
+        return Some(ErrorCode::PolicyBlocked);
+    }
+    if cause.is::<FileExists>() {
+        return Some(ErrorCode::SaveExists);
+    }
+    if cause.is::<InvalidSetting>() {
+        return Some(ErrorCode::InvalidConfig);
+    }
+    if cause.is::<InvalidArguments>() {
+        return Some(ErrorCode::InvalidArguments);
+    }
+    if let Some(err) = cause.downcast_ref::<StdoutError>() {
+        return Some(match err {
+            StdoutError::Closed => ErrorCode::StdoutClosed,
+            StdoutError::Write(_) => ErrorCode::Io,
+        });
+    }
+    if cause.is::<PipeFailed>() {
+        return Some(ErrorCode::PipeFailed);
+    }
+    if cause.is::<regex::Error>() {
+        return Some(ErrorCode::InvalidRegex);
+    }
+    if cause.is::<io::Error>() {
+        return Some(ErrorCode::Io);
+    }
+
+    None
+}
+
+fn classify_connection(err: &ConnectionError) -> ErrorCode {
+    match err {
+        ConnectionError::Unauthorized { status: 403 } => ErrorCode::Auth403,
+        ConnectionError::Unauthorized { .. } => ErrorCode::Auth401,
+        ConnectionError::NotFound { .. } => ErrorCode::NotFound,
+        ConnectionError::Api { .. } => ErrorCode::Api,
+        ConnectionError::Transport(_) => ErrorCode::Network,
+        ConnectionError::OverBudget(_) => ErrorCode::BudgetExhausted,
+        ConnectionError::RemoteChanged { .. } => ErrorCode::RemoteChanged,
+    }
+}
@@ -0,0 +1,124 @@
+use clap::{Args, Subcommand};
+
+use super::documents::{self, Recent};
+use super::output::table::{Column, Table};
+use super::output::{print_json, Output};
+use super::{ListingArgs, PaginationArgs};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::events::{self as logic, EventQuery};
+use crate::logic::users;
+
+#[derive(Subcommand)]
+pub enum EventsCommand {
+    /// Lists what happened in the workspace, newest first: who did what, and to which document.
+    List(ListArgs),
+}
+
+#[derive(Args)]
+pub struct ListArgs {
+    /// Only lists what this user did, given by ID or email address.
+    #[arg(long)]
+    actor: Option<String>,
+    /// Only lists events for this document.
+    #[arg(long, value_parser = documents::document_id)]
+    document: Option<String>,
+    /// Lists every event from this UTC date or time onwards, like `2024-05-01` or
+    /// `2024-05-01T09:00:00Z`, instead of a single page.
+    #[arg(long, value_parser = utc_timestamp, conflicts_with_all = ["page", "results_per_page"])]
+    since: Option<String>,
+    #[command(flatten)]
+    pagination: PaginationArgs,
+    #[command(flatten)]
+    listing: ListingArgs,
+}
+
+pub fn exec(
+    cmd: EventsCommand,
+    client: &GetOutlineClient,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    match cmd {
+        EventsCommand::List(args) => list(client, args, profile, output),
+    }
+}
+
+fn list(
+    client: &GetOutlineClient,
+    args: ListArgs,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let actor_id = match args.actor {
+        Some(actor) if actor.contains('@') => Some(users::user_info(client, &actor)?.id),
+        actor => actor,
+    };
+    let document_id = match args.document {
+        Some(document) => Some(documents::resolve_reference(
+            &Recent::load(profile),
+            &document,
+        )?),
+        None => None,
+    };
+    let query = EventQuery {
+        actor_id: actor_id.as_deref(),
+        document_id: document_id.as_deref(),
+    };
+
+    let events = match &args.since {
+        Some(since) => logic::events_since(client, &query, since)?,
+        None => logic::list_events(client, &query, args.pagination.into())?,
+    };
+    if output.json {
+        return print_json(&events);
+    }
+    if events.is_empty() {
+        println!("No events found.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("Time"),
+        Column::Shrink("Actor"),
+        Column::Full("Event"),
+        Column::Full("Document"),
+    ]);
+    for event in events {
+        let actor = event
+            .actor
+            .map(|actor| actor.name)
+            .or(event.actor_id)
+            .unwrap_or_else(|| "unknown".to_string());
+        table.row([
+            event.created_at,
+            actor,
+            event.name,
+            event.document_id.unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    table.print(output, args.listing.into())?;
+
+    Ok(())
+}
+
+/// Accepts a date like `2024-05-01`, or a time on it in UTC like `2024-05-01T09:00:00Z`, which
+/// compare correctly against the timestamps getOutline sends.
+pub(super) fn utc_timestamp(value: &str) -> Result<String, String> {
+    let is_date = value.len() >= 10
+        && value
+            .bytes()
+            .take(10)
+            .enumerate()
+            .all(|(index, byte)| match index {
+                4 | 7 => byte == b'-',
+                _ => byte.is_ascii_digit(),
+            });
+    let rest = value.get(10..).unwrap_or_default();
+    if is_date && (rest.is_empty() || (rest.starts_with('T') && rest.ends_with('Z'))) {
+        Ok(value.to_string())
+    } else {
+        Err(format!(
+            "{value} isn't a UTC date or time like 2024-05-01 or 2024-05-01T09:00:00Z"
+        ))
+    }
+}
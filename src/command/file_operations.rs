@@ -0,0 +1,174 @@
+use clap::{Subcommand, ValueEnum};
+
+use serde_json::json;
+
+use super::output::table::{Column, Table};
+use super::output::{print_json, Output};
+use super::{confirm, ListingArgs, PaginationArgs};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::file_operations::{self as logic, FileOperation, FileOperationKind};
+
+#[derive(Subcommand)]
+pub enum FileOperationsCommand {
+    /// Lists the workspace's exports or imports, newest first.
+    List {
+        /// Which kind of job to list.
+        #[arg(long = "type", value_enum, default_value_t = Kind::Export)]
+        kind: Kind,
+        #[command(flatten)]
+        pagination: PaginationArgs,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Shows a job's details.
+    Info {
+        /// The ID of the job.
+        id: String,
+    },
+    /// Deletes a job, along with the file it produced.
+    Delete {
+        /// The ID of the job.
+        id: String,
+        /// Skips the confirmation prompt.
+        #[arg(long, short)]
+        yes: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Kind {
+    Export,
+    Import,
+}
+
+impl From<Kind> for FileOperationKind {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Export => FileOperationKind::Export,
+            Kind::Import => FileOperationKind::Import,
+        }
+    }
+}
+
+pub fn exec(
+    cmd: FileOperationsCommand,
+    client: &GetOutlineClient,
+    output: Output,
+) -> anyhow::Result<()> {
+    match cmd {
+        FileOperationsCommand::List {
+            kind,
+            pagination,
+            listing,
+        } => list(client, kind, pagination, listing, output),
+        FileOperationsCommand::Info { id } => info(client, &id, output),
+        FileOperationsCommand::Delete { id, yes } => delete(client, &id, yes, output),
+    }
+}
+
+fn list(
+    client: &GetOutlineClient,
+    kind: Kind,
+    pagination: PaginationArgs,
+    listing: ListingArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let kind = FileOperationKind::from(kind);
+    let operations = logic::list_file_operations(client, kind, pagination.into())?;
+    if output.json {
+        return print_json(&operations);
+    }
+    if operations.is_empty() {
+        println!("No {}s found.", kind.description());
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("ID"),
+        Column::Full("State"),
+        Column::Shrink("Name"),
+        Column::Full("Created"),
+    ]);
+    for operation in &operations {
+        table.row([
+            operation.id.clone(),
+            operation.state.description().to_string(),
+            name(operation).to_string(),
+            operation
+                .created_at
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+        ]);
+    }
+    table.print(output, listing.into())?;
+
+    Ok(())
+}
+
+fn info(client: &GetOutlineClient, id: &str, output: Output) -> anyhow::Result<()> {
+    let operation = logic::file_operation_info(client, id)?;
+    if output.json {
+        return print_json(&operation);
+    }
+
+    let mut fields = vec![
+        ("Name", name(&operation)),
+        ("ID", operation.id.as_str()),
+        (
+            "Type",
+            operation
+                .kind
+                .map_or("unknown", FileOperationKind::description),
+        ),
+        ("Format", operation.format.as_deref().unwrap_or("unknown")),
+        ("State", operation.state.description()),
+    ];
+    if let Some(error) = operation.error.as_deref() {
+        fields.push(("Error", error));
+    }
+    fields.extend([
+        (
+            "Collection",
+            operation
+                .collection_id
+                .as_deref()
+                .unwrap_or("the whole workspace"),
+        ),
+        (
+            "Started by",
+            operation.user.as_ref().map_or("unknown", |user| &user.name),
+        ),
+        (
+            "Created",
+            operation.created_at.as_deref().unwrap_or("unknown"),
+        ),
+    ]);
+    for (key, value) in fields {
+        println!("{:<11} {value}", format!("{key}:"));
+    }
+
+    Ok(())
+}
+
+fn delete(client: &GetOutlineClient, id: &str, yes: bool, output: Output) -> anyhow::Result<()> {
+    if !yes && !confirm(&format!("Delete job {id} and its file?"))? {
+        if output.json {
+            return print_json(&json!({ "id": id, "deleted": false }));
+        }
+        println!("Aborted, nothing was deleted.");
+        return Ok(());
+    }
+
+    logic::delete_file_operation(client, id)?;
+    if output.json {
+        return print_json(&json!({ "id": id, "deleted": true }));
+    }
+    println!("Deleted {id}");
+
+    Ok(())
+}
+
+/// What to call a job in listings, since not every job has a file name.
+fn name(operation: &FileOperation) -> &str {
+    operation.name.as_deref().unwrap_or("(unnamed)")
+}
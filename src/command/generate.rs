@@ -0,0 +1,137 @@
+//! `generate-fixtures`: made-up content for performance testing and workshop exercises, served by
+//! a mock getOutline server or created in a scratch workspace. Only built with the
+//! `test-fixtures` feature.
+
+use std::thread;
+
+use anyhow::Context;
+use clap::Args;
+use serde_json::json;
+
+use super::output::{print_json, Output};
+use super::progress::Reporter;
+use super::{confirm, Connector};
+use crate::fixtures::generate::FakeWorkspace;
+use crate::fixtures::MockServer;
+use crate::logic::workspace::WorkspaceReader;
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    /// How many documents to make up.
+    #[arg(long, default_value_t = 100)]
+    documents: usize,
+    /// How many collections to spread the documents across.
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+    collections: u32,
+    /// Picks which content is made up. The same seed always makes up the same documents.
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+    /// The address to serve the content from. Port 0 picks a free one.
+    #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1:8765")]
+    listen: String,
+    /// Creates the content in the workspace the profile connects to instead, in new collections
+    /// alongside what's already there. Meant for scratch workspaces: the only way to undo it is
+    /// deleting the collections.
+    #[arg(long, conflicts_with = "listen")]
+    workspace: bool,
+    /// Creates the content in the workspace without asking first.
+    #[arg(long, requires = "workspace")]
+    yes: bool,
+}
+
+pub fn exec(
+    args: GenerateArgs,
+    connector: &Connector,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let fake = FakeWorkspace::generate(args.documents, args.collections as usize, args.seed);
+    if args.workspace {
+        let operation = format!(
+            "generate-fixtures --documents {} --collections {} --seed {}",
+            args.documents, args.collections, args.seed
+        );
+        populate(&fake, connector, profile, &operation, args.yes, output)
+    } else {
+        serve(fake, &args.listen, output)
+    }
+}
+
+/// Serves `fake` from a mock server on `address` until the CLI is stopped.
+fn serve(fake: FakeWorkspace, address: &str, output: Output) -> anyhow::Result<()> {
+    let documents = fake.document_count();
+    let collections = fake.collections.len();
+    let server = MockServer::start_workspace(address, fake)
+        .with_context(|| format!("could not listen on {address}"))?;
+
+    if output.json {
+        print_json(&json!({
+            "url": server.url(),
+            "documents": documents,
+            "collections": collections,
+        }))?;
+    } else {
+        println!(
+            "Serving {documents} documents in {collections} collections at {}",
+            server.url()
+        );
+    }
+    eprintln!(
+        "Point GETOUTLINE_BASE_URL at it to try commands out (any API key works), and press \
+         Ctrl-C to stop it."
+    );
+    loop {
+        thread::park();
+    }
+}
+
+/// Creates `fake`'s collections and documents in the workspace `profile` connects to.
+fn populate(
+    fake: &FakeWorkspace,
+    connector: &Connector,
+    profile: Option<&str>,
+    operation: &str,
+    yes: bool,
+    output: Output,
+) -> anyhow::Result<()> {
+    let client = connector.connect(profile)?;
+    if !yes {
+        let team = client.auth_info()?.team.name;
+        let prompt = format!(
+            "Create {} made-up documents in {} new collections in {team}?",
+            fake.document_count(),
+            fake.collections.len()
+        );
+        if !confirm(&prompt)? {
+            eprintln!("Aborted, nothing was created.");
+            return Ok(());
+        }
+    }
+
+    let mut reporter = Reporter::start(output.progress, operation);
+    let created = fake.create_in(&client, |document, summary| {
+        let _ = output.status(format_args!("  Created \"{}\"", document.title));
+        reporter.completed(&summary.id, &summary.title);
+    });
+    reporter.finish();
+    let created = created?;
+
+    if output.json {
+        let collections: Vec<_> = created
+            .collections
+            .iter()
+            .map(|collection| json!({ "id": collection.id, "name": collection.name }))
+            .collect();
+        return print_json(&json!({
+            "collections": collections,
+            "documents": created.documents,
+        }));
+    }
+    println!(
+        "Created {} documents in {} collections",
+        created.documents,
+        created.collections.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use clap::Subcommand;
+
+use super::output::table::{Column, Table};
+use super::output::{print_json, Output};
+use super::{ListingArgs, PaginationArgs};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::groups as logic;
+
+#[derive(Subcommand)]
+pub enum GroupsCommand {
+    /// Lists the groups in the workspace and how many members each has.
+    List {
+        #[command(flatten)]
+        pagination: PaginationArgs,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Shows a group's details.
+    Info {
+        /// The ID of the group.
+        id: String,
+    },
+    /// Lists everyone in a group.
+    Members {
+        /// The ID of the group.
+        id: String,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+}
+
+pub fn exec(cmd: GroupsCommand, client: &GetOutlineClient, output: Output) -> anyhow::Result<()> {
+    match cmd {
+        GroupsCommand::List {
+            pagination,
+            listing,
+        } => list(client, pagination, listing, output),
+        GroupsCommand::Info { id } => info(client, &id, output),
+        GroupsCommand::Members { id, listing } => members(client, &id, listing, output),
+    }
+}
+
+fn list(
+    client: &GetOutlineClient,
+    pagination: PaginationArgs,
+    listing: ListingArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let groups = logic::list_groups(client, pagination.into())?;
+    if output.json {
+        return print_json(&groups);
+    }
+    if groups.is_empty() {
+        println!("No groups found.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("ID"),
+        Column::Shrink("Name"),
+        Column::Full("Members"),
+    ]);
+    for group in groups {
+        table.row([group.id, group.name, group.member_count.to_string()]);
+    }
+    table.print(output, listing.into())?;
+
+    Ok(())
+}
+
+fn info(client: &GetOutlineClient, id: &str, output: Output) -> anyhow::Result<()> {
+    let group = logic::group_info(client, id)?;
+    if output.json {
+        return print_json(&group);
+    }
+
+    let fields = [
+        ("Name", group.name.as_str()),
+        ("ID", &group.id),
+        ("Members", &group.member_count.to_string()),
+        ("Created", group.created_at.as_deref().unwrap_or("unknown")),
+    ];
+    for (key, value) in fields {
+        println!("{:<8} {value}", format!("{key}:"));
+    }
+
+    Ok(())
+}
+
+fn members(
+    client: &GetOutlineClient,
+    id: &str,
+    listing: ListingArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let users = logic::group_members(client, id)?;
+    if output.json {
+        return print_json(&users);
+    }
+    if users.is_empty() {
+        println!("Group {id} doesn't have any members.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("ID"),
+        Column::Shrink("Name"),
+        Column::Shrink("Email"),
+    ]);
+    for user in users {
+        table.row([
+            user.id,
+            user.name,
+            user.email.unwrap_or_else(|| "no email".to_string()),
+        ]);
+    }
+    table.print(output, listing.into())?;
+
+    Ok(())
+}
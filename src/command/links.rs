@@ -0,0 +1,182 @@
+use clap::Args;
+use serde::Serialize;
+
+use super::confirm;
+use super::output::{print_json, Output};
+use super::progress::{Checkpoint, Reporter};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::links::{self as logic, RewrittenLink};
+
+#[derive(Args)]
+pub struct RewriteLinksArgs {
+    /// The host links currently point at, e.g. `old.example.com`.
+    #[arg(long)]
+    from: String,
+    /// The host links should point at instead.
+    #[arg(long)]
+    to: String,
+    /// Only rewrites links in documents in this collection.
+    #[arg(long)]
+    collection: Option<String>,
+    /// Updates the documents. Without this, the rewritten links are only listed.
+    #[arg(long)]
+    apply: bool,
+    /// Asks before updating each document. Only applies with `--apply`.
+    #[arg(long, requires = "apply")]
+    confirm: bool,
+    /// Skips the documents finished by an earlier run of the same rewrite which stopped part way.
+    #[arg(long, requires = "apply")]
+    resume: bool,
+}
+
+impl RewriteLinksArgs {
+    /// The arguments which decide what gets changed, to tell one rewrite's progress from another's.
+    fn operation(&self) -> String {
+        let mut operation = format!("rewrite-links --from {} --to {}", self.from, self.to);
+        if let Some(collection) = &self.collection {
+            operation.push_str(&format!(" --collection {collection}"));
+        }
+        operation
+    }
+}
+
+/// What a run of the command has got through so far.
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Tally {
+    documents_found: usize,
+    documents_updated: usize,
+    links_rewritten: usize,
+    /// Each document with links to rewrite, for `--json`.
+    documents: Vec<DocumentLinks>,
+}
+
+/// A document's links which point at `--from`, as printed with `--json`.
+#[derive(Serialize)]
+struct DocumentLinks {
+    id: String,
+    title: String,
+    links: Vec<RewrittenLink>,
+    /// Whether the document was updated with the rewritten links.
+    updated: bool,
+}
+
+pub fn exec(
+    args: RewriteLinksArgs,
+    client: &GetOutlineClient,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let mut checkpoint =
+        Checkpoint::start(profile, "rewrite-links", args.operation(), args.resume)?;
+    let mut reporter = Reporter::start(output.progress, &args.operation());
+    let mut tally = Tally::default();
+    let result = rewrite_each(
+        &args,
+        client,
+        output,
+        &mut checkpoint,
+        &mut reporter,
+        &mut tally,
+    );
+    reporter.finish();
+    if let Err(err) = result {
+        return Err(if args.apply {
+            checkpoint.save_after(err)
+        } else {
+            err
+        });
+    }
+
+    if args.apply {
+        checkpoint.finish();
+    }
+
+    if output.json {
+        return print_json(&tally);
+    }
+    if tally.documents_found == 0 {
+        println!("No links point at {}.", args.from);
+    } else if args.apply {
+        println!(
+            "Rewrote {} links in {} of {} documents.",
+            tally.links_rewritten, tally.documents_updated, tally.documents_found
+        );
+    } else {
+        println!(
+            "Dry run: {} documents have links to rewrite. Re-run with --apply to update them.",
+            tally.documents_found
+        );
+    }
+
+    Ok(())
+}
+
+/// Goes through the documents mentioning `--from` one at a time, so a run which stops part way
+/// has still finished with some of them.
+fn rewrite_each(
+    args: &RewriteLinksArgs,
+    client: &GetOutlineClient,
+    output: Output,
+    checkpoint: &mut Checkpoint,
+    reporter: &mut Reporter,
+    tally: &mut Tally,
+) -> anyhow::Result<()> {
+    for summary in logic::documents_mentioning(client, &args.from, args.collection.as_deref())? {
+        if checkpoint.is_done(&summary.id) {
+            continue;
+        }
+        let (id, title) = (summary.id.clone(), summary.title.clone());
+        let Some(rewrite) = logic::plan_link_rewrite(client, summary, &args.from, &args.to)
+            .map_err(|err| reporter.failed(&id, err.into()))?
+        else {
+            checkpoint.mark_done(&id);
+            reporter.completed(&id, &title);
+            continue;
+        };
+
+        let document = &rewrite.document;
+        tally.documents_found += 1;
+        if output.json {
+            tally.documents.push(DocumentLinks {
+                id: document.id.clone(),
+                title: document.title.clone(),
+                links: rewrite.links.clone(),
+                updated: false,
+            });
+        } else {
+            println!(
+                "\"{}\" ({}): {} links",
+                document.title,
+                document.id,
+                rewrite.links.len()
+            );
+            for link in &rewrite.links {
+                println!("    {} -> {}", link.old, link.new);
+            }
+        }
+
+        if !args.apply {
+            reporter.completed(&id, &title);
+            continue;
+        }
+        if args.confirm && !confirm(&format!("Update \"{}\"?", document.title))? {
+            // Not marked as done, so --resume asks about it again
+            output.status(format_args!("Skipped \"{}\"", document.title))?;
+            reporter.completed(&id, &title);
+            continue;
+        }
+
+        logic::apply_link_rewrite(client, &rewrite)
+            .map_err(|err| reporter.failed(&id, err.into()))?;
+        tally.documents_updated += 1;
+        tally.links_rewritten += rewrite.links.len();
+        if let Some(updated) = tally.documents.last_mut() {
+            updated.updated = true;
+        }
+        checkpoint.mark_done(&id);
+        reporter.completed(&id, &title);
+    }
+
+    Ok(())
+}
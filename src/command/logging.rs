@@ -0,0 +1,137 @@
+//! Prints what the crate logs with `tracing` on stderr, as many messages as `-v`, `-vv`, or
+//! `--quiet` ask for, so stdout is left to what the command prints.
+//!
+//! Only the messages this crate logs get more detailed with `-v`. Its dependencies only get to
+//! print warnings and errors, which keeps `-vvv` to what a getOutline user can make sense of.
+
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Once;
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::{self, Interest};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Every level, from the most to the least severe.
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+/// Which of [LEVELS] is the least severe one printed. The shell changes it from one command to
+/// the next.
+static LEVEL: AtomicU8 = AtomicU8::new(1);
+
+/// Guards installing [Logger], which only happens once.
+static INSTALLED: Once = Once::new();
+
+/// How much the `-v` and `--quiet` flags asked to be logged.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Verbosity {
+    /// How many times `-v` was given.
+    pub verbose: u8,
+    pub quiet: bool,
+}
+
+impl Verbosity {
+    /// The least severe level which gets printed: errors with `--quiet`, warnings by default, then
+    /// what each request did with `-v`, more detail about them with `-vv`, and the bodies sent and
+    /// received with `-vvv`.
+    pub fn level(self) -> Level {
+        match (self.quiet, self.verbose) {
+            (true, _) => Level::ERROR,
+            (false, 0) => Level::WARN,
+            (false, 1) => Level::INFO,
+            (false, 2) => Level::DEBUG,
+            (false, _) => Level::TRACE,
+        }
+    }
+}
+
+/// Starts printing log messages as `verbosity` asks, for the rest of the command.
+pub(super) fn start(verbosity: Verbosity) {
+    let level = LEVELS
+        .iter()
+        .position(|level| *level == verbosity.level())
+        .unwrap_or(1);
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    INSTALLED.call_once(|| {
+        // Programs using the crate as a library may have set up their own logging already
+        let _ = subscriber::set_global_default(Logger);
+    });
+}
+
+/// Prints each log message as a line on stderr, like `Debug: sent documents.info`.
+struct Logger;
+
+impl Subscriber for Logger {
+    fn register_callsite(&self, _: &'static Metadata<'static>) -> Interest {
+        // The level can change between commands in the shell, so it's checked every time
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let level = *metadata.level();
+        let ours = metadata.target().starts_with(env!("CARGO_CRATE_NAME"));
+        level <= LEVELS[usize::from(LEVEL.load(Ordering::Relaxed))]
+            && (ours || level <= Level::WARN)
+    }
+
+    fn event(&self, event: &Event<'_>) {
+        let mut line = Line::default();
+        event.record(&mut line);
+        let label = match *event.metadata().level() {
+            Level::ERROR => "Error",
+            Level::WARN => "Warning",
+            Level::INFO => "Info",
+            Level::DEBUG => "Debug",
+            _ => "Trace",
+        };
+        eprintln!("{label}: {}{}", line.message, line.fields);
+    }
+
+    // Nothing in the crate logs with spans, so they're ignored
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+/// A log message being put together from an event's fields: the message, followed by
+/// `name=value` for each of the others.
+#[derive(Default)]
+struct Line {
+    message: String,
+    fields: String,
+}
+
+impl Visit for Line {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else if value.is_empty() || value.contains(char::is_whitespace) {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
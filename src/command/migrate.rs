@@ -0,0 +1,140 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use clap::Subcommand;
+
+use super::output::{print_json, Output};
+use super::progress::Reporter;
+use super::Connector;
+use crate::logic::collections::{self as logic, MigrationMap};
+
+#[derive(Subcommand)]
+pub enum MigrateCommand {
+    /// Copies a collection from one workspace profile into another.
+    ///
+    /// Profiles are configured with `GETOUTLINE_<PROFILE>_API_KEY` and (optionally)
+    /// `GETOUTLINE_<PROFILE>_BASE_URL` environment variables. Each profile's
+    /// `GETOUTLINE_<PROFILE>_REQUESTS_PER_MINUTE` limits the requests sent to its own workspace.
+    Workspace {
+        /// The profile to read the collection from.
+        #[arg(long)]
+        from: String,
+        /// The profile to write the copy into.
+        #[arg(long)]
+        to: String,
+        /// The ID of the collection to migrate, in the `--from` workspace.
+        #[arg(long)]
+        collection: String,
+        /// The name to give the new collection. Defaults to the original collection's name.
+        #[arg(long)]
+        name: Option<String>,
+        /// A JSON file recording which document each original was copied to. Documents it lists
+        /// are updated instead of being copied again, so the migration can be run repeatedly. It's
+        /// created if it doesn't exist, and updated after every run.
+        #[arg(long, value_name = "FILE")]
+        mapping: Option<PathBuf>,
+    },
+}
+
+/// Runs a migrate command, connecting to both workspaces through `connector`.
+pub fn exec(cmd: MigrateCommand, connector: &Connector, output: Output) -> anyhow::Result<()> {
+    match cmd {
+        MigrateCommand::Workspace {
+            from,
+            to,
+            collection,
+            name,
+            mapping,
+        } => workspace(
+            &from,
+            &to,
+            &collection,
+            name.as_deref(),
+            mapping.as_deref(),
+            connector,
+            output,
+        ),
+    }
+}
+
+fn workspace(
+    from: &str,
+    to: &str,
+    collection: &str,
+    name: Option<&str>,
+    mapping_file: Option<&Path>,
+    connector: &Connector,
+    output: Output,
+) -> anyhow::Result<()> {
+    let mut mapping = match mapping_file {
+        Some(file) => read_mapping(file)?,
+        None => MigrationMap::default(),
+    };
+    let source = connector
+        .connect(Some(from))
+        .with_context(|| format!("could not set up the \"{from}\" profile"))?;
+    let destination = connector
+        .connect(Some(to))
+        .with_context(|| format!("could not set up the \"{to}\" profile"))?;
+
+    let operation = format!("migrate workspace --from {from} --to {to} --collection {collection}");
+    let mut reporter = Reporter::start(output.progress, &operation);
+    let migrated = logic::migrate_collection(
+        &source,
+        &destination,
+        collection,
+        name,
+        &mut mapping,
+        |node| {
+            // A closed stdout stops the command once the migration is done, not halfway through
+            let _ = output.status(format_args!("  Migrated \"{}\"", node.title));
+            reporter.completed(&node.id, &node.title);
+        },
+    );
+    reporter.finish();
+    // Save the mapping even if the migration failed, so the documents which were copied before
+    // it did aren't copied again next time
+    if let Some(file) = mapping_file {
+        write_mapping(file, &mapping)?;
+    }
+    let migrated = migrated?;
+    if output.json {
+        return print_json(&migrated);
+    }
+    println!(
+        "Migrated {} documents from {from} into \"{}\" ({}) in {to}",
+        migrated.documents_copied, migrated.collection.name, migrated.collection.id
+    );
+    if migrated.documents_updated > 0 {
+        println!(
+            "{} of them were updated from an earlier migration instead of being copied again",
+            migrated.documents_updated
+        );
+    }
+
+    Ok(())
+}
+
+fn read_mapping(file: &Path) -> anyhow::Result<MigrationMap> {
+    match fs::read_to_string(file) {
+        Ok(json) => serde_json::from_str(&json)
+            .with_context(|| format!("{} is not a migration mapping file", file.display())),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(MigrationMap::default()),
+        Err(err) => Err(err).with_context(|| format!("could not read {}", file.display())),
+    }
+}
+
+fn write_mapping(file: &Path, mapping: &MigrationMap) -> anyhow::Result<()> {
+    let mut json = serde_json::to_string_pretty(mapping).context("could not encode the mapping")?;
+    json.push('\n');
+    // Write to a temporary file first so an interrupted run can't leave a half-written mapping,
+    // which would mean copying everything again
+    let mut temp_name = file.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp = file.with_file_name(temp_name);
+    fs::write(&temp, json)
+        .and_then(|()| fs::rename(&temp, file))
+        .with_context(|| format!("could not write {}", file.display()))
+}
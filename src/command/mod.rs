@@ -0,0 +1,496 @@
+//! Command-line argument definitions and dispatch to the individual command handlers.
+//!
+//! The command handlers print with `println!`, `print!`, and `eprintln!` as usual, but those are
+//! the macros below rather than the standard library's, so `--no-secrets-output` covers
+//! everything they print, and failing to write to stdout ends the command instead of panicking.
+//! Since `println!` and `print!` return early with an [errors::StdoutError], they can only be
+//! used in functions which return a `Result` (see [output::write_stdout]).
+
+/// Like the standard `println!`, but hides secrets in safe mode. See [printable].
+macro_rules! println {
+    () => { $crate::command::output::write_stdout("\n")? };
+    ($($arg:tt)*) => {
+        $crate::command::output::write_stdout(&::std::format!(
+            "{}\n",
+            $crate::command::printable(&::std::format!($($arg)*))
+        ))?
+    };
+}
+
+/// Like the standard `print!`, but hides secrets in safe mode. See [printable].
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::command::output::write_stdout(
+            &$crate::command::printable(&::std::format!($($arg)*))
+        )?
+    };
+}
+
+/// Like the standard `eprint!`, but hides secrets in safe mode. See [printable].
+macro_rules! eprint {
+    ($($arg:tt)*) => {
+        ::std::eprint!("{}", $crate::command::printable(&::std::format!($($arg)*)))
+    };
+}
+
+/// Like the standard `eprintln!`, but hides secrets in safe mode. See [printable].
+macro_rules! eprintln {
+    () => { ::std::eprintln!() };
+    ($($arg:tt)*) => {
+        ::std::eprintln!("{}", $crate::command::printable(&::std::format!($($arg)*)))
+    };
+}
+
+mod archive;
+mod attachments;
+mod auth;
+mod bookmarks;
+mod cache;
+mod catalog;
+mod collections;
+mod completion;
+mod diff;
+mod digest;
+mod documents;
+pub mod errors;
+mod events;
+mod file_operations;
+#[cfg(feature = "test-fixtures")]
+mod generate;
+mod groups;
+mod links;
+mod logging;
+mod migrate;
+pub mod output;
+#[cfg(unix)]
+mod picker;
+mod progress;
+mod replace;
+mod report;
+mod shares;
+mod shell;
+#[cfg(unix)]
+mod terminal;
+mod timings;
+mod tui;
+mod users;
+mod workspace;
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use clap::{ArgAction, ArgMatches, Args, Parser, Subcommand};
+
+use crate::config;
+use crate::getoutline_connection::{ConfigError, GetOutlineClient};
+use crate::logic::budget::{Budget, Meter};
+use crate::logic::timings::Timings;
+use crate::logic::{policy, secrets, Page};
+use logging::Verbosity;
+use output::table::ListingFormat;
+use output::Output;
+use progress::ProgressFormat;
+
+/// Lists and downloads documents from getOutline.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct CLIArgs {
+    /// The configuration profile to use, as set up with `GETOUTLINE_<PROFILE>_*` environment
+    /// variables. Defaults to `GETOUTLINE_PROFILE`.
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+    /// Stops before sending more than this many requests to getOutline. `rewrite-links` and
+    /// `documents replace` run with `--apply` save how far they got, so they can be continued
+    /// with `--resume`, and `migrate workspace` does through its `--mapping` file. Other commands
+    /// stop, and start over when they're run again.
+    #[arg(long, global = true)]
+    pub max_requests: Option<u64>,
+    /// Stops sending requests once this much has been downloaded from getOutline, e.g. `500K` or
+    /// `20M`. Progress is saved the same way as with `--max-requests`.
+    #[arg(long, global = true, value_parser = byte_size)]
+    pub max_bytes: Option<u64>,
+    /// Stops sending requests once the command has run this long, e.g. `90s`, `10m`, or `2h`, to
+    /// finish cleanly before a CI job or cron window times out. Progress is saved the same way as
+    /// with `--max-requests`.
+    #[arg(long, global = true, value_parser = duration)]
+    pub max_duration: Option<Duration>,
+    /// Prints plain, line-oriented text without color, box-drawing characters, or tables, which
+    /// reads better with a screen reader.
+    #[arg(long, global = true)]
+    pub plain: bool,
+    /// How bulk commands like `rewrite-links` report their progress. `json` adds a JSON object
+    /// per line on stderr as each document is started, finished, or fails.
+    #[arg(long, global = true, value_enum, default_value_t = ProgressFormat::Human)]
+    pub progress: ProgressFormat,
+    /// Hides API keys, share links, and email addresses from everything printed, for showing the
+    /// CLI to an audience. Also turned on by `GETOUTLINE_NO_SECRETS_OUTPUT=true`, and
+    /// automatically while recording with asciinema.
+    #[arg(long, global = true)]
+    pub no_secrets_output: bool,
+    /// Prints how long each request to getOutline took on stderr once the command is done, split
+    /// into looking up the server, connecting to it, waiting for it to respond, and downloading
+    /// the response, to tell a slow network from a slow server.
+    #[arg(long, global = true)]
+    pub timings: bool,
+    /// Logs what the command is doing on stderr: `-v` for each request sent to getOutline and
+    /// how it went, `-vv` for more detail like what getOutline said about failed requests, and
+    /// `-vvv` for everything sent and received.
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+    /// Leaves warnings out of what's printed on stderr, only printing errors.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// Prints what each command did as JSON instead of text, for scripts: an array for listings
+    /// and an object for everything else. Errors are printed on stderr as
+    /// `{"error": {"code": ..., "message": ...}}`.
+    #[arg(long, global = true)]
+    pub json: bool,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Work with documents in the workspace.
+    #[command(subcommand)]
+    Documents(documents::DocumentsCommand),
+    /// Work with collections of documents.
+    #[command(subcommand)]
+    Collections(collections::CollectionsCommand),
+    /// Upload files for documents to embed or link to.
+    #[command(subcommand)]
+    Attachments(attachments::AttachmentsCommand),
+    /// Manage the API key the CLI connects with.
+    #[command(subcommand)]
+    Auth(auth::AuthCommand),
+    /// Take an inventory of the workspace's collections and documents.
+    #[command(subcommand)]
+    Catalog(catalog::CatalogCommand),
+    /// Points links at a new host, e.g. after a domain migration, previewing the changes first.
+    RewriteLinks(links::RewriteLinksArgs),
+    /// Manage short local names for documents, which work anywhere a document ID does.
+    #[command(subcommand)]
+    Bookmark(bookmarks::BookmarkCommand),
+    /// Browses the workspace full-screen: collections in a sidebar, the selected collection's
+    /// documents next to them, and a preview of the selected document. `s` saves the document in
+    /// the current directory, `o` opens it in a web browser, and `q` quits.
+    Browse,
+    /// Manage the copies of documents kept on disk so reading them again is quick.
+    #[command(subcommand)]
+    Cache(cache::CacheCommand),
+    /// Print a script which sets up tab completion for a shell, including completing document and
+    /// collection IDs.
+    Completions(completion::CompletionsArgs),
+    /// Lists the ways the last word of a command line could be completed, for the scripts printed
+    /// by `completions`.
+    #[command(name = "__complete", hide = true)]
+    Complete(completion::CompleteArgs),
+    /// Summarize the documents created and updated recently, to print or publish as a new
+    /// document.
+    Digest(digest::DigestArgs),
+    /// Look through the workspace's audit log.
+    #[command(subcommand)]
+    Events(events::EventsCommand),
+    /// Keep track of exports and imports running in the background, and clean up old ones.
+    #[command(subcommand)]
+    FileOperations(file_operations::FileOperationsCommand),
+    /// Make up collections and documents for performance testing and workshop exercises, and
+    /// serve them from a mock getOutline server (or create them in a scratch workspace).
+    #[cfg(feature = "test-fixtures")]
+    GenerateFixtures(generate::GenerateArgs),
+    /// Look up the groups in the workspace and who is in them.
+    #[command(subcommand)]
+    Groups(groups::GroupsCommand),
+    /// Move content between workspaces configured as separate profiles.
+    #[command(subcommand)]
+    Migrate(migrate::MigrateCommand),
+    /// Check the workspace for things which need cleaning up.
+    #[command(subcommand)]
+    Report(report::ReportCommand),
+    /// Work with the links documents have been shared through.
+    #[command(subcommand)]
+    Shares(shares::SharesCommand),
+    /// Runs commands one after another at an interactive prompt, like `documents search runbook`
+    /// or just `search runbook`, reusing one connection to getOutline. Up and down go through
+    /// the commands run before, and `exit` or Ctrl-D leaves.
+    Shell,
+    /// Work with the people who have accounts in the workspace.
+    #[command(subcommand)]
+    Users(users::UsersCommand),
+    /// Work with the workspace as a whole.
+    #[command(subcommand)]
+    Workspace(workspace::WorkspaceCommand),
+}
+
+/// Environment variables which say the terminal is being recorded, so safe mode should be on.
+const RECORDING_HINTS: &[&str] = &["ASCIINEMA_REC"];
+
+/// Whether output is printed in safe mode, from `--no-secrets-output` or a recording being
+/// detected.
+static HIDE_SECRETS: AtomicBool = AtomicBool::new(false);
+
+/// `text` as it should be printed: with API keys, share links, and email addresses hidden in safe
+/// mode, and untouched otherwise.
+pub fn printable(text: &str) -> Cow<'_, str> {
+    if HIDE_SECRETS.load(Ordering::Relaxed) {
+        secrets::hide_secrets(text)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Opens connections to getOutline which share a command's request budget and timings.
+pub struct Connector {
+    meter: Arc<Meter>,
+    /// Where requests are timed, with `--timings`.
+    timings: Option<Arc<Timings>>,
+    /// The connections kept open between commands in the shell.
+    clients: Option<Rc<Clients>>,
+}
+
+impl Connector {
+    /// Connects to the workspace configured by `profile`, or the default profile.
+    pub fn connect(&self, profile: Option<&str>) -> Result<GetOutlineClient, ConfigError> {
+        let client = match &self.clients {
+            Some(clients) => clients.get(profile)?,
+            None => GetOutlineClient::from_config(profile)?,
+        }
+        .with_meter(self.meter.clone());
+        match &self.timings {
+            Some(timings) => client.with_timings(timings.clone()),
+            None => Ok(client),
+        }
+    }
+}
+
+/// Connections to getOutline for each profile used so far, so the shell only reads a profile's
+/// settings and connects once.
+#[derive(Default)]
+struct Clients(RefCell<HashMap<Option<String>, GetOutlineClient>>);
+
+impl Clients {
+    fn get(&self, profile: Option<&str>) -> Result<GetOutlineClient, ConfigError> {
+        let mut clients = self.0.borrow_mut();
+        let key = profile.map(str::to_string);
+        if let Some(client) = clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let client = GetOutlineClient::from_config(profile)?;
+        clients.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Closes every connection, so the next ones read the settings again.
+    fn forget(&self) {
+        self.0.borrow_mut().clear();
+    }
+}
+
+/// Flags for picking out one page of a long listing.
+#[derive(Args)]
+pub struct PaginationArgs {
+    /// Which page of results to show, starting from 1.
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    page: u32,
+    /// How many results to show on each page.
+    #[arg(long, default_value_t = 25, value_parser = clap::value_parser!(u32).range(1..=100))]
+    results_per_page: u32,
+}
+
+impl From<PaginationArgs> for Page {
+    fn from(args: PaginationArgs) -> Self {
+        Page {
+            offset: (args.page - 1) * args.results_per_page,
+            limit: args.results_per_page,
+        }
+    }
+}
+
+/// Flags for how a listing is printed.
+#[derive(Args)]
+pub struct ListingArgs {
+    /// How to print the results. `csv` starts with a header row and quotes any value with commas,
+    /// quotes, or line breaks in it. `--json` takes precedence over this.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t)]
+    output: ListingFormat,
+}
+
+impl From<ListingArgs> for ListingFormat {
+    fn from(args: ListingArgs) -> Self {
+        args.output
+    }
+}
+
+/// The names of the subcommands picked on the command line, e.g. `["documents", "save"]`.
+pub fn command_path(matches: &ArgMatches) -> Vec<String> {
+    let mut path = Vec::new();
+    let mut matches = matches;
+    while let Some((name, sub_matches)) = matches.subcommand() {
+        path.push(name.to_string());
+        matches = sub_matches;
+    }
+
+    path
+}
+
+/// Runs the command selected on the command line, after checking the profile's policy allows it.
+///
+/// `command_path` is the list of subcommand names which were picked, from [command_path].
+pub fn exec_command(args: CLIArgs, command_path: &[String]) -> anyhow::Result<()> {
+    run(args, command_path, None)
+}
+
+/// Runs a command like [exec_command] does, connecting through `clients` if the shell has some
+/// open already.
+fn run(args: CLIArgs, command_path: &[String], clients: Option<Rc<Clients>>) -> anyhow::Result<()> {
+    // The script is the same whatever the settings are, so it's printed without reading them
+    let command = match args.command {
+        Command::Completions(args) => return completion::print_script(args),
+        command => command,
+    };
+
+    // Before reading any settings, so what happened reading the `.env` file is logged
+    let verbosity = Verbosity {
+        verbose: args.verbose,
+        quiet: args.quiet,
+    };
+    logging::start(verbosity);
+
+    let recording = RECORDING_HINTS
+        .iter()
+        .any(|var| std::env::var_os(var).is_some());
+    let hide_secrets = args.no_secrets_output || config::is_on(None, "NO_SECRETS_OUTPUT");
+    HIDE_SECRETS.store(hide_secrets || recording, Ordering::Relaxed);
+
+    let profile = args.profile.or_else(|| config::get(None, "PROFILE"));
+    let profile = profile.as_deref();
+    if let Some(allowed) = config::get(profile, policy::ALLOWED_COMMANDS) {
+        policy::CommandPolicy::parse(&allowed)
+            .check(command_path)
+            .with_context(|| {
+                format!(
+                    "blocked by {}",
+                    config::var_name(profile, policy::ALLOWED_COMMANDS)
+                )
+            })?;
+    }
+
+    let connector = Connector {
+        meter: Arc::new(Meter::new(Budget {
+            max_requests: args.max_requests,
+            max_bytes: args.max_bytes,
+            max_duration: args.max_duration,
+        })),
+        timings: args.timings.then(Arc::default),
+        clients,
+    };
+    let client = || connector.connect(profile);
+    let output = Output {
+        plain: args.plain,
+        progress: args.progress,
+        json: args.json,
+    };
+    let result = match command {
+        Command::Documents(cmd) => documents::exec(cmd, &client()?, profile, output),
+        Command::Collections(cmd) => collections::exec(cmd, &client()?, output),
+        Command::RewriteLinks(args) => links::exec(args, &client()?, profile, output),
+        Command::Attachments(cmd) => attachments::exec(cmd, &client()?, profile, output),
+        Command::Auth(cmd) => auth::exec(cmd, &client()?, profile, output),
+        Command::Catalog(cmd) => catalog::exec(cmd, &client()?, output),
+        Command::Bookmark(cmd) => bookmarks::exec(cmd, profile, output),
+        Command::Browse => tui::exec(&client()?, profile, output),
+        Command::Cache(cmd) => cache::exec(cmd, profile, output),
+        Command::Completions(_) => unreachable!("the script is printed before reading settings"),
+        Command::Complete(args) => completion::complete(args, &connector, profile),
+        Command::Digest(args) => digest::exec(args, &client()?, output),
+        Command::Events(cmd) => events::exec(cmd, &client()?, profile, output),
+        Command::FileOperations(cmd) => file_operations::exec(cmd, &client()?, output),
+        #[cfg(feature = "test-fixtures")]
+        Command::GenerateFixtures(args) => generate::exec(args, &connector, profile, output),
+        Command::Groups(cmd) => groups::exec(cmd, &client()?, output),
+        Command::Migrate(cmd) => migrate::exec(cmd, &connector, output),
+        Command::Report(cmd) => report::exec(cmd, &client()?, output),
+        Command::Shares(cmd) => shares::exec(cmd, &client()?, profile, output),
+        Command::Shell if connector.clients.is_some() => Err(errors::InvalidArguments {
+            message: "the shell is already running".to_string(),
+        }
+        .into()),
+        Command::Shell => shell::exec(profile, output, verbosity),
+        Command::Users(cmd) => users::exec(cmd, &client()?, output),
+        Command::Workspace(cmd) => workspace::exec(cmd, &client()?, output),
+    };
+
+    // The timings are most useful when a command failed or was slow, so they're printed either way
+    if let Some(timings) = &connector.timings {
+        timings::report(&timings.requests());
+    }
+    result
+}
+
+/// Accepts a number of bytes, optionally with a `K`, `M`, or `G` suffix for kibibytes, mebibytes,
+/// or gibibytes.
+pub(super) fn byte_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.char_indices().last() {
+        Some((at, 'k' | 'K')) => (&value[..at], 1 << 10),
+        Some((at, 'm' | 'M')) => (&value[..at], 1 << 20),
+        Some((at, 'g' | 'G')) => (&value[..at], 1 << 30),
+        _ => (value, 1),
+    };
+
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|count| count.checked_mul(multiplier))
+        .ok_or_else(|| format!("{value} isn't a size like 1048576, 500K, or 20M"))
+}
+
+/// Accepts a length of time in seconds, optionally with an `s`, `m`, `h`, `d`, or `w` suffix for
+/// seconds, minutes, hours, days, or weeks.
+pub(super) fn duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.char_indices().last() {
+        Some((at, 's')) => (&value[..at], 1),
+        Some((at, 'm')) => (&value[..at], 60),
+        Some((at, 'h')) => (&value[..at], 60 * 60),
+        Some((at, 'd')) => (&value[..at], 24 * 60 * 60),
+        Some((at, 'w')) => (&value[..at], 7 * 24 * 60 * 60),
+        _ => (value, 1),
+    };
+
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|count| count.checked_mul(multiplier))
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("{value} isn't a length of time like 90s, 10m, or 2h"))
+}
+
+/// Asks the user a yes/no question on the terminal, treating anything other than "y" or "yes"
+/// (including a closed stdin) as "no".
+fn confirm(prompt: &str) -> io::Result<bool> {
+    let answer = ask(&format!("{prompt} [y/N]"))?.to_ascii_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
+
+/// Asks the user to type something in on the terminal, returning what they typed without the
+/// surrounding whitespace (or nothing if stdin is closed).
+///
+/// The prompt goes to stderr, so it doesn't end up in the middle of `--json` output.
+fn ask(prompt: &str) -> io::Result<String> {
+    eprint!("{prompt} ");
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
@@ -0,0 +1,116 @@
+//! How commands print what they did: as text for people, or as JSON for scripts with `--json`.
+//!
+//! With `--json`, each command prints exactly one JSON value on stdout once it's done: an array
+//! for listings, and an object for everything else. Messages along the way, like the progress of
+//! an export, go to stderr or aren't printed, so stdout can be parsed as a whole.
+
+pub(super) mod table;
+
+use std::fmt::Display;
+use std::io::{self, ErrorKind, Write};
+use std::time::Duration;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use super::errors::{ErrorCode, StdoutError};
+use super::progress::ProgressFormat;
+
+/// How the global flags ask for output to be printed.
+#[derive(Clone, Copy)]
+pub(super) struct Output {
+    /// `--plain`
+    pub(super) plain: bool,
+    /// `--progress`
+    pub(super) progress: ProgressFormat,
+    /// `--json`
+    pub(super) json: bool,
+}
+
+impl Output {
+    /// Prints a message about how a command is getting on, like `Exporting...`. It goes to stdout
+    /// as usual, or to stderr with `--json`, where stdout only has the result.
+    pub(super) fn status(&self, message: impl Display) -> Result<(), StdoutError> {
+        if self.json {
+            eprintln!("{message}");
+        } else {
+            println!("{message}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes `text` to stdout, for the `println!` and `print!` macros in [super].
+///
+/// Unlike the standard macros, which panic, this returns an error if stdout can't be written to,
+/// so the command stops and cleans up as it would for any other error. If whatever was reading
+/// stdout has gone away, that's [StdoutError::Closed], and the CLI then exits quietly.
+pub(super) fn write_stdout(text: &str) -> Result<(), StdoutError> {
+    let mut stdout = io::stdout().lock();
+    // Flushing straight away finds out about a closed pipe now, rather than on some later write
+    match stdout
+        .write_all(text.as_bytes())
+        .and_then(|()| stdout.flush())
+    {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => Err(StdoutError::Closed),
+        Err(err) => Err(StdoutError::Write(err)),
+    }
+}
+
+/// Prints `value` as JSON on stdout, for `--json`.
+pub(super) fn print_json(value: &impl Serialize) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(value).context("could not encode the output")?;
+    println!("{json}");
+    Ok(())
+}
+
+/// `bytes` in bytes, kibibytes, or mebibytes, whichever reads best, like `1.5M`.
+pub(super) fn size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes}B"),
+        1024..1_048_576 => format!("{:.1}K", bytes as f64 / 1024.0),
+        _ => format!("{:.1}M", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// `duration` in its largest whole unit, like `3d` or `5m`.
+pub(super) fn age(duration: Duration) -> String {
+    match duration.as_secs() {
+        seconds @ 0..60 => format!("{seconds}s"),
+        seconds @ 60..3600 => format!("{}m", seconds / 60),
+        seconds @ 3600..86_400 => format!("{}h", seconds / 3600),
+        seconds => format!("{}d", seconds / 86_400),
+    }
+}
+
+/// A failed command's error, as it's printed with `--json`.
+#[derive(Serialize)]
+struct ErrorOutput<'a> {
+    error: ErrorDetails<'a>,
+}
+
+#[derive(Serialize)]
+struct ErrorDetails<'a> {
+    code: &'a str,
+    message: &'a str,
+}
+
+/// Prints why a command failed on stderr, as `Error [<code>]: <message>`, or with `json` as an
+/// object like `{"error": {"code": "E_NOT_FOUND", "message": "..."}}`.
+pub fn print_error(err: &anyhow::Error, code: ErrorCode, json: bool) {
+    let message = format!("{err:#}");
+    if json {
+        let output = ErrorOutput {
+            error: ErrorDetails {
+                code: code.as_str(),
+                message: &message,
+            },
+        };
+        let json = serde_json::to_string(&output).expect("error output always serializes");
+        eprintln!("{json}");
+    } else {
+        eprintln!("Error [{}]: {message}", code.as_str());
+    }
+}
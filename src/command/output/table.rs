@@ -0,0 +1,231 @@
+//! Listings like `users list`, printed as a table with a header and lined-up columns.
+//!
+//! Tables are only drawn on a terminal, where the columns which can be cut short are shortened to
+//! fit its width. With `--plain`, or when stdout is piped somewhere, each row is printed as
+//! tab-separated fields without a header instead, which screen readers and tools like `cut` handle
+//! better. `--output csv` prints CSV with a header row either way.
+
+use std::io::{self, IsTerminal};
+
+use clap::ValueEnum;
+
+use super::super::errors::StdoutError;
+use super::Output;
+use crate::logic::csv::csv_record;
+
+/// The spaces between columns.
+const GAP: usize = 2;
+/// Columns are never cut shorter than this, or their header, whichever is longer.
+const MIN_WIDTH: usize = 8;
+
+/// How a listing is printed, as picked with `--output`.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum ListingFormat {
+    /// A table on a terminal, or tab-separated lines when piped somewhere.
+    #[default]
+    Table,
+    /// Comma-separated values with a header row, for spreadsheets.
+    Csv,
+}
+
+/// A column of a [Table], with its header.
+#[derive(Debug, Clone, Copy)]
+pub enum Column {
+    /// Always shown in full, for things like IDs and timestamps which get copied into other
+    /// commands.
+    Full(&'static str),
+    /// Cut short with `…` when the table is wider than the terminal, for things like titles and
+    /// names.
+    Shrink(&'static str),
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Full(header) | Column::Shrink(header) => header,
+        }
+    }
+}
+
+/// Rows of a listing to be printed together, so the columns can be lined up.
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(columns: impl Into<Vec<Column>>) -> Self {
+        Self {
+            columns: columns.into(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Adds a row, with one value for each column.
+    pub fn row<const N: usize>(&mut self, cells: [String; N]) {
+        debug_assert_eq!(N, self.columns.len(), "a row has a value per column");
+        self.rows.push(cells.into());
+    }
+
+    /// Prints the table on stdout in `format`. Tables are lined up to fit the terminal, or printed
+    /// as tab-separated rows if there's no terminal to fit or `output` asks for plain text.
+    pub fn print(&self, output: Output, format: ListingFormat) -> Result<(), StdoutError> {
+        if let ListingFormat::Csv = format {
+            let headers: Vec<_> = self.columns.iter().map(|column| column.header()).collect();
+            println!("{}", csv_record(&headers));
+            for row in &self.rows {
+                println!("{}", csv_record(row));
+            }
+            return Ok(());
+        }
+        if output.plain || !io::stdout().is_terminal() {
+            for row in &self.rows {
+                println!("{}", row.join("\t"));
+            }
+            return Ok(());
+        }
+
+        let natural: Vec<_> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                self.rows
+                    .iter()
+                    .map(|row| row[index].chars().count())
+                    .chain([column.header().chars().count()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let widths = match terminal_width() {
+            Some(available) => fit(&self.columns, natural, available),
+            None => natural,
+        };
+
+        let headers = self
+            .columns
+            .iter()
+            .map(|column| column.header().to_string());
+        println!("{}", aligned(&headers.collect::<Vec<_>>(), &widths));
+        for row in &self.rows {
+            println!("{}", aligned(row, &widths));
+        }
+
+        Ok(())
+    }
+}
+
+/// Shrinks the widest [Column::Shrink] columns a character at a time until the table fits in
+/// `available` characters, or they're all as short as they can go.
+fn fit(columns: &[Column], mut widths: Vec<usize>, available: usize) -> Vec<usize> {
+    let minimum = |index: usize| MIN_WIDTH.max(columns[index].header().chars().count());
+    let mut total = widths.iter().sum::<usize>() + GAP * widths.len().saturating_sub(1);
+
+    while total > available {
+        let widest = columns
+            .iter()
+            .enumerate()
+            .filter(|(index, column)| {
+                matches!(column, Column::Shrink(_)) && widths[*index] > minimum(*index)
+            })
+            .max_by_key(|(index, _)| widths[*index]);
+        let Some((index, _)) = widest else {
+            break;
+        };
+        widths[index] -= 1;
+        total -= 1;
+    }
+
+    widths
+}
+
+/// One line of a table: `cells` padded to `widths`, cutting short any which don't fit.
+fn aligned(cells: &[String], widths: &[usize]) -> String {
+    let line: Vec<_> = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, &width)| {
+            let cell = if cell.chars().count() > width {
+                let kept: String = cell.chars().take(width.saturating_sub(1)).collect();
+                format!("{kept}…")
+            } else {
+                cell.clone()
+            };
+            format!("{cell:<width$}")
+        })
+        .collect();
+    line.join(&" ".repeat(GAP)).trim_end().to_string()
+}
+
+/// How many characters fit on a line of the terminal: `$COLUMNS` if it's set, or else what the
+/// terminal says.
+fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+    {
+        return Some(columns);
+    }
+
+    #[cfg(unix)]
+    if let Ok(size) = rustix::termios::tcgetwinsize(io::stdout()) {
+        if size.ws_col > 0 {
+            return Some(usize::from(size.ws_col));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|cell| cell.to_string()).collect()
+    }
+
+    #[test]
+    fn only_shrink_columns_are_shortened_widest_first() {
+        let columns = [
+            Column::Full("ID"),
+            Column::Shrink("Title"),
+            Column::Shrink("Owner"),
+        ];
+
+        // 10 + 30 + 20 and two gaps of 2 is 64, so 14 characters have to go: the title is cut
+        // down to the owner's width first, and then they're both cut
+        assert_eq!(fit(&columns, vec![10, 30, 20], 50), [10, 18, 18]);
+        assert_eq!(fit(&columns, vec![10, 30, 20], 40), [10, 13, 13]);
+    }
+
+    #[test]
+    fn columns_stop_shrinking_at_their_minimum_width() {
+        let columns = [Column::Shrink("Title"), Column::Shrink("Last accessed")];
+
+        // Neither goes below MIN_WIDTH, or its header if that's longer
+        assert_eq!(fit(&columns, vec![30, 30], 10), [MIN_WIDTH, 13]);
+    }
+
+    #[test]
+    fn tables_of_full_columns_wider_than_the_terminal_are_left_as_they_are() {
+        let columns = [
+            Column::Full("ID"),
+            Column::Shrink("Title"),
+            Column::Full("URL"),
+        ];
+
+        assert_eq!(fit(&columns, vec![36, 20, 40], 30), [36, MIN_WIDTH, 40]);
+        assert_eq!(fit(&columns, vec![36, 20, 40], 200), [36, 20, 40]);
+    }
+
+    #[test]
+    fn cells_which_dont_fit_are_cut_short_with_an_ellipsis() {
+        assert_eq!(
+            aligned(&cells(&["Deploy runbook", "Ada", "x"]), &[8, 5, 1]),
+            "Deploy …  Ada    x"
+        );
+        assert_eq!(aligned(&cells(&["Café menus", "é"]), &[5, 3]), "Café…  é");
+    }
+}
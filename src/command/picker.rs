@@ -0,0 +1,193 @@
+//! Picking one of a list of items by typing a few letters of it, for commands run on a terminal
+//! without saying which document they're for.
+//!
+//! The list is drawn on stderr below the line the cursor is on, and cleared away again once
+//! something is picked, so it doesn't get in the way of what the command prints.
+
+use std::io::{self, Write};
+
+use super::terminal::{self, Key, Keys, RawMode};
+use crate::logic::fuzzy::{self, FuzzyMatch};
+
+/// The most items listed at once.
+const MAX_ROWS: usize = 10;
+
+/// Lets someone pick one of `items` by typing part of it after `prompt`. Returns the index of the
+/// item picked, or `None` if they gave up with Ctrl-C.
+pub(super) fn pick(prompt: &str, items: &[String]) -> io::Result<Option<usize>> {
+    let _raw = RawMode::enable()?;
+    let mut keys = Keys(io::stdin().lock());
+    let mut screen = io::stderr().lock();
+    let (width, height) = terminal::size(io::stderr());
+    let mut picker = Picker {
+        items,
+        query: String::new(),
+        matches: Vec::new(),
+        selected: 0,
+        scroll: 0,
+        // One row each for the prompt and the count
+        rows: MAX_ROWS.min(height.saturating_sub(2)).max(1),
+    };
+    picker.filter();
+
+    // Makes room for the list, scrolling the terminal up if the cursor is near the bottom
+    write!(
+        screen,
+        "{}\x1b[{}A",
+        "\n".repeat(picker.rows + 1),
+        picker.rows + 1
+    )?;
+    let picked = loop {
+        picker.draw(&mut screen, prompt, width)?;
+        let Some(key) = keys.next()? else {
+            break None;
+        };
+        match key {
+            Key::Enter => {
+                if let Some((index, _)) = picker.matches.get(picker.selected) {
+                    break Some(*index);
+                }
+            }
+            Key::Ctrl('c' | 'g') => break None,
+            Key::Ctrl('d') if picker.query.is_empty() => break None,
+            Key::Up | Key::Ctrl('p') | Key::BackTab => picker.select(-1),
+            Key::Down | Key::Ctrl('n') | Key::Tab => picker.select(1),
+            Key::PageUp => picker.select(-signed(picker.rows)),
+            Key::PageDown => picker.select(signed(picker.rows)),
+            Key::Backspace => {
+                picker.query.pop();
+                picker.filter();
+            }
+            Key::Ctrl('u') => {
+                picker.query.clear();
+                picker.filter();
+            }
+            Key::Ctrl('w') => {
+                let kept = picker
+                    .query
+                    .trim_end()
+                    .rfind(' ')
+                    .map_or(0, |space| space + 1);
+                picker.query.truncate(kept);
+                picker.filter();
+            }
+            Key::Char(c) => {
+                picker.query.push(c);
+                picker.filter();
+            }
+            _ => {}
+        }
+    };
+
+    screen.write_all(b"\r\x1b[J")?;
+    screen.flush()?;
+    Ok(picked)
+}
+
+/// The state of the list being picked from.
+struct Picker<'a> {
+    items: &'a [String],
+    query: String,
+    /// The items the query matches, best first.
+    matches: Vec<(usize, FuzzyMatch)>,
+    /// Which of `matches` would be picked.
+    selected: usize,
+    /// Which of `matches` is listed first.
+    scroll: usize,
+    /// How many items are listed at once.
+    rows: usize,
+}
+
+impl Picker<'_> {
+    /// Matches the items against the query again, selecting the best match.
+    fn filter(&mut self) {
+        self.matches = fuzzy::fuzzy_rank(&self.query, self.items.iter().map(String::as_str));
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    /// Moves the selection `by` items down (or up, if it's negative), keeping it in view.
+    fn select(&mut self, by: isize) {
+        let last = self.matches.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(by).min(last);
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + self.rows {
+            self.scroll = self.selected + 1 - self.rows;
+        }
+    }
+
+    /// Redraws the prompt, how many items match, and the matching items below them, leaving the
+    /// cursor after the query.
+    fn draw(&self, screen: &mut impl Write, prompt: &str, width: usize) -> io::Result<()> {
+        let input = format!("{prompt}{}", self.query);
+        let input = clean(&input, width.saturating_sub(1));
+        write!(screen, "\r{input}\x1b[K")?;
+        write!(
+            screen,
+            "\r\n\x1b[2m  {}/{}\x1b[0m\x1b[K",
+            self.matches.len(),
+            self.items.len()
+        )?;
+        for row in 0..self.rows {
+            screen.write_all(b"\r\n")?;
+            if let Some((index, found)) = self.matches.get(self.scroll + row) {
+                let selected = self.scroll + row == self.selected;
+                self.draw_item(screen, &self.items[*index], found, selected, width)?;
+            }
+            screen.write_all(b"\x1b[K")?;
+        }
+
+        write!(screen, "\x1b[{}A\r", self.rows + 1)?;
+        let column = input.chars().count();
+        if column > 0 {
+            write!(screen, "\x1b[{column}C")?;
+        }
+        screen.flush()
+    }
+
+    /// Draws one item, cut short to fit in `width` columns, with the characters the query matched
+    /// in bold.
+    fn draw_item(
+        &self,
+        screen: &mut impl Write,
+        item: &str,
+        found: &FuzzyMatch,
+        selected: bool,
+        width: usize,
+    ) -> io::Result<()> {
+        let available = width.saturating_sub(3);
+        let text = clean(item, available);
+        let mut line = String::from(if selected { "\x1b[7m> " } else { "  " });
+        let mut positions = found.positions.iter().peekable();
+        for (index, c) in text.chars().enumerate() {
+            if positions.next_if_eq(&&index).is_some() {
+                line.push_str(&format!("\x1b[1m{c}\x1b[22m"));
+            } else {
+                line.push(c);
+            }
+        }
+        if selected {
+            line.push_str("\x1b[0m");
+        }
+        screen.write_all(line.as_bytes())
+    }
+}
+
+/// `text` with anything which could move the cursor or change colors turned into spaces, and cut
+/// short with `…` if it's longer than `width` characters.
+fn clean(text: &str, width: usize) -> String {
+    let mut cleaned: String = text
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    if cleaned.chars().count() > width {
+        cleaned = cleaned.chars().take(width.saturating_sub(1)).collect();
+        cleaned.push('…');
+    }
+    cleaned
+}
+
+fn signed(rows: usize) -> isize {
+    isize::try_from(rows).unwrap_or(isize::MAX)
+}
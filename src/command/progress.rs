@@ -0,0 +1,162 @@
+//! Saving how far an `--apply` run of a bulk command got, so one which stopped part way (for
+//! example at `--max-requests`) can be continued with `--resume`, and reporting how far it has got
+//! as it goes.
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use super::errors;
+use crate::logic::progress::Progress;
+use crate::state;
+
+/// The progress of one bulk operation, and where it's kept.
+pub(super) struct Checkpoint {
+    file_name: String,
+    progress: Progress,
+}
+
+impl Checkpoint {
+    /// Starts keeping track of `operation`, a description of the command and the arguments which
+    /// pick out what it changes. With `resume`, the documents finished by the last run of the
+    /// same operation are counted as done.
+    ///
+    /// Each command keeps one saved checkpoint per profile, so starting it again without
+    /// `resume` replaces it once that run saves.
+    pub(super) fn start(
+        profile: Option<&str>,
+        command: &str,
+        operation: String,
+        resume: bool,
+    ) -> anyhow::Result<Self> {
+        let file_name = state::file_name(profile, &format!("{command}-progress"));
+        let progress = if resume {
+            Progress::resume(state::load(&file_name), &operation)?
+        } else {
+            Progress::new(&operation)
+        };
+
+        Ok(Self {
+            file_name,
+            progress,
+        })
+    }
+
+    pub(super) fn is_done(&self, document_id: &str) -> bool {
+        self.progress.is_done(document_id)
+    }
+
+    pub(super) fn mark_done(&mut self, document_id: &str) {
+        self.progress.mark_done(document_id);
+    }
+
+    /// Saves how far the operation got after it failed with `err`, and says how to carry on.
+    pub(super) fn save_after(&self, err: anyhow::Error) -> anyhow::Error {
+        if let Err(save_err) = state::save(&self.file_name, &self.progress) {
+            return err.context(format!(
+                "stopped part way, and could not save the progress to resume from: {save_err}"
+            ));
+        }
+
+        err.context(format!(
+            "stopped after finishing {} documents, run the same command with --resume to continue",
+            self.progress.done_count()
+        ))
+    }
+
+    /// Forgets the saved progress once the operation has finished. Failing to only warrants a
+    /// warning, since the command itself worked.
+    pub(super) fn finish(self) {
+        if let Err(err) = state::remove(&self.file_name) {
+            tracing::warn!(
+                "could not delete the finished progress in {}: {err}",
+                self.file_name
+            );
+        }
+    }
+}
+
+/// How bulk commands report their progress, besides what they print for people to read.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum ProgressFormat {
+    /// Only the usual output.
+    #[default]
+    Human,
+    /// Also a JSON object per line on stderr for each document started, finished, or failed, for
+    /// GUIs and CI wrappers to show their own progress with.
+    Json,
+}
+
+/// One line of `--progress json` output.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+enum ProgressEvent<'a> {
+    Started {
+        operation: &'a str,
+    },
+    ItemCompleted {
+        id: &'a str,
+        title: &'a str,
+    },
+    ItemFailed {
+        id: &'a str,
+        code: &'static str,
+        error: String,
+    },
+    Finished {
+        completed: usize,
+        failed: usize,
+    },
+}
+
+/// Reports each document a bulk operation gets through in the chosen [ProgressFormat].
+pub(super) struct Reporter {
+    format: ProgressFormat,
+    completed: usize,
+    failed: usize,
+}
+
+impl Reporter {
+    /// Reports that `operation` (like [Checkpoint::start]'s) has started.
+    pub(super) fn start(format: ProgressFormat, operation: &str) -> Self {
+        let reporter = Self {
+            format,
+            completed: 0,
+            failed: 0,
+        };
+        reporter.emit(&ProgressEvent::Started { operation });
+        reporter
+    }
+
+    pub(super) fn completed(&mut self, id: &str, title: &str) {
+        self.completed += 1;
+        self.emit(&ProgressEvent::ItemCompleted { id, title });
+    }
+
+    /// Reports that working on document `id` failed with `err`, and hands `err` back.
+    pub(super) fn failed(&mut self, id: &str, err: anyhow::Error) -> anyhow::Error {
+        self.failed += 1;
+        self.emit(&ProgressEvent::ItemFailed {
+            id,
+            code: errors::classify(&err).as_str(),
+            error: format!("{err:#}"),
+        });
+        err
+    }
+
+    /// Reports that the operation is over, whether or not it got through everything.
+    pub(super) fn finish(self) {
+        self.emit(&ProgressEvent::Finished {
+            completed: self.completed,
+            failed: self.failed,
+        });
+    }
+
+    fn emit(&self, event: &ProgressEvent) {
+        if let ProgressFormat::Json = self.format {
+            eprintln!(
+                "{}",
+                serde_json::to_string(event).expect("progress events always serialize")
+            );
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::Context;
+use clap::Args;
+use regex::Regex;
+use serde::Serialize;
+
+use super::output::{print_json, Output};
+use super::progress::{Checkpoint, Reporter};
+use super::{confirm, diff};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::documents::{self, SearchQuery};
+use crate::logic::replace as logic;
+
+#[derive(Args)]
+pub struct ReplaceArgs {
+    /// A search query selecting which documents to look at.
+    #[arg(long)]
+    matching: String,
+    /// The regular expression to find.
+    #[arg(long)]
+    find: String,
+    /// The text to replace each match with. Capture groups can be referenced as `$1` or `${name}`.
+    #[arg(long)]
+    replace: String,
+    /// Only looks at documents in this collection.
+    #[arg(long)]
+    collection: Option<String>,
+    /// Updates the documents. Without this, the changes are only previewed.
+    #[arg(long)]
+    apply: bool,
+    /// Asks before updating each document. Only applies with `--apply`.
+    #[arg(long, requires = "apply")]
+    confirm: bool,
+    /// Skips the documents finished by an earlier run of the same replacement which stopped part
+    /// way.
+    #[arg(long, requires = "apply")]
+    resume: bool,
+}
+
+impl ReplaceArgs {
+    /// The arguments which decide what gets changed, to tell one replacement's progress from
+    /// another's.
+    fn operation(&self) -> String {
+        let mut operation = format!(
+            "replace --matching {} --find {} --replace {}",
+            self.matching, self.find, self.replace
+        );
+        if let Some(collection) = &self.collection {
+            operation.push_str(&format!(" --collection {collection}"));
+        }
+        operation
+    }
+}
+
+/// What a run of the command has got through so far.
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Tally {
+    documents_found: usize,
+    documents_updated: usize,
+    /// Each document which would change, for `--json`.
+    documents: Vec<DocumentChange>,
+}
+
+/// How a document would change, as printed with `--json`.
+#[derive(Serialize)]
+struct DocumentChange {
+    id: String,
+    title: String,
+    matches: usize,
+    /// The change as a unified diff.
+    diff: String,
+    /// Whether the document was updated with the change.
+    updated: bool,
+}
+
+pub fn exec(
+    args: ReplaceArgs,
+    client: &GetOutlineClient,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    let find = Regex::new(&args.find).context("--find is not a valid regular expression")?;
+    let mut checkpoint = Checkpoint::start(profile, "replace", args.operation(), args.resume)?;
+    let mut reporter = Reporter::start(output.progress, &args.operation());
+    let mut tally = Tally::default();
+    let result = replace_each(
+        &args,
+        &find,
+        client,
+        output,
+        &mut checkpoint,
+        &mut reporter,
+        &mut tally,
+    );
+    reporter.finish();
+    if let Err(err) = result {
+        return Err(if args.apply {
+            checkpoint.save_after(err)
+        } else {
+            err
+        });
+    }
+
+    if args.apply {
+        checkpoint.finish();
+    }
+
+    if output.json {
+        return print_json(&tally);
+    }
+    if tally.documents_found == 0 {
+        println!("No documents would change.");
+    } else if args.apply {
+        println!(
+            "Updated {} of {} documents.",
+            tally.documents_updated, tally.documents_found
+        );
+    } else {
+        println!(
+            "Dry run: {} documents would change. Re-run with --apply to update them.",
+            tally.documents_found
+        );
+    }
+
+    Ok(())
+}
+
+/// Goes through the documents matching `--matching` one at a time, so a run which stops part way
+/// has still finished with some of them.
+fn replace_each(
+    args: &ReplaceArgs,
+    find: &Regex,
+    client: &GetOutlineClient,
+    output: Output,
+    checkpoint: &mut Checkpoint,
+    reporter: &mut Reporter,
+    tally: &mut Tally,
+) -> anyhow::Result<()> {
+    let query = SearchQuery {
+        text: &args.matching,
+        collection_id: args.collection.as_deref(),
+    };
+
+    for summary in documents::search_workspace(client, &query)? {
+        if checkpoint.is_done(&summary.id) {
+            continue;
+        }
+        let (id, title) = (summary.id.clone(), summary.title.clone());
+        let Some(replacement) = logic::plan_replacement(client, summary, find, &args.replace)
+            .map_err(|err| reporter.failed(&id, err.into()))?
+        else {
+            checkpoint.mark_done(&id);
+            reporter.completed(&id, &title);
+            continue;
+        };
+
+        let document = &replacement.document;
+        tally.documents_found += 1;
+        let render = |color| {
+            diff::render_unified(
+                &replacement.original,
+                &replacement.replaced,
+                &format!("{} (current)", document.title),
+                &format!("{} (replaced)", document.title),
+                color,
+            )
+        };
+        if output.json {
+            tally.documents.push(DocumentChange {
+                id: document.id.clone(),
+                title: document.title.clone(),
+                matches: replacement.matches,
+                diff: render(false),
+                updated: false,
+            });
+        } else {
+            println!(
+                "\"{}\" ({}): {} matches",
+                document.title, document.id, replacement.matches
+            );
+            print!("{}", render(diff::use_color(output.plain)));
+        }
+
+        if !args.apply {
+            reporter.completed(&id, &title);
+            continue;
+        }
+        if args.confirm && !confirm(&format!("Update \"{}\"?", document.title))? {
+            // Not marked as done, so --resume asks about it again
+            output.status(format_args!("Skipped \"{}\"", document.title))?;
+            reporter.completed(&id, &title);
+            continue;
+        }
+
+        logic::apply_replacement(client, &replacement)
+            .map_err(|err| reporter.failed(&id, err.into()))?;
+        output.status(format_args!("Updated \"{}\"", document.title))?;
+        tally.documents_updated += 1;
+        if let Some(updated) = tally.documents.last_mut() {
+            updated.updated = true;
+        }
+        checkpoint.mark_done(&id);
+        reporter.completed(&id, &title);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use clap::Subcommand;
+use serde_json::json;
+
+use super::errors::StdoutError;
+use super::output::table::{Column, Table};
+use super::output::{print_json, size, Output};
+use super::{confirm, ListingArgs};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::attachments as logic;
+
+#[derive(Subcommand)]
+pub enum ReportCommand {
+    /// Lists the attachments no document refers to anymore, so the storage they take up can be
+    /// reclaimed.
+    ///
+    /// Every document the API key can read is checked, drafts included, so use an admin's key:
+    /// attachments only used by documents the key can't see are listed too.
+    OrphanedAttachments {
+        /// Deletes the orphaned attachments once they're listed. This can't be undone.
+        #[arg(long)]
+        delete: bool,
+        /// Deletes them without asking first.
+        #[arg(long, requires = "delete")]
+        yes: bool,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+}
+
+pub fn exec(cmd: ReportCommand, client: &GetOutlineClient, output: Output) -> anyhow::Result<()> {
+    match cmd {
+        ReportCommand::OrphanedAttachments {
+            delete,
+            yes,
+            listing,
+        } => orphaned_attachments(client, delete, yes, listing, output),
+    }
+}
+
+fn orphaned_attachments(
+    client: &GetOutlineClient,
+    delete: bool,
+    yes: bool,
+    listing: ListingArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    // Messages go to stderr, leaving stdout for the listing, so it can be saved as CSV
+    eprintln!("Checking every attachment against every document...");
+    let report = logic::find_orphaned_attachments(client)?;
+    if output.json && !delete {
+        return print_json(&report.orphaned);
+    }
+    if !output.json {
+        print_report(&report, listing, output)?;
+    }
+
+    if delete && !report.orphaned.is_empty() {
+        let count = match report.orphaned.len() {
+            1 => "1 attachment".to_string(),
+            count => format!("{count} attachments"),
+        };
+        let prompt =
+            format!("Delete {count}? Any links to them will break, and this can't be undone.");
+        if !yes && !confirm(&prompt)? {
+            if output.json {
+                return print_json(&json!({ "attachments": report.orphaned, "deleted": false }));
+            }
+            eprintln!("Aborted, nothing was deleted.");
+            return Ok(());
+        }
+
+        for attachment in &report.orphaned {
+            logic::delete_attachment(client, &attachment.id)?;
+        }
+        if !output.json {
+            eprintln!("Deleted {count}, freeing {}.", size(report.bytes()));
+        }
+    }
+
+    if output.json {
+        return print_json(&json!({ "attachments": report.orphaned, "deleted": true }));
+    }
+
+    Ok(())
+}
+
+/// Prints the orphaned attachments as a listing, followed by how much space they take up.
+fn print_report(
+    report: &logic::OrphanedAttachments,
+    listing: ListingArgs,
+    output: Output,
+) -> Result<(), StdoutError> {
+    if report.orphaned.is_empty() {
+        eprintln!(
+            "All {} attachments are used by at least one of the {} documents.",
+            report.attachments, report.documents
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("ID"),
+        Column::Shrink("Name"),
+        Column::Full("Size"),
+        Column::Full("Uploaded"),
+        Column::Full("Uploaded for"),
+    ]);
+    for attachment in &report.orphaned {
+        table.row([
+            attachment.id.clone(),
+            attachment.name.clone(),
+            attachment.size.map_or("unknown".to_string(), size),
+            attachment
+                .created_at
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            attachment
+                .document_id
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+        ]);
+    }
+    table.print(output, listing.into())?;
+    eprintln!(
+        "{} of {} attachments aren't used by any of the {} documents, taking up {}.",
+        report.orphaned.len(),
+        report.attachments,
+        report.documents,
+        size(report.bytes())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::{ArgGroup, Subcommand};
+use serde_json::json;
+
+use super::documents::{self, Recent};
+use super::output::table::{Column, Table};
+use super::output::{age, print_json, Output};
+use super::{ListingArgs, PaginationArgs};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::shares as logic;
+
+#[derive(Subcommand)]
+pub enum SharesCommand {
+    /// Lists share links with the document they're for, who created them, and when they were last
+    /// followed.
+    List {
+        #[command(flatten)]
+        pagination: PaginationArgs,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Lists every published share link, which anyone with the link can follow, oldest first,
+    /// flagging the ones old enough to be worth checking they're still meant to be public.
+    Audit {
+        /// Flags links created longer ago than this, e.g. `30d` or `12w`.
+        #[arg(long, value_parser = super::duration, default_value = "90d", value_name = "DURATION")]
+        older_than: Duration,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Stops a share link from working, picked out by its ID or by the document it's for.
+    #[command(group(ArgGroup::new("share").required(true).args(["share_id", "doc"])))]
+    Revoke {
+        /// The ID of the share link, as shown by `shares list`.
+        share_id: Option<String>,
+        /// Revokes the link for this document instead.
+        #[arg(long, value_parser = documents::document_id)]
+        doc: Option<String>,
+    },
+}
+
+pub fn exec(
+    cmd: SharesCommand,
+    client: &GetOutlineClient,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    match cmd {
+        SharesCommand::List {
+            pagination,
+            listing,
+        } => list(client, pagination, listing, output),
+        SharesCommand::Audit {
+            older_than,
+            listing,
+        } => audit(client, older_than, listing, output),
+        SharesCommand::Revoke {
+            doc: Some(doc_id), ..
+        } => {
+            let doc_id = documents::resolve_reference(&Recent::load(profile), &doc_id)?;
+            revoke_for_document(client, &doc_id, output)
+        }
+        SharesCommand::Revoke {
+            share_id: Some(share_id),
+            ..
+        } => revoke(client, &share_id, output),
+        SharesCommand::Revoke { .. } => unreachable!("clap requires a share ID or --doc"),
+    }
+}
+
+fn list(
+    client: &GetOutlineClient,
+    pagination: PaginationArgs,
+    listing: ListingArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let shares = logic::list_shares(client, pagination.into())?;
+    if output.json {
+        return print_json(&shares);
+    }
+    if shares.is_empty() {
+        println!("No share links found.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("ID"),
+        Column::Shrink("Document"),
+        Column::Full("Link"),
+        Column::Shrink("Created by"),
+        Column::Full("Last accessed"),
+    ]);
+    for share in shares {
+        let creator = share
+            .created_by
+            .map_or("unknown".to_string(), |author| author.name);
+        table.row([
+            share.id,
+            share.document_title,
+            share.url,
+            creator,
+            share
+                .last_accessed_at
+                .unwrap_or_else(|| "never accessed".to_string()),
+        ]);
+    }
+    table.print(output, listing.into())?;
+
+    Ok(())
+}
+
+fn audit(
+    client: &GetOutlineClient,
+    older_than: Duration,
+    listing: ListingArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs());
+    let shares = logic::audit_shares(client, now, older_than)?;
+    if output.json {
+        return print_json(&shares);
+    }
+    if shares.is_empty() {
+        println!("No documents are shared publicly.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("ID"),
+        Column::Shrink("Document"),
+        Column::Full("Link"),
+        Column::Shrink("Created by"),
+        Column::Full("Age"),
+        Column::Full("Last accessed"),
+        Column::Full("Flag"),
+    ]);
+    let flagged = shares.iter().filter(|audited| audited.flagged).count();
+    let total = shares.len();
+    for audited in shares {
+        let share = audited.share;
+        let creator = share
+            .created_by
+            .map_or("unknown".to_string(), |author| author.name);
+        table.row([
+            share.id,
+            share.document_title,
+            share.url,
+            creator,
+            audited.age.map_or("unknown".to_string(), age),
+            share
+                .last_accessed_at
+                .unwrap_or_else(|| "never accessed".to_string()),
+            if audited.flagged { "old" } else { "-" }.to_string(),
+        ]);
+    }
+    table.print(output, listing.into())?;
+    // On stderr, so the listing can be saved as CSV on its own
+    eprintln!(
+        "{flagged} of {total} public share links are older than {}.",
+        age(older_than)
+    );
+
+    Ok(())
+}
+
+fn revoke(client: &GetOutlineClient, share_id: &str, output: Output) -> anyhow::Result<()> {
+    logic::revoke_share(client, share_id)?;
+    if output.json {
+        return print_json(&json!({ "id": share_id, "revoked": true }));
+    }
+    println!("Revoked share link {share_id}");
+
+    Ok(())
+}
+
+fn revoke_for_document(
+    client: &GetOutlineClient,
+    doc_id: &str,
+    output: Output,
+) -> anyhow::Result<()> {
+    let share = logic::revoke_document_share(client, doc_id)?;
+    if output.json {
+        return print_json(&share);
+    }
+    println!(
+        "Revoked the share link for \"{}\" ({})",
+        share.document_title, share.url
+    );
+
+    Ok(())
+}
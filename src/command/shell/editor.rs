@@ -0,0 +1,194 @@
+//! Reading the shell's input a line at a time. On a terminal, lines can be edited with the keys
+//! most shells use, and the up and down arrows go through the lines typed before.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+#[cfg(unix)]
+use super::super::terminal::{Key, Keys, RawMode};
+
+/// How many of the most recent lines are kept in the history.
+const HISTORY_LIMIT: usize = 1000;
+
+/// What came of reading a line.
+pub(super) enum Input {
+    Line(String),
+    /// Ctrl-C was pressed, throwing away what was typed.
+    Interrupted,
+    /// Ctrl-D was pressed on an empty line, or there's no more input.
+    End,
+}
+
+pub(super) struct Editor {
+    history: Vec<String>,
+    /// Whether someone is typing the lines in, rather than them being piped in.
+    interactive: bool,
+}
+
+impl Editor {
+    /// An editor which starts out with the lines in `history`, oldest first.
+    pub(super) fn new(history: Vec<String>) -> Self {
+        Self {
+            history,
+            interactive: io::stdin().is_terminal() && io::stderr().is_terminal(),
+        }
+    }
+
+    pub(super) fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    /// The lines typed so far, oldest first.
+    pub(super) fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Adds `line` to the end of the history, forgetting the oldest lines once there are too
+    /// many. Returns whether it was added, which it isn't if it repeats the line before.
+    pub(super) fn remember(&mut self, line: &str) -> bool {
+        if self.history.last().is_some_and(|last| last == line) {
+            return false;
+        }
+        self.history.push(line.to_string());
+        let excess = self.history.len().saturating_sub(HISTORY_LIMIT);
+        self.history.drain(..excess);
+        true
+    }
+
+    /// Reads the next line, after showing `prompt` on stderr if someone is typing it in.
+    pub(super) fn read_line(&mut self, prompt: &str) -> io::Result<Input> {
+        #[cfg(unix)]
+        if self.interactive {
+            return self.edit_line(prompt);
+        }
+
+        if self.interactive {
+            eprint!("{prompt}");
+            io::stderr().flush()?;
+        }
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(Input::End);
+        }
+        Ok(Input::Line(line.trim_end_matches(['\n', '\r']).to_string()))
+    }
+}
+
+#[cfg(unix)]
+impl Editor {
+    /// Reads a line with the terminal in raw mode, redrawing it on stderr after every key.
+    fn edit_line(&mut self, prompt: &str) -> io::Result<Input> {
+        let _raw = RawMode::enable()?;
+        let mut keys = Keys(io::stdin().lock());
+        let mut screen = io::stderr().lock();
+        let mut line = Line::default();
+        // Which line of the history is shown, where `history.len()` is the one being typed
+        let mut recalled = self.history.len();
+        let mut typed = Line::default();
+
+        line.draw(&mut screen, prompt)?;
+        loop {
+            let Some(key) = keys.next()? else {
+                return Ok(Input::End);
+            };
+            match key {
+                Key::Enter => {
+                    screen.write_all(b"\r\n")?;
+                    return Ok(Input::Line(line.chars.into_iter().collect()));
+                }
+                Key::Ctrl('c') => {
+                    screen.write_all(b"^C\r\n")?;
+                    return Ok(Input::Interrupted);
+                }
+                Key::Ctrl('d') if line.chars.is_empty() => {
+                    screen.write_all(b"\r\n")?;
+                    return Ok(Input::End);
+                }
+                Key::Ctrl('d') | Key::Delete if line.cursor < line.chars.len() => {
+                    line.chars.remove(line.cursor);
+                }
+                Key::Backspace if line.cursor > 0 => {
+                    line.cursor -= 1;
+                    line.chars.remove(line.cursor);
+                }
+                Key::Left | Key::Ctrl('b') => line.cursor = line.cursor.saturating_sub(1),
+                Key::Right | Key::Ctrl('f') => {
+                    line.cursor = (line.cursor + 1).min(line.chars.len())
+                }
+                Key::Home | Key::Ctrl('a') => line.cursor = 0,
+                Key::End | Key::Ctrl('e') => line.cursor = line.chars.len(),
+                Key::Ctrl('k') => line.chars.truncate(line.cursor),
+                Key::Ctrl('u') => {
+                    line.chars.drain(..line.cursor);
+                    line.cursor = 0;
+                }
+                Key::Ctrl('w') => {
+                    let before = &line.chars[..line.cursor];
+                    let word_end = before
+                        .iter()
+                        .rposition(|c| !c.is_whitespace())
+                        .map_or(0, |index| index + 1);
+                    let word_start = before[..word_end]
+                        .iter()
+                        .rposition(|c| c.is_whitespace())
+                        .map_or(0, |index| index + 1);
+                    line.chars.drain(word_start..line.cursor);
+                    line.cursor = word_start;
+                }
+                Key::Ctrl('l') => screen.write_all(b"\x1b[2J\x1b[H")?,
+                Key::Up | Key::Ctrl('p') if recalled > 0 => {
+                    if recalled == self.history.len() {
+                        typed = line;
+                    }
+                    recalled -= 1;
+                    line = Line::from(&self.history[recalled]);
+                }
+                Key::Down | Key::Ctrl('n') if recalled < self.history.len() => {
+                    recalled += 1;
+                    line = match self.history.get(recalled) {
+                        Some(earlier) => Line::from(earlier),
+                        None => std::mem::take(&mut typed),
+                    };
+                }
+                Key::Char(c) => {
+                    line.chars.insert(line.cursor, c);
+                    line.cursor += 1;
+                }
+                _ => {}
+            }
+            line.draw(&mut screen, prompt)?;
+        }
+    }
+}
+
+/// The line being edited.
+#[cfg(unix)]
+#[derive(Default)]
+struct Line {
+    chars: Vec<char>,
+    /// How many characters come before the cursor.
+    cursor: usize,
+}
+
+#[cfg(unix)]
+impl Line {
+    /// `text`, with the cursor at its end.
+    fn from(text: &str) -> Self {
+        let chars: Vec<_> = text.chars().collect();
+        Self {
+            cursor: chars.len(),
+            chars,
+        }
+    }
+
+    /// Redraws the terminal's current row as `prompt` followed by the line, and puts the cursor
+    /// back where it was.
+    fn draw(&self, screen: &mut impl Write, prompt: &str) -> io::Result<()> {
+        let text: String = self.chars.iter().collect();
+        write!(screen, "\r{prompt}{text}\x1b[K")?;
+        let after = self.chars.len() - self.cursor;
+        if after > 0 {
+            write!(screen, "\x1b[{after}D")?;
+        }
+        screen.flush()
+    }
+}
@@ -0,0 +1,144 @@
+//! `shell`: a prompt for running one command after another, which reads each profile's settings
+//! and connects to getOutline once instead of for every command.
+
+mod editor;
+
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
+
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches};
+
+use super::errors::{ErrorCode, StdoutError};
+use super::logging::Verbosity;
+use super::output::{self, Output};
+use super::{command_path, errors, run, CLIArgs, Clients, HIDE_SECRETS};
+use crate::state;
+use editor::{Editor, Input};
+
+/// The name of the state file the commands typed into the shell are kept in.
+const HISTORY: &str = "shell-history";
+
+pub fn exec(profile: Option<&str>, output: Output, verbosity: Verbosity) -> anyhow::Result<()> {
+    let history_file = state::file_name(profile, HISTORY);
+    let mut editor = Editor::new(state::load(&history_file));
+    let defaults = Defaults {
+        profile,
+        output,
+        hide_secrets: HIDE_SECRETS.load(Ordering::Relaxed),
+        verbosity,
+    };
+    let clients = Rc::new(Clients::default());
+    let prompt = match profile {
+        Some(profile) => format!("get_outline ({profile})> "),
+        None => "get_outline> ".to_string(),
+    };
+
+    if editor.is_interactive() {
+        eprintln!(
+            "Type a command like `documents search runbook`, or `help` to list them. `exit` or \
+             Ctrl-D leaves."
+        );
+    }
+    let mut saving_history = true;
+    loop {
+        let line = match editor.read_line(&prompt)? {
+            Input::Line(line) => line,
+            Input::Interrupted => continue,
+            Input::End => break,
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if editor.remember(line) && saving_history {
+            if let Err(err) = state::save(&history_file, &editor.history()) {
+                tracing::warn!("couldn't save the shell's history: {err}");
+                saving_history = false;
+            }
+        }
+        if matches!(line, "exit" | "quit") {
+            break;
+        }
+        match shlex::split(line) {
+            Some(words) => run_line(words, &defaults, &clients)?,
+            None => eprintln!("error: a quote isn't closed"),
+        }
+    }
+
+    Ok(())
+}
+
+/// The global flags the shell was started with, which carry over to the commands typed into it.
+struct Defaults<'a> {
+    profile: Option<&'a str>,
+    output: Output,
+    hide_secrets: bool,
+    verbosity: Verbosity,
+}
+
+/// Runs the command typed as `words`, printing what went wrong if it fails rather than leaving
+/// the shell. The shell only ends if stdout has closed, since nothing else could be printed.
+fn run_line(
+    words: Vec<String>,
+    defaults: &Defaults,
+    clients: &Rc<Clients>,
+) -> Result<(), StdoutError> {
+    let parsed = CLIArgs::command()
+        .try_get_matches_from(arguments(words))
+        .and_then(|matches| Ok((CLIArgs::from_arg_matches(&matches)?, matches)));
+    let (mut args, matches) = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            // This is also how `help` and `--help` print their help
+            let _ = err.print();
+            return Ok(());
+        }
+    };
+
+    if matches.value_source("profile") != Some(ValueSource::CommandLine) {
+        args.profile = defaults.profile.map(str::to_string);
+    }
+    args.plain |= defaults.output.plain;
+    args.json |= defaults.output.json;
+    args.no_secrets_output |= defaults.hide_secrets;
+    if args.verbose == 0 && !args.quiet {
+        args.verbose = defaults.verbosity.verbose;
+        args.quiet = defaults.verbosity.quiet;
+    }
+
+    let json = args.json;
+    let path = command_path(&matches);
+    let result = run(args, &path, Some(clients.clone()));
+    // `auth` commands can change the API key the connections were opened with
+    if path.first().is_some_and(|command| command == "auth") {
+        clients.forget();
+    }
+    if let Err(err) = result {
+        match errors::classify(&err) {
+            ErrorCode::StdoutClosed => return Err(StdoutError::Closed),
+            code => output::print_error(&err, code, json),
+        }
+    }
+
+    Ok(())
+}
+
+/// The command line for `words`, with `documents` added in front of subcommands like `search`
+/// and `save` which only `documents` has.
+fn arguments(mut words: Vec<String>) -> Vec<String> {
+    let cli = CLIArgs::command();
+    if let Some(first) = words.first() {
+        let documents_only = cli.find_subcommand(first).is_none()
+            && cli
+                .find_subcommand("documents")
+                .is_some_and(|documents| documents.find_subcommand(first).is_some());
+        if documents_only {
+            words.insert(0, "documents".to_string());
+        }
+    }
+
+    words.insert(0, cli.get_name().to_string());
+    words
+}
@@ -0,0 +1,176 @@
+//! Reading keys straight from the terminal as they're pressed, for the shell's line editor, the
+//! document picker, and `browse`.
+
+use std::io::{self, Write};
+use std::os::fd::AsFd;
+
+/// A key pressed.
+pub(super) enum Key {
+    Char(char),
+    /// A letter pressed along with Ctrl, in lowercase.
+    Ctrl(char),
+    Enter,
+    Tab,
+    /// Shift-Tab.
+    BackTab,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    /// Anything else, which is ignored.
+    Other,
+}
+
+/// Reads keys from what the terminal sends for them: bytes for characters and control keys, with
+/// escape sequences for the arrows and the like.
+pub(super) struct Keys<R>(pub(super) R);
+
+impl<R: io::Read> Keys<R> {
+    /// The next key pressed, or `None` once the input is closed.
+    pub(super) fn next(&mut self) -> io::Result<Option<Key>> {
+        let Some(byte) = self.byte()? else {
+            return Ok(None);
+        };
+        let key = match byte {
+            b'\r' | b'\n' => Key::Enter,
+            b'\t' => Key::Tab,
+            0x7f | 0x08 => Key::Backspace,
+            0x1b => self.escape_sequence()?,
+            0x01..=0x1a => Key::Ctrl(char::from(b'a' + byte - 1)),
+            0x00..=0x1f => Key::Other,
+            _ => self.char(byte)?,
+        };
+        Ok(Some(key))
+    }
+
+    /// The key for an escape sequence like `ESC [ A`, for the up arrow.
+    fn escape_sequence(&mut self) -> io::Result<Key> {
+        if !matches!(self.byte()?, Some(b'[' | b'O')) {
+            return Ok(Key::Other);
+        }
+        let mut parameters = String::new();
+        loop {
+            let Some(byte) = self.byte()? else {
+                return Ok(Key::Other);
+            };
+            if !(0x40..=0x7e).contains(&byte) {
+                parameters.push(char::from(byte));
+                continue;
+            }
+            return Ok(match (byte, parameters.as_str()) {
+                (b'A', _) => Key::Up,
+                (b'B', _) => Key::Down,
+                (b'C', _) => Key::Right,
+                (b'D', _) => Key::Left,
+                (b'Z', _) => Key::BackTab,
+                (b'H', _) | (b'~', "1" | "7") => Key::Home,
+                (b'F', _) | (b'~', "4" | "8") => Key::End,
+                (b'~', "3") => Key::Delete,
+                (b'~', "5") => Key::PageUp,
+                (b'~', "6") => Key::PageDown,
+                _ => Key::Other,
+            });
+        }
+    }
+
+    /// The character `first` starts, reading the rest of its UTF-8 encoding.
+    fn char(&mut self, first: u8) -> io::Result<Key> {
+        let length = match first {
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => 1,
+        };
+        let mut bytes = vec![first];
+        while bytes.len() < length {
+            match self.byte()? {
+                Some(byte) => bytes.push(byte),
+                None => return Ok(Key::Other),
+            }
+        }
+        Ok(std::str::from_utf8(&bytes)
+            .ok()
+            .and_then(|text| text.chars().next())
+            .map_or(Key::Other, Key::Char))
+    }
+
+    fn byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte = [0];
+        match self.0.read(&mut byte)? {
+            0 => Ok(None),
+            _ => Ok(Some(byte[0])),
+        }
+    }
+}
+
+/// Keeps the terminal in raw mode, where keys are read as they're pressed instead of a line at a
+/// time and aren't echoed, until it's dropped.
+pub(super) struct RawMode {
+    original: rustix::termios::Termios,
+}
+
+impl RawMode {
+    pub(super) fn enable() -> io::Result<Self> {
+        let original = rustix::termios::tcgetattr(io::stdin())?;
+        let mut raw = original.clone();
+        raw.make_raw();
+        rustix::termios::tcsetattr(io::stdin(), rustix::termios::OptionalActions::Now, &raw)?;
+        Ok(Self { original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = rustix::termios::tcsetattr(
+            io::stdin(),
+            rustix::termios::OptionalActions::Now,
+            &self.original,
+        );
+    }
+}
+
+/// How many columns and rows the window `terminal` shows has, assuming 80 by 24 if it can't be
+/// told.
+pub(super) fn size(terminal: impl AsFd) -> (usize, usize) {
+    match rustix::termios::tcgetwinsize(terminal) {
+        Ok(size) if size.ws_col > 0 && size.ws_row > 0 => {
+            (usize::from(size.ws_col), usize::from(size.ws_row))
+        }
+        _ => (80, 24),
+    }
+}
+
+/// Takes over the whole terminal window, in raw mode and with the cursor hidden, until it's
+/// dropped and whatever was on screen before comes back.
+pub(super) struct FullScreen {
+    _raw: RawMode,
+}
+
+impl FullScreen {
+    pub(super) fn enter() -> io::Result<Self> {
+        let raw = RawMode::enable()?;
+        let mut stdout = io::stdout();
+        stdout.write_all(b"\x1b[?1049h\x1b[?25l")?;
+        stdout.flush()?;
+        Ok(Self { _raw: raw })
+    }
+
+    /// How many columns and rows the window has.
+    pub(super) fn size(&self) -> (usize, usize) {
+        size(io::stdout())
+    }
+}
+
+impl Drop for FullScreen {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x1b[?25h\x1b[?1049l");
+        let _ = stdout.flush();
+    }
+}
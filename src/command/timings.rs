@@ -0,0 +1,76 @@
+//! Printing the `--timings` breakdown of the requests a command sent.
+
+use std::time::Duration;
+
+use super::output::size;
+use crate::logic::timings::RequestTiming;
+
+/// Prints a table of how long each of `requests` took on stderr, followed by where the time went
+/// overall.
+pub(super) fn report(requests: &[RequestTiming]) {
+    eprintln!();
+    if requests.is_empty() {
+        eprintln!("No requests were sent to getOutline.");
+        return;
+    }
+
+    let width = requests
+        .iter()
+        .map(|request| request.method.len())
+        .chain(["Request".len()])
+        .max()
+        .unwrap_or_default();
+    eprintln!(
+        "{:<width$}  {:>6}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}  {:>8}",
+        "Request", "Status", "DNS", "Connect", "TLS", "Waiting", "Body", "Total", "Size"
+    );
+    for request in requests {
+        let (dns, connect, tls) = match request.setup {
+            Some(setup) => (
+                milliseconds(setup.dns),
+                milliseconds(setup.connect),
+                setup.tls.map_or("-".to_string(), milliseconds),
+            ),
+            // An already open connection was reused
+            None => ("-".to_string(), "-".to_string(), "-".to_string()),
+        };
+        eprintln!(
+            "{:<width$}  {:>6}  {dns:>8}  {connect:>8}  {tls:>8}  {:>8}  {:>8}  {:>8}  {:>8}",
+            request.method,
+            request
+                .status
+                .map_or("failed".to_string(), |status| status.to_string()),
+            milliseconds(request.waiting),
+            milliseconds(request.body),
+            milliseconds(request.total()),
+            size(request.bytes),
+        );
+    }
+
+    let setups = requests.iter().filter_map(|request| request.setup);
+    let connecting: Duration = setups.clone().map(|setup| setup.dns + setup.connect).sum();
+    let handshaking: Duration = setups.filter_map(|setup| setup.tls).sum();
+    let waiting: Duration = requests.iter().map(|request| request.waiting).sum();
+    let downloading: Duration = requests.iter().map(|request| request.body).sum();
+    let count = match requests.len() {
+        1 => "1 request".to_string(),
+        count => format!("{count} requests"),
+    };
+    eprintln!(
+        "{count}: {} connecting, {} on TLS handshakes, {} waiting for getOutline, {} downloading",
+        milliseconds(connecting),
+        milliseconds(handshaking),
+        milliseconds(waiting),
+        milliseconds(downloading),
+    );
+}
+
+/// `duration` in milliseconds, to a tenth of one for short ones.
+fn milliseconds(duration: Duration) -> String {
+    let milliseconds = duration.as_secs_f64() * 1000.0;
+    if milliseconds < 10.0 {
+        format!("{milliseconds:.1}ms")
+    } else {
+        format!("{milliseconds:.0}ms")
+    }
+}
@@ -0,0 +1,291 @@
+//! What the browser shows, and what each key does to it.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+use super::draw::{self, Layout};
+use crate::command::documents::{open_in_browser, web_url, write_new_file, Recent};
+use crate::command::output::Output;
+use crate::command::terminal::{FullScreen, Key, Keys};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::collections::{self, Collection, CollectionTreeReader, OutlineEntry};
+use crate::logic::documents::{self, ContentSource, Document};
+use crate::logic::parsers;
+
+/// The panes of the browser, from left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Pane {
+    Collections,
+    Documents,
+    Preview,
+}
+
+/// Which item of a list is selected, and how far the list is scrolled to show it.
+#[derive(Debug, Default, Clone, Copy)]
+pub(super) struct Selection {
+    pub(super) index: usize,
+    /// The first item shown.
+    pub(super) scroll: usize,
+}
+
+impl Selection {
+    /// Moves the selection `by` items up or down, staying within the `count` there are.
+    fn moved(self, by: isize, count: usize) -> Self {
+        Self {
+            index: self
+                .index
+                .saturating_add_signed(by)
+                .min(count.saturating_sub(1)),
+            ..self
+        }
+    }
+
+    /// Scrolls the list just far enough for the selected item to be among the `rows` shown.
+    pub(super) fn scroll_into_view(&mut self, rows: usize) {
+        if self.index < self.scroll {
+            self.scroll = self.index;
+        } else if self.index >= self.scroll + rows {
+            self.scroll = self.index + 1 - rows;
+        }
+    }
+}
+
+/// Something the browser has to fetch before it can show the selected collection or document.
+enum Missing {
+    Outline(String),
+    Document(String),
+}
+
+pub(super) struct Browser<'a> {
+    client: &'a GetOutlineClient,
+    profile: Option<&'a str>,
+    pub(super) output: Output,
+    pub(super) collections: Vec<Collection>,
+    /// The documents in each collection looked at so far, by the collection's ID.
+    outlines: HashMap<String, Vec<OutlineEntry>>,
+    /// The documents previewed so far by ID, or `None` for those which couldn't be fetched.
+    documents: HashMap<String, Option<Document>>,
+    pub(super) focus: Pane,
+    pub(super) collection: Selection,
+    pub(super) document: Selection,
+    /// How many lines down the preview is scrolled.
+    pub(super) preview_scroll: usize,
+    /// What the last key did, shown along the bottom instead of the keys to press.
+    pub(super) status: Option<String>,
+}
+
+impl<'a> Browser<'a> {
+    /// Fetches the collections to start browsing from.
+    pub(super) fn open(
+        client: &'a GetOutlineClient,
+        profile: Option<&'a str>,
+        output: Output,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            client,
+            profile,
+            output,
+            collections: collections::all_collections(client)?,
+            outlines: HashMap::new(),
+            documents: HashMap::new(),
+            focus: Pane::Collections,
+            collection: Selection::default(),
+            document: Selection::default(),
+            preview_scroll: 0,
+            status: None,
+        })
+    }
+
+    /// Shows the browser until `q` is pressed.
+    pub(super) fn run(mut self) -> anyhow::Result<()> {
+        let mut screen = FullScreen::enter()?;
+        let mut keys = Keys(io::stdin().lock());
+
+        loop {
+            let layout = Layout::new(screen.size());
+            while let Some(missing) = self.missing() {
+                self.status = Some("Loading...".to_string());
+                draw::draw(&mut self, &layout)?;
+                self.status = None;
+                self.fetch(missing);
+            }
+            draw::draw(&mut self, &layout)?;
+
+            let Some(key) = keys.next()? else {
+                return Ok(());
+            };
+            self.status = None;
+            let page = layout.rows as isize;
+            match key {
+                Key::Char('q') | Key::Ctrl('c') => return Ok(()),
+                Key::Up | Key::Char('k') | Key::Ctrl('p') => self.move_by(-1, &layout),
+                Key::Down | Key::Char('j') | Key::Ctrl('n') => self.move_by(1, &layout),
+                Key::PageUp => self.move_by(-page, &layout),
+                Key::PageDown | Key::Char(' ') => self.move_by(page, &layout),
+                Key::Home | Key::Char('g') => self.move_by(isize::MIN, &layout),
+                Key::End | Key::Char('G') => self.move_by(isize::MAX, &layout),
+                Key::Right | Key::Tab | Key::Enter | Key::Char('l') => {
+                    self.focus = match self.focus {
+                        Pane::Collections => Pane::Documents,
+                        Pane::Documents | Pane::Preview => Pane::Preview,
+                    }
+                }
+                Key::Left | Key::BackTab | Key::Backspace | Key::Char('h') => {
+                    self.focus = match self.focus {
+                        Pane::Collections | Pane::Documents => Pane::Collections,
+                        Pane::Preview => Pane::Documents,
+                    }
+                }
+                Key::Char('s') => self.save(),
+                Key::Char('o') => {
+                    // Terminal browsers need the screen back, and others may print on it
+                    drop(screen);
+                    self.open_selected();
+                    screen = FullScreen::enter()?;
+                }
+                Key::Char('r') => self.refresh(),
+                _ => {}
+            }
+        }
+    }
+
+    pub(super) fn selected_collection(&self) -> Option<&Collection> {
+        self.collections.get(self.collection.index)
+    }
+
+    /// The documents in the selected collection, or `None` if they haven't been fetched yet.
+    pub(super) fn outline(&self) -> Option<&[OutlineEntry]> {
+        let collection = self.selected_collection()?;
+        self.outlines.get(&collection.id).map(Vec::as_slice)
+    }
+
+    pub(super) fn selected_entry(&self) -> Option<&OutlineEntry> {
+        self.outline()?.get(self.document.index)
+    }
+
+    /// The selected document, or `None` if it hasn't been fetched, and `Some(None)` if it
+    /// couldn't be.
+    pub(super) fn preview(&self) -> Option<Option<&Document>> {
+        let entry = self.selected_entry()?;
+        self.documents.get(&entry.id).map(Option::as_ref)
+    }
+
+    fn missing(&self) -> Option<Missing> {
+        let collection = self.selected_collection()?;
+        let Some(outline) = self.outlines.get(&collection.id) else {
+            return Some(Missing::Outline(collection.id.clone()));
+        };
+        let entry = outline.get(self.document.index)?;
+        (!self.documents.contains_key(&entry.id)).then(|| Missing::Document(entry.id.clone()))
+    }
+
+    /// Fetches what's `missing`, keeping what went wrong to show if it can't be.
+    fn fetch(&mut self, missing: Missing) {
+        match missing {
+            Missing::Outline(id) => {
+                let outline = match self.client.document_tree(&id) {
+                    Ok(tree) => collections::outline(&tree),
+                    Err(err) => {
+                        self.status = Some(format!("Couldn't list the documents: {err}"));
+                        Vec::new()
+                    }
+                };
+                self.outlines.insert(id, outline);
+            }
+            Missing::Document(id) => {
+                let document = documents::fetch_document(self.client, &id, ContentSource::Info)
+                    .map_err(|err| {
+                        self.status = Some(format!("Couldn't fetch the document: {err}"))
+                    })
+                    .ok();
+                self.documents.insert(id, document);
+            }
+        }
+    }
+
+    /// Moves the selection in the focused pane, or scrolls the preview, `by` lines.
+    fn move_by(&mut self, by: isize, layout: &Layout) {
+        match self.focus {
+            Pane::Collections => {
+                let moved = self.collection.moved(by, self.collections.len());
+                if moved.index != self.collection.index {
+                    self.document = Selection::default();
+                    self.preview_scroll = 0;
+                }
+                self.collection = moved;
+            }
+            Pane::Documents => {
+                let count = self.outline().map_or(0, <[_]>::len);
+                let moved = self.document.moved(by, count);
+                if moved.index != self.document.index {
+                    self.preview_scroll = 0;
+                }
+                self.document = moved;
+            }
+            Pane::Preview => {
+                let lines = self.preview().flatten().map_or(0, |document| {
+                    parsers::fit_lines(&document.text, layout.preview).len()
+                });
+                self.preview_scroll = self
+                    .preview_scroll
+                    .saturating_add_signed(by)
+                    .min(lines.saturating_sub(layout.rows));
+            }
+        }
+    }
+
+    /// The ID and content of the selected document, or why there isn't one.
+    fn selected_document(&self) -> Result<(&str, &Document), String> {
+        let entry = self
+            .selected_entry()
+            .ok_or_else(|| "There's no document selected".to_string())?;
+        match self.documents.get(&entry.id) {
+            Some(Some(document)) => Ok((&entry.id, document)),
+            _ => Err("The document couldn't be fetched".to_string()),
+        }
+    }
+
+    /// Saves the selected document's markdown in the current directory, like `documents save`.
+    fn save(&mut self) {
+        let status = self.selected_document().and_then(|(id, document)| {
+            let path = PathBuf::from(format!("{}.md", parsers::file_name_for(&document.title)));
+            write_new_file(&path, document.text.as_bytes(), false)
+                .map_err(|err| format!("{err:#}"))?;
+            Recent::load(self.profile).remember(id, &document.title);
+            Ok(format!(
+                "Saved \"{}\" to {}",
+                document.title,
+                path.display()
+            ))
+        });
+        self.status = Some(status.unwrap_or_else(|err| err));
+    }
+
+    /// Opens the selected document in a web browser, like `documents open`.
+    fn open_selected(&mut self) {
+        let status = self.selected_document().and_then(|(id, document)| {
+            let path = documents::web_path(document.url.as_deref(), id);
+            let url = web_url(self.client, &path);
+            open_in_browser(&url).map_err(|err| format!("Couldn't start a web browser: {err}"))?;
+            Recent::load(self.profile).remember(id, &document.title);
+            Ok(format!("Opened \"{}\" at {url}", document.title))
+        });
+        self.status = Some(status.unwrap_or_else(|err| err));
+    }
+
+    /// Forgets everything fetched so far and lists the collections again, to pick up changes.
+    fn refresh(&mut self) {
+        self.outlines.clear();
+        self.documents.clear();
+        match collections::all_collections(self.client) {
+            Ok(collections) => {
+                self.collections = collections;
+                self.collection = self.collection.moved(0, self.collections.len());
+                self.document = Selection::default();
+                self.preview_scroll = 0;
+            }
+            Err(err) => self.status = Some(format!("Couldn't list the collections: {err}")),
+        }
+    }
+}
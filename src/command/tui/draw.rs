@@ -0,0 +1,216 @@
+//! Drawing the browser: its three panes side by side under their headings, with a line along the
+//! bottom for the keys to press.
+
+use std::io::{self, Write};
+
+use super::browser::{Browser, Pane};
+use crate::command::printable;
+use crate::logic::parsers;
+
+/// How wide each pane is, and how many rows of items fit in them.
+pub(super) struct Layout {
+    pub(super) collections: usize,
+    pub(super) documents: usize,
+    pub(super) preview: usize,
+    /// The rows between the headings and the line along the bottom.
+    pub(super) rows: usize,
+}
+
+impl Layout {
+    /// The layout for a window of `columns` by `rows`.
+    pub(super) fn new((columns, rows): (usize, usize)) -> Self {
+        // Two columns go to the lines between the panes
+        let available = columns.saturating_sub(2).max(3);
+        let collections = (available / 4).clamp(1, 32);
+        let documents = (available * 3 / 10).max(1);
+        Self {
+            collections,
+            documents,
+            preview: available.saturating_sub(collections + documents).max(1),
+            rows: rows.saturating_sub(2).max(1),
+        }
+    }
+}
+
+/// How a line of a pane is shown.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Normal,
+    Heading,
+    /// The selected item of the focused pane.
+    Selected,
+    /// The selected item of a pane which isn't focused.
+    Current,
+}
+
+impl Style {
+    fn escape(self) -> &'static str {
+        match self {
+            Style::Normal => "",
+            Style::Heading => "\x1b[1m",
+            Style::Selected => "\x1b[7m",
+            Style::Current => "\x1b[1;4m",
+        }
+    }
+}
+
+/// Draws the whole browser on stdout.
+pub(super) fn draw(browser: &mut Browser, layout: &Layout) -> io::Result<()> {
+    let collections = collection_lines(browser, layout);
+    let documents = document_lines(browser, layout);
+    let preview = preview_lines(browser, layout);
+    let separator = if browser.output.plain { "|" } else { "│" };
+
+    let mut frame = String::new();
+    let panes = [
+        (collections, layout.collections),
+        (documents, layout.documents),
+        (preview, layout.preview),
+    ];
+    for row in 0..=layout.rows {
+        frame.push_str(&format!("\x1b[{};1H", row + 1));
+        let cells: Vec<_> = panes
+            .iter()
+            .map(|(lines, width)| {
+                let (text, style) = lines
+                    .get(row)
+                    .map_or(("", Style::Normal), |(text, style)| (text.as_str(), *style));
+                let cell = fit(text, *width);
+                match style {
+                    Style::Normal => cell,
+                    style => format!("{}{cell}\x1b[0m", style.escape()),
+                }
+            })
+            .collect();
+        frame.push_str(&cells.join(separator));
+        frame.push_str("\x1b[K");
+    }
+
+    let width = layout.collections + layout.documents + layout.preview + 2;
+    let bottom = match &browser.status {
+        Some(status) => status.as_str(),
+        None if browser.output.plain => {
+            "up and down: move, left and right: switch pane, s: save, o: open in a browser, r: \
+             refresh, q: quit"
+        }
+        None => "↑↓ move  ←→ switch pane  s save  o open in browser  r refresh  q quit",
+    };
+    frame.push_str(&format!(
+        "\x1b[{};1H\x1b[7m{}\x1b[0m\x1b[K",
+        layout.rows + 2,
+        fit(bottom, width)
+    ));
+
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(printable(&frame).as_bytes())?;
+    stdout.flush()
+}
+
+/// The collections pane: its heading, then the collections which fit.
+fn collection_lines(browser: &mut Browser, layout: &Layout) -> Vec<(String, Style)> {
+    let focused = browser.focus == Pane::Collections;
+    let mut lines = vec![("Collections".to_string(), Style::Heading)];
+    if browser.collections.is_empty() {
+        lines.push(("(no collections)".to_string(), Style::Normal));
+        return lines;
+    }
+
+    browser.collection.scroll_into_view(layout.rows);
+    let selection = browser.collection;
+    lines.extend(
+        browser
+            .collections
+            .iter()
+            .enumerate()
+            .skip(selection.scroll)
+            .take(layout.rows)
+            .map(|(index, collection)| {
+                let style = item_style(index == selection.index, focused);
+                (collection.name.clone(), style)
+            }),
+    );
+    lines
+}
+
+/// The documents pane: the selected collection's name, then its documents which fit, indented
+/// under the ones they're nested in.
+fn document_lines(browser: &mut Browser, layout: &Layout) -> Vec<(String, Style)> {
+    let focused = browser.focus == Pane::Documents;
+    browser.document.scroll_into_view(layout.rows);
+    let selection = browser.document;
+
+    let heading = browser
+        .selected_collection()
+        .map_or("Documents".to_string(), |collection| {
+            collection.name.clone()
+        });
+    let mut lines = vec![(heading, Style::Heading)];
+    match browser.outline() {
+        None => {}
+        Some([]) => lines.push(("(no documents)".to_string(), Style::Normal)),
+        Some(outline) => lines.extend(
+            outline
+                .iter()
+                .enumerate()
+                .skip(selection.scroll)
+                .take(layout.rows)
+                .map(|(index, entry)| {
+                    let style = item_style(index == selection.index, focused);
+                    (
+                        format!("{}{}", "  ".repeat(entry.depth), entry.title),
+                        style,
+                    )
+                }),
+        ),
+    }
+    lines
+}
+
+/// The preview pane: the selected document's title, then as much of its markdown as fits from
+/// where it's scrolled to. Headings stand out.
+fn preview_lines(browser: &Browser, layout: &Layout) -> Vec<(String, Style)> {
+    let document = match browser.preview() {
+        Some(Some(document)) => document,
+        Some(None) => return vec![("(couldn't fetch the document)".to_string(), Style::Normal)],
+        None => return Vec::new(),
+    };
+
+    let mut lines = vec![(document.title.clone(), Style::Heading)];
+    lines.extend(
+        parsers::fit_lines(&document.text, layout.preview)
+            .into_iter()
+            .skip(browser.preview_scroll)
+            .take(layout.rows)
+            .map(|line| {
+                let style = if line.starts_with('#') {
+                    Style::Heading
+                } else {
+                    Style::Normal
+                };
+                (line, style)
+            }),
+    );
+    lines
+}
+
+fn item_style(selected: bool, focused: bool) -> Style {
+    match (selected, focused) {
+        (true, true) => Style::Selected,
+        (true, false) => Style::Current,
+        (false, _) => Style::Normal,
+    }
+}
+
+/// `text` padded or cut short with `…` to exactly `width` characters, with anything which could
+/// move the cursor or change colors turned into spaces.
+fn fit(text: &str, width: usize) -> String {
+    let mut cell: String = text
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    if cell.chars().count() > width {
+        cell = cell.chars().take(width.saturating_sub(1)).collect();
+        cell.push('…');
+    }
+    format!("{cell:<width$}")
+}
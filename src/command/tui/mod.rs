@@ -0,0 +1,34 @@
+//! `browse`: a full-screen browser for the workspace, with the collections in a sidebar, the
+//! documents of the selected one next to them, and a preview of the selected document.
+
+#[cfg(unix)]
+mod browser;
+#[cfg(unix)]
+mod draw;
+
+#[cfg(unix)]
+use std::io::{self, IsTerminal};
+
+use super::errors;
+use super::output::Output;
+use crate::getoutline_connection::GetOutlineClient;
+
+pub fn exec(
+    client: &GetOutlineClient,
+    profile: Option<&str>,
+    output: Output,
+) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    if io::stdin().is_terminal() && io::stdout().is_terminal() {
+        return browser::Browser::open(client, profile, output)?.run();
+    }
+
+    #[cfg(not(unix))]
+    let _ = (client, profile, output);
+    Err(errors::InvalidArguments {
+        message: "`browse` needs a terminal to run in; scripts can use `collections tree` and \
+                  `documents save` instead"
+            .to_string(),
+    }
+    .into())
+}
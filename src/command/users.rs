@@ -0,0 +1,207 @@
+use clap::{Subcommand, ValueEnum};
+
+use super::output::table::{Column, Table};
+use super::output::{print_json, Output};
+use super::{errors, ListingArgs, PaginationArgs};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::users::{self as logic, Invite, UserQuery};
+
+#[derive(Subcommand)]
+pub enum UsersCommand {
+    /// Lists the workspace's users with their email, role, and when they were last active, e.g.
+    /// for an access review.
+    List {
+        /// Only lists users whose name or email contains this.
+        query: Option<String>,
+        /// Lists suspended users instead of active ones.
+        #[arg(long)]
+        suspended: bool,
+        /// Only lists admins.
+        #[arg(long)]
+        admins: bool,
+        #[command(flatten)]
+        pagination: PaginationArgs,
+        #[command(flatten)]
+        listing: ListingArgs,
+    },
+    /// Invites people to the workspace by email.
+    Invite {
+        /// The address to send an invitation to. Repeat it to invite several people at once.
+        #[arg(long, required = true)]
+        email: Vec<String>,
+        /// The name to give each invited user, in the same order as `--email`. Anyone without one
+        /// is named after the part of their address before the `@`.
+        #[arg(long)]
+        name: Vec<String>,
+        /// What the invited users are allowed to do.
+        #[arg(long, value_enum, default_value_t = Role::Member)]
+        role: Role,
+    },
+    /// Shows a user's details.
+    Info {
+        /// The user's ID or email address.
+        user: String,
+    },
+}
+
+/// What an invited user is allowed to do in the workspace.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Role {
+    /// Edit documents in the collections they have access to.
+    Member,
+    /// Manage the workspace, including its users and settings.
+    Admin,
+    /// Read documents, without editing them.
+    Viewer,
+}
+
+impl From<Role> for logic::Role {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Member => logic::Role::Member,
+            Role::Admin => logic::Role::Admin,
+            Role::Viewer => logic::Role::Viewer,
+        }
+    }
+}
+
+pub fn exec(cmd: UsersCommand, client: &GetOutlineClient, output: Output) -> anyhow::Result<()> {
+    match cmd {
+        UsersCommand::List {
+            query,
+            suspended,
+            admins,
+            pagination,
+            listing,
+        } => {
+            let query = UserQuery {
+                name: query.as_deref(),
+                suspended,
+                admins,
+            };
+            list(client, &query, pagination, listing, output)
+        }
+        UsersCommand::Invite { email, name, role } => invite(client, email, name, role, output),
+        UsersCommand::Info { user } => info(client, &user, output),
+    }
+}
+
+fn list(
+    client: &GetOutlineClient,
+    query: &UserQuery,
+    pagination: PaginationArgs,
+    listing: ListingArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let users = logic::list_users(client, query, pagination.into())?;
+    if output.json {
+        return print_json(&users);
+    }
+    if users.is_empty() {
+        println!("No users found.");
+        return Ok(());
+    }
+
+    let mut table = Table::new([
+        Column::Full("ID"),
+        Column::Shrink("Name"),
+        Column::Shrink("Email"),
+        Column::Full("Role"),
+        Column::Full("Last active"),
+    ]);
+    for user in users {
+        let mut role = user.role.unwrap_or_else(|| "unknown".to_string());
+        if user.is_suspended {
+            role.push_str(" (suspended)");
+        }
+        table.row([
+            user.id,
+            user.name,
+            user.email.unwrap_or_else(|| "no email".to_string()),
+            role,
+            user.last_active_at
+                .unwrap_or_else(|| "never active".to_string()),
+        ]);
+    }
+    table.print(output, listing.into())?;
+
+    Ok(())
+}
+
+fn invite(
+    client: &GetOutlineClient,
+    emails: Vec<String>,
+    names: Vec<String>,
+    role: Role,
+    output: Output,
+) -> anyhow::Result<()> {
+    if names.len() > emails.len() {
+        return Err(errors::InvalidArguments {
+            message: format!(
+                "each --name goes with an --email, but there are {} names and {} emails",
+                names.len(),
+                emails.len()
+            ),
+        }
+        .into());
+    }
+
+    let mut names = names.into_iter();
+    let invites: Vec<_> = emails
+        .into_iter()
+        .map(|email| Invite {
+            name: names
+                .next()
+                .unwrap_or_else(|| email.split('@').next().unwrap_or_default().to_string()),
+            email,
+            role: role.into(),
+        })
+        .collect();
+
+    let invited = logic::invite_users(client, &invites)?;
+    if output.json {
+        return print_json(&invited);
+    }
+    for user in &invited {
+        println!(
+            "Invited {} <{}>",
+            user.name,
+            user.email.as_deref().unwrap_or("no email")
+        );
+    }
+    let skipped = invites.len().saturating_sub(invited.len());
+    if skipped > 0 {
+        println!("Skipped {skipped} of the addresses, usually because they already have accounts");
+    }
+
+    Ok(())
+}
+
+fn info(client: &GetOutlineClient, user: &str, output: Output) -> anyhow::Result<()> {
+    let user = logic::user_info(client, user)?;
+    if output.json {
+        return print_json(&user);
+    }
+
+    let status = if user.is_suspended {
+        "suspended"
+    } else {
+        "active"
+    };
+    let fields = [
+        ("Name", user.name.as_str()),
+        ("ID", &user.id),
+        ("Email", user.email.as_deref().unwrap_or("(not visible)")),
+        ("Role", user.role.as_deref().unwrap_or("unknown")),
+        ("Status", status),
+        (
+            "Last active",
+            user.last_active_at.as_deref().unwrap_or("never"),
+        ),
+    ];
+    for (key, value) in fields {
+        println!("{:<12} {value}", format!("{key}:"));
+    }
+
+    Ok(())
+}
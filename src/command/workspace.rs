@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Subcommand;
+use serde_json::json;
+
+use super::collections::{print_export_state, ExportFormat};
+use super::documents::write_new_file;
+use super::output::{print_json, Output};
+use super::{archive, errors};
+use crate::getoutline_connection::GetOutlineClient;
+use crate::logic::collections as logic;
+use crate::logic::workspace;
+
+/// What the archive is called when it's saved without being extracted.
+const ARCHIVE_NAME: &str = "workspace-export.zip";
+
+#[derive(Subcommand)]
+pub enum WorkspaceCommand {
+    /// Shows which workspace the API key belongs to, who it's for, and how people sign in.
+    Info,
+    /// Exports every collection in the workspace as one archive, e.g. for a backup.
+    Export {
+        /// The format to export the documents in.
+        #[arg(long, value_enum, default_value_t = ExportFormat::OutlineMarkdown)]
+        format: ExportFormat,
+        /// The directory to save the export in, which is created if it doesn't exist.
+        #[arg(long, short, default_value = "workspace-export")]
+        output: PathBuf,
+        /// Unpacks the archive into the directory instead of saving it there as
+        /// `workspace-export.zip`.
+        #[arg(long)]
+        extract: bool,
+        /// Replaces an archive which was already saved, or extracts into a directory which
+        /// already has files in it.
+        #[arg(long)]
+        overwrite: bool,
+    },
+}
+
+pub fn exec(
+    cmd: WorkspaceCommand,
+    client: &GetOutlineClient,
+    output: Output,
+) -> anyhow::Result<()> {
+    match cmd {
+        WorkspaceCommand::Info => info(client, output),
+        WorkspaceCommand::Export {
+            format,
+            output: directory,
+            extract,
+            overwrite,
+        } => export(client, format, directory, extract, overwrite, output),
+    }
+}
+
+fn info(client: &GetOutlineClient, output: Output) -> anyhow::Result<()> {
+    let info = workspace::workspace_info(client)?;
+    if output.json {
+        return print_json(&info);
+    }
+
+    let signed_in_as = match &info.user.email {
+        Some(email) => format!("{} <{email}>", info.user.name),
+        None => info.user.name.clone(),
+    };
+    let sign_in_methods = info
+        .sign_in_methods
+        .iter()
+        .map(|method| method.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let fields = [
+        ("Name", info.team.name.as_str()),
+        ("ID", &info.team.id),
+        ("URL", info.team.url.as_deref().unwrap_or(client.base_url())),
+        ("API", client.base_url()),
+        ("API key of", &signed_in_as),
+        (
+            "Sign-in",
+            if sign_in_methods.is_empty() {
+                "unknown"
+            } else {
+                &sign_in_methods
+            },
+        ),
+    ];
+    for (key, value) in fields {
+        println!("{:<11} {value}", format!("{key}:"));
+    }
+
+    Ok(())
+}
+
+fn export(
+    client: &GetOutlineClient,
+    format: ExportFormat,
+    directory: PathBuf,
+    extract: bool,
+    overwrite: bool,
+    output: Output,
+) -> anyhow::Result<()> {
+    // Check where the export is going before starting it, since exporting a workspace takes a while
+    let archive_path = directory.join(ARCHIVE_NAME);
+    if extract {
+        archive::check_destination(&directory, overwrite)?;
+    } else if archive_path.exists() && !overwrite {
+        return Err(errors::FileExists { path: archive_path }.into());
+    }
+
+    output.status("Exporting the workspace...")?;
+    let archive = logic::export_workspace(client, format.into(), |state| {
+        print_export_state(state, output)
+    })?;
+
+    if extract {
+        let files = archive::extract(&archive, &directory)?;
+        if output.json {
+            return print_json(&json!({ "directory": directory, "files": files }));
+        }
+        println!("Extracted {files} files into {}", directory.display());
+    } else {
+        fs::create_dir_all(&directory)
+            .with_context(|| format!("could not create {}", directory.display()))?;
+        write_new_file(&archive_path, &archive, overwrite)?;
+        if output.json {
+            return print_json(&json!({ "path": archive_path }));
+        }
+        println!("Saved the export to {}", archive_path.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! Settings read from the environment (or a `.env` file in the working directory).
+//!
+//! Every setting is named `GETOUTLINE_<SETTING>`. A named profile has its own copy of each
+//! setting called `GETOUTLINE_<PROFILE>_<SETTING>`, so several workspaces can be configured side
+//! by side.
+//!
+//! The `.env` file is only read once the first setting is, so commands which don't need any
+//! settings don't wait for it.
+
+use std::sync::Once;
+
+const PREFIX: &str = "GETOUTLINE";
+
+/// Guards reading the `.env` file, which only happens once.
+static ENV_FILE: Once = Once::new();
+
+/// The name of the environment variable holding `setting` for `profile`, or for the default
+/// profile if there isn't one.
+pub fn var_name(profile: Option<&str>, setting: &str) -> String {
+    match profile {
+        Some(profile) => {
+            let profile = profile.to_ascii_uppercase().replace('-', "_");
+            format!("{PREFIX}_{profile}_{setting}")
+        }
+        None => format!("{PREFIX}_{setting}"),
+    }
+}
+
+/// Reads `setting` for `profile`, if it's been set.
+pub fn get(profile: Option<&str>, setting: &str) -> Option<String> {
+    // Variables which are already set win over the `.env` file
+    ENV_FILE.call_once(|| match dotenvy::dotenv() {
+        Ok(path) => tracing::debug!("read settings from {}", path.display()),
+        Err(err) if err.not_found() => {}
+        // The line can have an API key in it, so it isn't repeated
+        Err(dotenvy::Error::LineParse(..)) => {
+            tracing::warn!(
+                "the .env file has a line which isn't a setting, so the rest of it was ignored"
+            )
+        }
+        Err(err) => tracing::warn!("could not read the .env file: {err}"),
+    });
+    std::env::var(var_name(profile, setting)).ok()
+}
+
+/// Whether the on/off `setting` is turned on for `profile`: set to anything other than nothing,
+/// `0`, `false`, `no`, or `off`.
+pub fn is_on(profile: Option<&str>, setting: &str) -> bool {
+    get(profile, setting).is_some_and(|value| {
+        let value = value.trim().to_ascii_lowercase();
+        !matches!(
+            value.as_str(),
+            "" | "0" | "f" | "false" | "n" | "no" | "off"
+        )
+    })
+}
+
+/// The value `contents`, the text of a `.env` file, gives `var`, if it sets it.
+pub fn env_file_value(contents: &str, var: &str) -> Option<String> {
+    let (_, value) = contents.lines().find_map(|line| assignment(line, var))?;
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some((quoted, _)) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.split_once(quote))
+        {
+            return Some(quoted.to_string());
+        }
+    }
+    let value = value.split_once(" #").map_or(value, |(value, _)| value);
+    Some(value.trim_end().to_string())
+}
+
+/// `contents`, the text of a `.env` file, with each line which sets `var` changed to set it to
+/// `value` instead. Every other line is left as it is.
+pub fn set_env_file_value(contents: &str, var: &str, value: &str) -> String {
+    contents
+        .split_inclusive('\n')
+        .map(|line| {
+            let text = line.trim_end_matches(['\n', '\r']);
+            let ending = &line[text.len()..];
+            match assignment(text, var) {
+                Some((start, _)) => format!("{start}{value}{ending}"),
+                None => line.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Splits `line` of a `.env` file into everything up to the `=` and the value after it, if it
+/// sets `var`.
+fn assignment<'a>(line: &'a str, var: &str) -> Option<(&'a str, &'a str)> {
+    let line_start = line.trim_start();
+    let name = line_start
+        .strip_prefix("export ")
+        .map_or(line_start, str::trim_start);
+    let value = name.strip_prefix(var)?.trim_start().strip_prefix('=')?;
+    Some((&line[..line.len() - value.len()], value))
+}
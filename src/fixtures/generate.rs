@@ -0,0 +1,632 @@
+//! Fake workspace content, generated from a seed, for performance testing and workshop exercises
+//! which need more to work with than the canned [responses](super::responses).
+//!
+//! The content is made up of collections of nested documents with headings, lists, code blocks,
+//! tables, and links to each other, so it exercises the same code paths as a real workspace. The
+//! same seed always generates the same content.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use serde_json::{json, Value};
+
+use crate::logic::collections::{self, CollectionWriter, DocumentNode};
+use crate::logic::documents::{DocumentSummary, DocumentWriter, NewDocument};
+use crate::logic::time::utc_timestamp;
+use crate::logic::ConnectionError;
+
+/// How many levels deep documents are nested at most.
+const MAX_DEPTH: usize = 3;
+/// When the generated documents start being created: 2024-01-01T00:00:00Z.
+const EPOCH: u64 = 1_704_067_200;
+/// How many items a page of a listing has when the request doesn't say.
+const DEFAULT_PAGE_SIZE: usize = 25;
+
+const TEAMS: &[&str] = &[
+    "Engineering",
+    "Product",
+    "Design",
+    "Support",
+    "Operations",
+    "Security",
+    "Marketing",
+    "Sales",
+    "People",
+    "Finance",
+    "Legal",
+    "Data",
+];
+const TOPICS: &[&str] = &[
+    "Deploy",
+    "Onboarding",
+    "Incident",
+    "Release",
+    "Billing",
+    "Search",
+    "Login",
+    "Payments",
+    "Mobile app",
+    "Database",
+    "API",
+    "Caching",
+    "Pricing",
+    "Hiring",
+    "Quarterly",
+    "Backup",
+    "Alerting",
+    "Roadmap",
+    "Accessibility",
+    "Vendor",
+];
+const KINDS: &[&str] = &[
+    "runbook",
+    "checklist",
+    "design review",
+    "notes",
+    "guide",
+    "postmortem",
+    "FAQ",
+    "plan",
+    "spec",
+    "overview",
+    "retrospective",
+    "playbook",
+];
+const HEADINGS: &[&str] = &[
+    "Background",
+    "Goals",
+    "How it works",
+    "Steps",
+    "Open questions",
+    "Rollout",
+    "Risks",
+    "Timeline",
+    "Who to ask",
+    "Troubleshooting",
+];
+const WORDS: &[&str] = &[
+    "the",
+    "team",
+    "service",
+    "release",
+    "customer",
+    "request",
+    "dashboard",
+    "queue",
+    "deploy",
+    "staging",
+    "production",
+    "review",
+    "owner",
+    "alert",
+    "metric",
+    "latency",
+    "budget",
+    "config",
+    "migration",
+    "endpoint",
+    "rollback",
+    "incident",
+    "weekly",
+    "schedule",
+    "backlog",
+    "handoff",
+    "on-call",
+    "ticket",
+    "feature",
+    "flag",
+    "account",
+    "invoice",
+    "region",
+    "cluster",
+    "cache",
+    "index",
+    "report",
+    "policy",
+    "checklist",
+    "access",
+    "and",
+    "with",
+    "before",
+    "after",
+    "every",
+    "should",
+    "can",
+    "needs",
+    "until",
+    "when",
+];
+const AUTHORS: &[&str] = &[
+    "Ada Lovelace",
+    "Grace Hopper",
+    "Alan Turing",
+    "Katherine Johnson",
+    "Edsger Dijkstra",
+    "Barbara Liskov",
+];
+
+/// A made-up workspace, full of collections of documents.
+#[derive(Debug, Clone)]
+pub struct FakeWorkspace {
+    pub collections: Vec<FakeCollection>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeCollection {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// The collection's documents, each one after the document it's nested under.
+    pub documents: Vec<FakeDocument>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeDocument {
+    pub id: String,
+    /// The short ID at the end of the document's link.
+    pub url_id: String,
+    pub title: String,
+    pub text: String,
+    /// Which of the collection's documents this one is nested under, if any.
+    pub parent: Option<usize>,
+    /// When the document was created and last updated, as Unix times.
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub revision: u64,
+    /// Who last updated the document.
+    pub author: &'static str,
+}
+
+/// What [FakeWorkspace::create_in] made.
+#[derive(Debug, Clone)]
+pub struct Created {
+    pub collections: Vec<collections::Collection>,
+    pub documents: usize,
+}
+
+impl FakeWorkspace {
+    /// Generates `documents` documents spread across `collections` collections, picked by `seed`.
+    /// With no collections, there's nowhere to put any documents, so the workspace is empty.
+    pub fn generate(documents: usize, collections: usize, seed: u64) -> Self {
+        let mut rng = Rng(seed);
+        let mut collections: Vec<_> = (0..collections)
+            .map(|index| {
+                let team = TEAMS[index % TEAMS.len()];
+                let name = match index / TEAMS.len() {
+                    0 => team.to_string(),
+                    round => format!("{team} {}", round + 1),
+                };
+                FakeCollection {
+                    id: rng.uuid(),
+                    description: format!("Everything the {team} team writes down."),
+                    name,
+                    documents: Vec::new(),
+                }
+            })
+            .collect();
+        if collections.is_empty() {
+            return Self { collections };
+        }
+
+        let mut titles = HashSet::new();
+        // Where each document ended up, to link to the ones before it
+        let mut placed: Vec<(usize, usize)> = Vec::with_capacity(documents);
+        let mut depths: Vec<Vec<usize>> = vec![Vec::new(); collections.len()];
+        for _ in 0..documents {
+            let collection = rng.below(collections.len());
+            let title = unique_title(&mut rng, &mut titles);
+            let siblings = &collections[collection].documents;
+            let parent = (!siblings.is_empty() && rng.below(10) < 4)
+                .then(|| rng.below(siblings.len()))
+                .filter(|&parent| depths[collection][parent] + 1 < MAX_DEPTH);
+            let links: Vec<_> = (0..rng.below(3))
+                .filter(|_| !placed.is_empty())
+                .map(|_| {
+                    let (collection, index) = placed[rng.below(placed.len())];
+                    let linked = &collections[collection].documents[index];
+                    (linked.title.clone(), linked.url())
+                })
+                .collect();
+
+            let created_at = EPOCH + rng.below(365 * 86_400) as u64;
+            let document = FakeDocument {
+                id: rng.uuid(),
+                url_id: rng.url_id(),
+                text: document_text(&mut rng, &title, &links),
+                title,
+                parent,
+                created_at,
+                updated_at: created_at + rng.below(60 * 86_400) as u64,
+                revision: 1 + rng.below(40) as u64,
+                author: AUTHORS[rng.below(AUTHORS.len())],
+            };
+            let depth = parent.map_or(0, |parent| depths[collection][parent] + 1);
+            depths[collection].push(depth);
+            placed.push((collection, collections[collection].documents.len()));
+            collections[collection].documents.push(document);
+        }
+
+        Self { collections }
+    }
+
+    /// How many documents there are across every collection.
+    pub fn document_count(&self) -> usize {
+        self.collections
+            .iter()
+            .map(|collection| collection.documents.len())
+            .sum()
+    }
+
+    /// The document with the ID or short ID `id`, along with the collection it's in.
+    pub fn document(&self, id: &str) -> Option<(&FakeCollection, &FakeDocument)> {
+        self.collections.iter().find_map(|collection| {
+            let document = collection
+                .documents
+                .iter()
+                .find(|document| document.id == id || document.url_id == id)?;
+            Some((collection, document))
+        })
+    }
+
+    /// Creates the collections and documents through `writer`, e.g. in a scratch workspace, calling
+    /// `on_created` with each document once it has been.
+    ///
+    /// Links between the documents point at the IDs they were generated with, so they don't lead
+    /// anywhere in a real workspace.
+    pub fn create_in<W>(
+        &self,
+        writer: &W,
+        mut on_created: impl FnMut(&FakeDocument, &DocumentSummary),
+    ) -> Result<Created, ConnectionError>
+    where
+        W: CollectionWriter + DocumentWriter,
+    {
+        let mut created = Created {
+            collections: Vec::new(),
+            documents: 0,
+        };
+        for collection in &self.collections {
+            let new_collection = collections::create_collection(
+                writer,
+                &collection.name,
+                Some(&collection.description),
+                false,
+            )?;
+            let mut ids: Vec<String> = Vec::with_capacity(collection.documents.len());
+            for document in &collection.documents {
+                let summary = DocumentWriter::create(
+                    writer,
+                    &NewDocument {
+                        title: &document.title,
+                        text: &document.text,
+                        collection_id: &new_collection.id,
+                        parent_document_id: document.parent.map(|parent| ids[parent].as_str()),
+                    },
+                )?;
+                on_created(document, &summary);
+                ids.push(summary.id);
+                created.documents += 1;
+            }
+            created.collections.push(new_collection);
+        }
+
+        Ok(created)
+    }
+
+    /// The body getOutline would answer API method `method` with, given the JSON `request`, or
+    /// `None` if there isn't one to find. Only the methods for reading the workspace are
+    /// answered.
+    pub(super) fn answer(&self, method: &str, request: &Value) -> Option<Value> {
+        let id = request["id"].as_str().unwrap_or_default();
+        let body = match method {
+            "auth.info" => data(json!({
+                "user": {"id": "fake-user", "name": AUTHORS[0], "email": "ada@example.com"},
+                "team": {
+                    "id": "fake-team",
+                    "name": "Fake Workspace",
+                    "url": "http://localhost",
+                    "subdomain": "fake",
+                },
+            })),
+            "collections.list" => page(
+                request,
+                self.collections
+                    .iter()
+                    .map(FakeCollection::to_json)
+                    .collect(),
+            ),
+            "collections.info" => data(self.collection(id)?.to_json()),
+            "collections.documents" => data(json!(self.collection(id)?.tree())),
+            "documents.info" => {
+                let (collection, document) = self.document(id)?;
+                data(document.to_json(collection))
+            }
+            "documents.list" => {
+                let collection_id = request["collectionId"].as_str();
+                let mut documents: Vec<_> = self
+                    .documents()
+                    .filter(|(collection, _)| collection_id.is_none_or(|id| collection.id == id))
+                    .collect();
+                documents.sort_by_key(|(_, document)| Reverse(document.updated_at));
+                let documents = documents
+                    .into_iter()
+                    .map(|(collection, document)| document.to_json(collection))
+                    .collect();
+                page(request, documents)
+            }
+            "documents.drafts" => page(request, Vec::new()),
+            "documents.search" => {
+                let query = request["query"].as_str().unwrap_or_default().to_lowercase();
+                let collection_id = request["collectionId"].as_str();
+                let results = self
+                    .documents()
+                    .filter(|(collection, _)| collection_id.is_none_or(|id| collection.id == id))
+                    .filter_map(|(collection, document)| {
+                        let context = search_context(&document.text, &query)?;
+                        Some(json!({
+                            "context": context,
+                            "ranking": 1.0,
+                            "document": document.to_json(collection),
+                        }))
+                    })
+                    .collect();
+                page(request, results)
+            }
+            _ => return None,
+        };
+
+        Some(body)
+    }
+
+    fn collection(&self, id: &str) -> Option<&FakeCollection> {
+        self.collections
+            .iter()
+            .find(|collection| collection.id == id)
+    }
+
+    fn documents(&self) -> impl Iterator<Item = (&FakeCollection, &FakeDocument)> {
+        self.collections.iter().flat_map(|collection| {
+            collection
+                .documents
+                .iter()
+                .map(move |document| (collection, document))
+        })
+    }
+}
+
+impl FakeCollection {
+    /// How the collection's documents are nested, as `collections.documents` lists them.
+    pub fn tree(&self) -> Vec<DocumentNode> {
+        self.children(None)
+    }
+
+    fn children(&self, parent: Option<usize>) -> Vec<DocumentNode> {
+        self.documents
+            .iter()
+            .enumerate()
+            .filter(|(_, document)| document.parent == parent)
+            .map(|(index, document)| DocumentNode {
+                id: document.id.clone(),
+                title: document.title.clone(),
+                children: self.children(Some(index)),
+            })
+            .collect()
+    }
+
+    fn to_json(&self) -> Value {
+        let created_at = self
+            .documents
+            .iter()
+            .map(|document| document.created_at)
+            .min();
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "description": self.description,
+            "permission": "read_write",
+            "sharing": true,
+            "createdAt": utc_timestamp(created_at.unwrap_or(EPOCH)),
+            "updatedAt": utc_timestamp(created_at.unwrap_or(EPOCH)),
+        })
+    }
+}
+
+impl FakeDocument {
+    /// Where the document would be found in the web app, relative to the workspace's URL.
+    pub fn url(&self) -> String {
+        let slug: String = self
+            .title
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        format!("/doc/{slug}-{}", self.url_id)
+    }
+
+    fn to_json(&self, collection: &FakeCollection) -> Value {
+        let parent = self.parent.map(|parent| &collection.documents[parent].id);
+        json!({
+            "id": self.id,
+            "urlId": self.url_id,
+            "title": self.title,
+            "url": self.url(),
+            "text": self.text,
+            "collectionId": collection.id,
+            "parentDocumentId": parent,
+            "createdAt": utc_timestamp(self.created_at),
+            "updatedAt": utc_timestamp(self.updated_at),
+            "publishedAt": utc_timestamp(self.created_at),
+            "revision": self.revision,
+            "createdBy": {"name": self.author},
+            "updatedBy": {"name": self.author},
+        })
+    }
+}
+
+/// A response with `data` in getOutline's envelope.
+fn data(data: Value) -> Value {
+    json!({"ok": true, "status": 200, "data": data})
+}
+
+/// The page of `items` the `offset` and `limit` in `request` ask for.
+fn page(request: &Value, items: Vec<Value>) -> Value {
+    let number = |field: &str, default| {
+        request[field]
+            .as_u64()
+            .map_or(default, |n| usize::try_from(n).unwrap_or(usize::MAX))
+    };
+    let offset = number("offset", 0);
+    let limit = number("limit", DEFAULT_PAGE_SIZE);
+    let items: Vec<_> = items.into_iter().skip(offset).take(limit).collect();
+    json!({
+        "ok": true,
+        "status": 200,
+        "pagination": {"offset": offset, "limit": limit},
+        "data": items,
+    })
+}
+
+/// The bit of `text` around the first place it has `query` (already in lowercase), with the match
+/// in `<b>` tags the way getOutline marks it.
+fn search_context(text: &str, query: &str) -> Option<String> {
+    if query.is_empty() {
+        return None;
+    }
+    let line = text
+        .lines()
+        .find(|line| line.to_lowercase().contains(query))?;
+    let lowercase = line.to_lowercase();
+    let start = lowercase.find(query)?;
+    // Lowercasing can change how long some characters are, in which case the tags are left out
+    match (
+        line.get(..start),
+        line.get(start..start + query.len()),
+        line.get(start + query.len()..),
+    ) {
+        (Some(before), Some(matched), Some(after)) if lowercase.len() == line.len() => {
+            Some(format!("{before}<b>{matched}</b>{after}"))
+        }
+        _ => Some(line.to_string()),
+    }
+}
+
+/// A title which isn't one of the `taken` ones yet, which it's added to.
+fn unique_title(rng: &mut Rng, taken: &mut HashSet<String>) -> String {
+    let base = format!(
+        "{} {}",
+        TOPICS[rng.below(TOPICS.len())],
+        KINDS[rng.below(KINDS.len())]
+    );
+    let mut title = base.clone();
+    let mut copy = 1;
+    while taken.contains(&title) {
+        copy += 1;
+        title = format!("{base} {copy}");
+    }
+    taken.insert(title.clone());
+    title
+}
+
+/// The markdown of a document called `title`, linking to the `(title, url)` of each of `links`.
+fn document_text(rng: &mut Rng, title: &str, links: &[(String, String)]) -> String {
+    let count = 2 + rng.below(3);
+    let mut text = format!("{}\n", sentences(rng, count));
+    for (linked_title, url) in links {
+        text.push_str(&format!("\nSee [{linked_title}]({url}) for more.\n"));
+    }
+
+    for section in 0..1 + rng.below(4) {
+        let heading = HEADINGS[(section + rng.below(HEADINGS.len())) % HEADINGS.len()];
+        text.push_str(&format!("\n## {heading}\n\n"));
+        match rng.below(5) {
+            0 => {
+                for step in 1..=2 + rng.below(4) {
+                    text.push_str(&format!("{step}. {}\n", sentences(rng, 1)));
+                }
+            }
+            1 => {
+                for _ in 0..2 + rng.below(4) {
+                    text.push_str(&format!("- {}\n", sentences(rng, 1)));
+                }
+            }
+            2 => {
+                let command = title.to_lowercase().replace(' ', "-");
+                text.push_str(&format!(
+                    "```shell\n./scripts/{command}.sh --env staging\n./scripts/{command}.sh \
+                     --env production\n```\n"
+                ));
+            }
+            3 => {
+                text.push_str("| Step | Owner | Status |\n|---|---|---|\n");
+                for row in 1..=1 + rng.below(4) {
+                    let owner = AUTHORS[rng.below(AUTHORS.len())];
+                    let status = ["Done", "In progress", "Blocked"][rng.below(3)];
+                    text.push_str(&format!("| {row} | {owner} | {status} |\n"));
+                }
+            }
+            _ => {
+                let count = 3 + rng.below(5);
+                text.push_str(&format!("{}\n", sentences(rng, count)));
+            }
+        }
+    }
+
+    text
+}
+
+/// `count` sentences of made-up text.
+fn sentences(rng: &mut Rng, count: usize) -> String {
+    (0..count)
+        .map(|_| {
+            let words: Vec<_> = (0..6 + rng.below(10))
+                .map(|_| WORDS[rng.below(WORDS.len())])
+                .collect();
+            let sentence = words.join(" ");
+            let mut chars = sentence.chars();
+            let first = chars.next().map(|c| c.to_uppercase().to_string());
+            format!("{}{}.", first.unwrap_or_default(), chars.as_str())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A small random number generator (SplitMix64), which is all it takes to make up content, and
+/// always gives the same numbers for the same seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 up to (but not including) `bound`, which has to be above 0.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    /// An ID shaped like the UUIDs getOutline gives everything.
+    fn uuid(&mut self) -> String {
+        let (high, low) = (self.next(), self.next());
+        format!(
+            "{:08x}-{:04x}-4{:03x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0xfff,
+            0x8000 | (low >> 48) & 0x3fff,
+            low & 0xffff_ffff_ffff
+        )
+    }
+
+    /// A short ID like the ones at the end of links to documents.
+    fn url_id(&mut self) -> String {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+        (0..10)
+            .map(|_| char::from(ALPHABET[self.below(ALPHABET.len())]))
+            .collect()
+    }
+}
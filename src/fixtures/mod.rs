@@ -0,0 +1,104 @@
+//! Canned getOutline responses and builders for the crate's types, for writing deterministic tests
+//! against code which uses this crate. Only available with the `test-fixtures` feature.
+//!
+//! The responses are real-shaped API payloads (including fields this crate ignores), so they can
+//! be served by a mock HTTP server like [MockServer] as well as decoded directly with [decode].
+//! [generate] makes up whole workspaces of content for the server to answer from instead.
+
+pub mod generate;
+mod server;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::getoutline_connection::{ConfigError, GetOutlineClient};
+use crate::logic::documents::{Document, DocumentSummary};
+
+pub use server::MockServer;
+
+/// Raw JSON bodies, as getOutline sends them.
+pub mod responses {
+    /// `documents.info` for a published document.
+    pub const DOCUMENT_INFO: &str = include_str!("responses/documents.info.json");
+    /// `documents.info` for a document which is still a draft.
+    pub const DRAFT_DOCUMENT_INFO: &str = include_str!("responses/documents.info.draft.json");
+    /// `documents.search` with two matches for "dashboard".
+    pub const DOCUMENT_SEARCH: &str = include_str!("responses/documents.search.json");
+    /// `collections.info` for the collection the documents above live in.
+    pub const COLLECTION_INFO: &str = include_str!("responses/collections.info.json");
+    /// `apiKeys.list` with two keys, one of them [API_KEY](super::API_KEY).
+    pub const API_KEYS_LIST: &str = include_str!("responses/apiKeys.list.json");
+    /// The error body sent with a 401 when the API key is missing or revoked.
+    pub const UNAUTHORIZED: &str = include_str!("responses/unauthorized.json");
+    /// The error body sent with a 404 for an unknown ID.
+    pub const NOT_FOUND: &str = include_str!("responses/not_found.json");
+}
+
+/// An API key which looks like a real one, for tests which need a client but never reach
+/// getOutline.
+pub const API_KEY: &str = "ol_api_0123456789abcdefghijklmnopqrstuvwxyzABCD";
+
+/// Builds a client which authenticates with [API_KEY] against `base_url`, e.g. a mock server.
+pub fn client(base_url: &str) -> Result<GetOutlineClient, ConfigError> {
+    GetOutlineClient::new(base_url, API_KEY)
+}
+
+/// Decodes the payload of one of the canned [responses], unwrapping its `data` envelope.
+///
+/// Panics if the response doesn't fit `T`, since that's a mistake in the test.
+pub fn decode<T: DeserializeOwned>(response: &str) -> T {
+    #[derive(Deserialize)]
+    struct Envelope<T> {
+        data: T,
+    }
+
+    match serde_json::from_str::<Envelope<T>>(response) {
+        Ok(envelope) => envelope.data,
+        Err(err) => panic!("fixture doesn't match the expected type: {err}"),
+    }
+}
+
+/// Builds a [DocumentSummary], e.g. an entry in a listing.
+pub fn document_summary(id: &str, title: &str) -> DocumentSummary {
+    DocumentSummary {
+        id: id.to_string(),
+        title: title.to_string(),
+        url_id: None,
+        url: None,
+    }
+}
+
+/// Builds a [Document], starting from a published document with a title and no content.
+#[derive(Debug, Clone)]
+pub struct DocumentBuilder {
+    document: Document,
+}
+
+impl DocumentBuilder {
+    pub fn new(title: &str) -> Self {
+        Self {
+            document: Document {
+                id: String::new(),
+                title: title.to_string(),
+                text: String::new(),
+                published_at: Some("2024-01-01T00:00:00.000Z".to_string()),
+                url: None,
+                revision: Some(1),
+            },
+        }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.document.text = text.to_string();
+        self
+    }
+
+    pub fn draft(mut self) -> Self {
+        self.document.published_at = None;
+        self
+    }
+
+    pub fn build(self) -> Document {
+        self.document
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use super::generate::FakeWorkspace;
+use super::responses;
+
+/// A getOutline stand-in running on a background thread, which answers each API method with a
+/// fixed body, or from a [FakeWorkspace]. Methods it doesn't know get a 404 with
+/// [responses::NOT_FOUND].
+///
+/// The server runs until the process exits, so it's meant for tests and benchmarks.
+#[derive(Debug)]
+pub struct MockServer {
+    url: String,
+}
+
+/// What the server answers requests with.
+enum Routes {
+    /// A body for each API method, whatever the request.
+    Fixed(HashMap<String, String>),
+    /// getOutline's answers for a workspace with the fake workspace's content.
+    Workspace(FakeWorkspace),
+}
+
+impl MockServer {
+    /// Starts a server on a free local port, answering each `(method, body)` in `routes`, e.g.
+    /// `("documents.info", responses::DOCUMENT_INFO)`.
+    pub fn start(routes: &[(&str, &str)]) -> io::Result<Self> {
+        let routes = routes
+            .iter()
+            .map(|(method, body)| (method.to_string(), body.to_string()))
+            .collect();
+        Self::listen("127.0.0.1:0", Routes::Fixed(routes))
+    }
+
+    /// Starts a server on `address` (port 0 picks a free one) which answers the API methods for
+    /// reading a workspace as if it had `workspace`'s content, e.g. `documents.info` by ID and
+    /// `documents.list` a page at a time.
+    pub fn start_workspace(address: &str, workspace: FakeWorkspace) -> io::Result<Self> {
+        Self::listen(address, Routes::Workspace(workspace))
+    }
+
+    fn listen(address: &str, routes: Routes) -> io::Result<Self> {
+        let routes = Arc::new(routes);
+        let listener = TcpListener::bind(address)?;
+        let url = format!("http://{}", listener.local_addr()?);
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let routes = Arc::clone(&routes);
+                thread::spawn(move || serve(stream, &routes));
+            }
+        });
+
+        Ok(Self { url })
+    }
+
+    /// The base URL to point a client at, as passed to [super::client].
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Routes {
+    /// The status and body to answer a request for `method` with, sent `body`.
+    fn answer(&self, method: &str, body: &[u8]) -> (&'static str, String) {
+        let found = match self {
+            Routes::Fixed(routes) => routes.get(method).cloned(),
+            Routes::Workspace(workspace) => {
+                let request = serde_json::from_slice(body).unwrap_or_default();
+                workspace
+                    .answer(method, &request)
+                    .map(|answer| answer.to_string())
+            }
+        };
+        match found {
+            Some(body) => ("200 OK", body),
+            None => ("404 Not Found", responses::NOT_FOUND.to_string()),
+        }
+    }
+}
+
+/// Answers requests on one connection until the client closes it.
+fn serve(stream: TcpStream, routes: &Routes) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+
+    while let Ok(Some((method, body))) = read_request(&mut reader) {
+        let (status, body) = routes.answer(&method, &body);
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        if writer.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads one request, returning the API method it was for (like `documents.info`) and its body,
+/// or nothing if the connection was closed.
+fn read_request(reader: &mut impl BufRead) -> io::Result<Option<(String, Vec<u8>)>> {
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line)? == 0 {
+        return Ok(None);
+    }
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let method = path.rsplit('/').next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = Vec::new();
+    reader
+        .by_ref()
+        .take(content_length)
+        .read_to_end(&mut body)?;
+
+    Ok(Some((method, body)))
+}
@@ -0,0 +1,30 @@
+use serde::Serialize;
+
+use super::{GetOutlineClient, IdRequest};
+use crate::logic::api_keys::{ApiKey, ApiKeyDeleter, ApiKeyReader, ApiKeyWriter};
+use crate::logic::{ConnectionError, Page};
+
+impl ApiKeyReader for GetOutlineClient {
+    fn list(&self, page: Page) -> Result<Vec<ApiKey>, ConnectionError> {
+        let response = self.post("apiKeys.list", &page)?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+struct CreateRequest<'a> {
+    name: &'a str,
+}
+
+impl ApiKeyWriter for GetOutlineClient {
+    fn create(&self, name: &str) -> Result<ApiKey, ConnectionError> {
+        let response = self.post("apiKeys.create", &CreateRequest { name })?;
+        Ok(response.data)
+    }
+}
+
+impl ApiKeyDeleter for GetOutlineClient {
+    fn delete(&self, id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data("apiKeys.delete", &IdRequest { id })
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use reqwest::blocking::multipart::{Form, Part};
+use reqwest::blocking::Client;
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+
+use super::{transport_error, Envelope, GetOutlineClient, IdRequest};
+use crate::logic::attachments::{
+    Attachment, AttachmentDeleter, AttachmentDownloader, AttachmentFile, AttachmentLister,
+    AttachmentUploader, NewAttachment,
+};
+use crate::logic::{ConnectionError, Page};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateRequest<'a> {
+    name: &'a str,
+    content_type: &'a str,
+    size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    document_id: Option<&'a str>,
+    preset: &'a str,
+}
+
+/// Where getOutline wants the file sent: either its own storage, or a presigned form for a
+/// storage bucket.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PendingUpload {
+    upload_url: String,
+    #[serde(default)]
+    form: HashMap<String, serde_json::Value>,
+    attachment: Attachment,
+}
+
+impl AttachmentUploader for GetOutlineClient {
+    fn upload(&self, attachment: &NewAttachment) -> Result<Attachment, ConnectionError> {
+        let request = CreateRequest {
+            name: attachment.name,
+            content_type: attachment.content_type,
+            size: attachment.content.len(),
+            document_id: attachment.document_id,
+            preset: "documentAttachment",
+        };
+        let response: Envelope<PendingUpload> = self.post("attachments.create", &request)?;
+        let pending = response.data;
+
+        let mut form = Form::new();
+        for (name, value) in pending.form {
+            let value = match value {
+                serde_json::Value::String(value) => value,
+                other => other.to_string(),
+            };
+            form = form.text(name, value);
+        }
+        let file = Part::bytes(attachment.content.to_vec())
+            .file_name(attachment.name.to_string())
+            .mime_str(attachment.content_type)
+            .map_err(transport_error)?;
+        form = form.part("file", file);
+
+        // Uploads to getOutline's own storage need the API key, but a storage bucket would
+        // refuse a request with an Authorization header it doesn't understand
+        let upload = if pending.upload_url.starts_with('/') {
+            self.http
+                .post(format!("{}{}", self.base_url, pending.upload_url))
+        } else {
+            Client::new().post(&pending.upload_url)
+        };
+        self.send(upload.multipart(form))?;
+
+        Ok(pending.attachment)
+    }
+}
+
+impl AttachmentDownloader for GetOutlineClient {
+    fn download(&self, id: &str) -> Result<AttachmentFile, ConnectionError> {
+        // The endpoint redirects to wherever the file is stored, which the client follows
+        let response =
+            self.send_for_response(self.request("attachments.redirect").json(&IdRequest { id }))?;
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let content = self.read_body(response)?.to_vec();
+
+        Ok(AttachmentFile {
+            content_type,
+            content,
+        })
+    }
+}
+
+impl AttachmentLister for GetOutlineClient {
+    fn list(&self, page: Page) -> Result<Vec<Attachment>, ConnectionError> {
+        let response = self.post("attachments.list", &page)?;
+        Ok(response.data)
+    }
+}
+
+impl AttachmentDeleter for GetOutlineClient {
+    fn delete(&self, id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data("attachments.delete", &IdRequest { id })
+    }
+}
@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+use super::GetOutlineClient;
+use crate::logic::catalog::{CatalogDocument, CatalogReader};
+use crate::logic::digest::RecentlyUpdatedReader;
+use crate::logic::{ConnectionError, Page};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentlyUpdatedRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collection_id: Option<&'a str>,
+    sort: &'static str,
+    direction: &'static str,
+    #[serde(flatten)]
+    page: Page,
+}
+
+impl CatalogReader for GetOutlineClient {
+    fn documents(&self, page: Page) -> Result<Vec<CatalogDocument>, ConnectionError> {
+        let response = self.post("documents.list", &page)?;
+        Ok(response.data)
+    }
+}
+
+impl RecentlyUpdatedReader for GetOutlineClient {
+    fn recently_updated(
+        &self,
+        collection_id: Option<&str>,
+        page: Page,
+    ) -> Result<Vec<CatalogDocument>, ConnectionError> {
+        let request = RecentlyUpdatedRequest {
+            collection_id,
+            sort: "updatedAt",
+            direction: "DESC",
+            page,
+        };
+        let response = self.post("documents.list", &request)?;
+        Ok(response.data)
+    }
+}
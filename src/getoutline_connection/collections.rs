@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Envelope, GetOutlineClient, IdRequest};
+use crate::logic::collections::{
+    Collection, CollectionDeleter, CollectionExporter, CollectionReader, CollectionTreeReader,
+    CollectionUpdate, CollectionUpdater, CollectionWriter, DocumentNode, ExportFormat,
+    NewCollection, WorkspaceExporter,
+};
+use crate::logic::file_operations::FileOperation;
+use crate::logic::memberships::Permission;
+use crate::logic::{ConnectionError, Page};
+
+impl CollectionReader for GetOutlineClient {
+    fn info(&self, id: &str) -> Result<Collection, ConnectionError> {
+        let response = self.post("collections.info", &IdRequest { id })?;
+        Ok(response.data)
+    }
+
+    fn list(&self, page: Page) -> Result<Vec<Collection>, ConnectionError> {
+        let response = self.post("collections.list", &page)?;
+        Ok(response.data)
+    }
+}
+
+impl CollectionTreeReader for GetOutlineClient {
+    fn document_tree(&self, collection_id: &str) -> Result<Vec<DocumentNode>, ConnectionError> {
+        let response = self.post("collections.documents", &IdRequest { id: collection_id })?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+struct CreateRequest<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    permission: Option<Permission>,
+}
+
+impl CollectionWriter for GetOutlineClient {
+    fn create(&self, collection: &NewCollection) -> Result<Collection, ConnectionError> {
+        let request = CreateRequest {
+            name: collection.name,
+            description: collection.description,
+            permission: collection.permission,
+        };
+
+        let response = self.post("collections.create", &request)?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+struct UpdateRequest<'a> {
+    id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<&'a str>,
+}
+
+impl CollectionUpdater for GetOutlineClient {
+    fn update(&self, update: &CollectionUpdate) -> Result<Collection, ConnectionError> {
+        let request = UpdateRequest {
+            id: update.id,
+            name: update.name,
+            description: update.description,
+            color: update.color,
+        };
+
+        let response = self.post("collections.update", &request)?;
+        Ok(response.data)
+    }
+}
+
+impl CollectionDeleter for GetOutlineClient {
+    fn delete(&self, id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data("collections.delete", &IdRequest { id })
+    }
+}
+
+#[derive(Serialize)]
+struct ExportRequest<'a> {
+    id: &'a str,
+    format: ExportFormat,
+}
+
+/// What getOutline sends back when it starts a background job.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartedOperation {
+    file_operation: FileOperation,
+}
+
+impl CollectionExporter for GetOutlineClient {
+    fn export(&self, id: &str, format: ExportFormat) -> Result<FileOperation, ConnectionError> {
+        let response: Envelope<StartedOperation> =
+            self.post("collections.export", &ExportRequest { id, format })?;
+        Ok(response.data.file_operation)
+    }
+}
+
+#[derive(Serialize)]
+struct ExportAllRequest {
+    format: ExportFormat,
+}
+
+impl WorkspaceExporter for GetOutlineClient {
+    fn export_all(&self, format: ExportFormat) -> Result<FileOperation, ConnectionError> {
+        let response: Envelope<StartedOperation> =
+            self.post("collections.export_all", &ExportAllRequest { format })?;
+        Ok(response.data.file_operation)
+    }
+}
@@ -0,0 +1,19 @@
+use serde::Serialize;
+
+use super::GetOutlineClient;
+use crate::logic::comments::{Comment, CommentWriter};
+use crate::logic::ConnectionError;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateRequest<'a> {
+    document_id: &'a str,
+    text: &'a str,
+}
+
+impl CommentWriter for GetOutlineClient {
+    fn create(&self, document_id: &str, text: &str) -> Result<Comment, ConnectionError> {
+        let response = self.post("comments.create", &CreateRequest { document_id, text })?;
+        Ok(response.data)
+    }
+}
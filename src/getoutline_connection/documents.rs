@@ -0,0 +1,161 @@
+use serde::Serialize;
+
+use super::{GetOutlineClient, IdRequest};
+use crate::logic::documents::{
+    Document, DocumentDeleter, DocumentExporter, DocumentLister, DocumentReader, DocumentSummary,
+    DocumentUnpublisher, DocumentUpdate, DocumentUpdater, DocumentWriter, FileFormat, NewDocument,
+    SearchQuery, SearchReader, SearchResult,
+};
+use crate::logic::{ConnectionError, Page};
+
+impl DocumentReader for GetOutlineClient {
+    fn info(&self, id: &str) -> Result<Document, ConnectionError> {
+        let response = self.post("documents.info", &IdRequest { id })?;
+        Ok(response.data)
+    }
+}
+
+impl DocumentLister for GetOutlineClient {
+    fn list(&self, page: Page) -> Result<Vec<DocumentSummary>, ConnectionError> {
+        let response = self.post("documents.list", &page)?;
+        Ok(response.data)
+    }
+
+    fn drafts(&self, page: Page) -> Result<Vec<DocumentSummary>, ConnectionError> {
+        let response = self.post("documents.drafts", &page)?;
+        Ok(response.data)
+    }
+}
+
+impl DocumentExporter for GetOutlineClient {
+    fn export(&self, id: &str) -> Result<String, ConnectionError> {
+        let response = self.post("documents.export", &IdRequest { id })?;
+        Ok(response.data)
+    }
+
+    fn export_file(&self, id: &str, format: FileFormat) -> Result<Vec<u8>, ConnectionError> {
+        let accept = match format {
+            FileFormat::Html => "text/html",
+            FileFormat::Pdf => "application/pdf",
+        };
+
+        self.post_for_bytes("documents.export", &IdRequest { id }, accept)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchRequest<'a> {
+    query: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collection_id: Option<&'a str>,
+    #[serde(flatten)]
+    page: Page,
+}
+
+impl SearchReader for GetOutlineClient {
+    fn search(
+        &self,
+        query: &SearchQuery,
+        page: Page,
+    ) -> Result<Vec<SearchResult>, ConnectionError> {
+        let request = SearchRequest {
+            query: query.text,
+            collection_id: query.collection_id,
+            page,
+        };
+
+        let response = self.post("documents.search", &request)?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateRequest<'a> {
+    title: &'a str,
+    text: &'a str,
+    collection_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_document_id: Option<&'a str>,
+    publish: bool,
+}
+
+impl DocumentWriter for GetOutlineClient {
+    fn create(&self, document: &NewDocument) -> Result<DocumentSummary, ConnectionError> {
+        let request = CreateRequest {
+            title: document.title,
+            text: document.text,
+            collection_id: document.collection_id,
+            parent_document_id: document.parent_document_id,
+            publish: true,
+        };
+
+        let response = self.post("documents.create", &request)?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+struct UpdateRequest<'a> {
+    id: &'a str,
+    text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+    append: bool,
+    #[serde(rename = "lastRevision", skip_serializing_if = "Option::is_none")]
+    last_revision: Option<u64>,
+}
+
+impl DocumentUpdater for GetOutlineClient {
+    fn update(&self, update: &DocumentUpdate) -> Result<DocumentSummary, ConnectionError> {
+        let request = UpdateRequest {
+            id: update.id,
+            text: update.text,
+            title: update.title,
+            append: update.append,
+            last_revision: update.expected_revision,
+        };
+
+        match self.post("documents.update", &request) {
+            Ok(response) => Ok(response.data),
+            Err(ConnectionError::Api { status, message })
+                if is_revision_conflict(status, &message) =>
+            {
+                Err(match update.expected_revision {
+                    Some(expected) => ConnectionError::RemoteChanged {
+                        id: update.id.to_string(),
+                        expected,
+                    },
+                    None => ConnectionError::Api { status, message },
+                })
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Whether an error from `documents.update` means `lastRevision` didn't match. getOutline reports
+/// it as a bad request ("Document has changed since last revision"); a 409 is treated the same way.
+fn is_revision_conflict(status: u16, message: &str) -> bool {
+    status == 409 || (status == 400 && message.to_ascii_lowercase().contains("revision"))
+}
+
+#[derive(Serialize)]
+struct DeleteRequest<'a> {
+    id: &'a str,
+    permanent: bool,
+}
+
+impl DocumentDeleter for GetOutlineClient {
+    fn delete(&self, id: &str, permanent: bool) -> Result<(), ConnectionError> {
+        self.post_without_data("documents.delete", &DeleteRequest { id, permanent })
+    }
+}
+
+impl DocumentUnpublisher for GetOutlineClient {
+    fn unpublish(&self, id: &str) -> Result<DocumentSummary, ConnectionError> {
+        let response = self.post("documents.unpublish", &IdRequest { id })?;
+        Ok(response.data)
+    }
+}
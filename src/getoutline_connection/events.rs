@@ -0,0 +1,33 @@
+use serde::Serialize;
+
+use super::GetOutlineClient;
+use crate::logic::events::{Event, EventQuery, EventReader};
+use crate::logic::{ConnectionError, Page};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actor_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    document_id: Option<&'a str>,
+    sort: &'a str,
+    direction: &'a str,
+    #[serde(flatten)]
+    page: Page,
+}
+
+impl EventReader for GetOutlineClient {
+    fn list(&self, query: &EventQuery, page: Page) -> Result<Vec<Event>, ConnectionError> {
+        let request = ListRequest {
+            actor_id: query.actor_id,
+            document_id: query.document_id,
+            sort: "createdAt",
+            direction: "DESC",
+            page,
+        };
+
+        let response = self.post("events.list", &request)?;
+        Ok(response.data)
+    }
+}
@@ -0,0 +1,43 @@
+use serde::Serialize;
+
+use super::{Envelope, GetOutlineClient, IdRequest};
+use crate::logic::file_operations::{
+    FileOperation, FileOperationDeleter, FileOperationKind, FileOperationReader,
+};
+use crate::logic::{ConnectionError, Page};
+
+#[derive(Serialize)]
+struct ListRequest {
+    #[serde(rename = "type")]
+    kind: FileOperationKind,
+    #[serde(flatten)]
+    page: Page,
+}
+
+impl FileOperationReader for GetOutlineClient {
+    fn list(
+        &self,
+        kind: FileOperationKind,
+        page: Page,
+    ) -> Result<Vec<FileOperation>, ConnectionError> {
+        let response: Envelope<Vec<FileOperation>> =
+            self.post("fileOperations.list", &ListRequest { kind, page })?;
+        Ok(response.data)
+    }
+
+    fn info(&self, id: &str) -> Result<FileOperation, ConnectionError> {
+        let response = self.post("fileOperations.info", &IdRequest { id })?;
+        Ok(response.data)
+    }
+
+    fn download(&self, id: &str) -> Result<Vec<u8>, ConnectionError> {
+        // This redirects to wherever the file is stored, which the HTTP client follows
+        self.post_for_bytes("fileOperations.redirect", &IdRequest { id }, "*/*")
+    }
+}
+
+impl FileOperationDeleter for GetOutlineClient {
+    fn delete(&self, id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data("fileOperations.delete", &IdRequest { id })
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Envelope, GetOutlineClient, IdRequest};
+use crate::logic::groups::{Group, GroupReader};
+use crate::logic::users::User;
+use crate::logic::{ConnectionError, Page};
+
+/// One page of `groups.list`, which also carries some of each group's memberships.
+#[derive(Deserialize)]
+struct GroupPage {
+    groups: Vec<Group>,
+}
+
+#[derive(Serialize)]
+struct MembersRequest<'a> {
+    id: &'a str,
+    #[serde(flatten)]
+    page: Page,
+}
+
+/// One page of `groups.memberships`, with the users the memberships are for.
+#[derive(Deserialize)]
+struct MemberPage {
+    users: Vec<User>,
+}
+
+impl GroupReader for GetOutlineClient {
+    fn list(&self, page: Page) -> Result<Vec<Group>, ConnectionError> {
+        let response: Envelope<GroupPage> = self.post("groups.list", &page)?;
+        Ok(response.data.groups)
+    }
+
+    fn info(&self, id: &str) -> Result<Group, ConnectionError> {
+        let response = self.post("groups.info", &IdRequest { id })?;
+        Ok(response.data)
+    }
+
+    fn members(&self, group_id: &str, page: Page) -> Result<Vec<User>, ConnectionError> {
+        let request = MembersRequest { id: group_id, page };
+        let response: Envelope<MemberPage> = self.post("groups.memberships", &request)?;
+        Ok(response.data.users)
+    }
+}
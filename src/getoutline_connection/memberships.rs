@@ -0,0 +1,152 @@
+use serde::Serialize;
+
+use super::GetOutlineClient;
+use crate::logic::memberships::{
+    CollectionMembershipWriter, CollectionPermission, MembershipPage, MembershipReader,
+    MembershipWriter, Permission,
+};
+use crate::logic::{ConnectionError, Page};
+
+#[derive(Serialize)]
+struct MembershipsRequest<'a> {
+    id: &'a str,
+    #[serde(flatten)]
+    page: Page,
+}
+
+impl MembershipReader for GetOutlineClient {
+    fn memberships(
+        &self,
+        document_id: &str,
+        page: Page,
+    ) -> Result<MembershipPage, ConnectionError> {
+        let response = self.post(
+            "documents.memberships",
+            &MembershipsRequest {
+                id: document_id,
+                page,
+            },
+        )?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddUserRequest<'a> {
+    id: &'a str,
+    user_id: &'a str,
+    permission: Permission,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoveUserRequest<'a> {
+    id: &'a str,
+    user_id: &'a str,
+}
+
+impl MembershipWriter for GetOutlineClient {
+    fn add_user(
+        &self,
+        document_id: &str,
+        user_id: &str,
+        permission: Permission,
+    ) -> Result<(), ConnectionError> {
+        self.post_without_data(
+            "documents.add_user",
+            &AddUserRequest {
+                id: document_id,
+                user_id,
+                permission,
+            },
+        )
+    }
+
+    fn remove_user(&self, document_id: &str, user_id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data(
+            "documents.remove_user",
+            &RemoveUserRequest {
+                id: document_id,
+                user_id,
+            },
+        )
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddCollectionUserRequest<'a> {
+    id: &'a str,
+    user_id: &'a str,
+    permission: CollectionPermission,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddGroupRequest<'a> {
+    id: &'a str,
+    group_id: &'a str,
+    permission: CollectionPermission,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoveGroupRequest<'a> {
+    id: &'a str,
+    group_id: &'a str,
+}
+
+impl CollectionMembershipWriter for GetOutlineClient {
+    fn add_user(
+        &self,
+        collection_id: &str,
+        user_id: &str,
+        permission: CollectionPermission,
+    ) -> Result<(), ConnectionError> {
+        self.post_without_data(
+            "collections.add_user",
+            &AddCollectionUserRequest {
+                id: collection_id,
+                user_id,
+                permission,
+            },
+        )
+    }
+
+    fn remove_user(&self, collection_id: &str, user_id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data(
+            "collections.remove_user",
+            &RemoveUserRequest {
+                id: collection_id,
+                user_id,
+            },
+        )
+    }
+
+    fn add_group(
+        &self,
+        collection_id: &str,
+        group_id: &str,
+        permission: CollectionPermission,
+    ) -> Result<(), ConnectionError> {
+        self.post_without_data(
+            "collections.add_group",
+            &AddGroupRequest {
+                id: collection_id,
+                group_id,
+                permission,
+            },
+        )
+    }
+
+    fn remove_group(&self, collection_id: &str, group_id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data(
+            "collections.remove_group",
+            &RemoveGroupRequest {
+                id: collection_id,
+                group_id,
+            },
+        )
+    }
+}
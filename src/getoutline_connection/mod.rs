@@ -0,0 +1,501 @@
+//! The getOutline REST API, exposed through implementations of the traits in `logic`.
+//!
+//! Every getOutline endpoint is a `POST` to `<base url>/api/<method>` with a JSON body, and every
+//! successful response wraps its payload in a `data` field.
+
+pub mod api_keys;
+pub mod attachments;
+pub mod catalog;
+pub mod collections;
+pub mod comments;
+pub mod documents;
+pub mod events;
+pub mod file_operations;
+pub mod groups;
+pub mod memberships;
+pub mod pins;
+pub mod revisions;
+pub mod shares;
+pub mod stars;
+mod timing;
+pub mod users;
+pub mod views;
+pub mod workspace;
+
+use std::num::NonZeroU32;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::logic::budget::{Meter, RateLimiter};
+use crate::logic::timings::Timings;
+use crate::logic::ConnectionError;
+use timing::{PendingTiming, Setups, TimedResolver, TimingLayer};
+
+const DEFAULT_BASE_URL: &str = "https://app.getoutline.com";
+
+/// The setting which names who is making changes through this profile.
+const AUDIT_ACTOR: &str = "AUDIT_ACTOR";
+
+/// The setting which caps how many requests a minute are sent to the profile's workspace.
+const REQUESTS_PER_MINUTE: &str = "REQUESTS_PER_MINUTE";
+
+/// Sent along with every request which changes the workspace when an audit actor is configured.
+/// getOutline itself ignores it, but it shows up in the access logs of a self-hosted instance or
+/// the proxy in front of it.
+const ACTOR_HEADER: &str = "x-getoutline-cli-actor";
+
+/// API methods which only read from the workspace. Everything else is treated as a change, so
+/// new endpoints are attributed by default. Methods are listed in full, since the same action
+/// can do either: `documents.export` only reads, but `collections.export` starts an export job.
+const READ_ONLY_METHODS: &[&str] = &[
+    "apiKeys.list",
+    "attachments.list",
+    "attachments.redirect",
+    "auth.config",
+    "auth.info",
+    "collections.documents",
+    "collections.group_memberships",
+    "collections.info",
+    "collections.list",
+    "collections.memberships",
+    "documents.drafts",
+    "documents.export",
+    "documents.info",
+    "documents.list",
+    "documents.memberships",
+    "documents.search",
+    "documents.search_titles",
+    "documents.users",
+    "documents.viewed",
+    "events.list",
+    "fileOperations.info",
+    "fileOperations.list",
+    "fileOperations.redirect",
+    "groups.info",
+    "groups.list",
+    "groups.memberships",
+    "pins.list",
+    "revisions.info",
+    "revisions.list",
+    "shares.info",
+    "shares.list",
+    "stars.list",
+    "users.info",
+    "users.list",
+    "views.list",
+];
+
+/// How much of each request and response body gets logged with `-vvv`.
+const LOGGED_BODY_CHARS: usize = 2000;
+
+/// API methods whose bodies are never logged, since they can carry API keys.
+const SECRET_METHOD_PREFIXES: &[&str] = &["apiKeys."];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("the {var} environment variable must be set to a getOutline API key")]
+    MissingApiKey { var: String },
+    #[error("the API key contains characters which can't be sent in an HTTP header")]
+    InvalidApiKey,
+    #[error("{var} contains characters which can't be sent in an HTTP header")]
+    InvalidAuditActor { var: String },
+    #[error("{var} must be a whole number of requests greater than zero")]
+    InvalidRateLimit { var: String },
+    #[error("could not set up the HTTP client")]
+    Client(#[from] reqwest::Error),
+    #[error("could not set up TLS")]
+    Tls(#[from] rustls::Error),
+}
+
+/// An authenticated connection to a getOutline workspace. Clones share the open connections.
+#[derive(Clone)]
+pub struct GetOutlineClient {
+    http: Client,
+    base_url: String,
+    /// The `Authorization` header, kept for rebuilding `http` with different settings.
+    auth: HeaderValue,
+    actor: Option<HeaderValue>,
+    meter: Option<Arc<Meter>>,
+    /// Shared by clones, so everything sent to the workspace counts towards its rate limit.
+    limiter: Option<Arc<RateLimiter>>,
+    timed: Option<Timed>,
+}
+
+/// Where a client measuring its requests for `--timings` keeps track of them.
+#[derive(Clone)]
+struct Timed {
+    timings: Arc<Timings>,
+    setups: Arc<Setups>,
+}
+
+impl GetOutlineClient {
+    /// Builds a client from the `API_KEY` and (optional) `BASE_URL` settings of `profile`, or of
+    /// the default profile if there isn't one.
+    ///
+    /// If the profile has an `AUDIT_ACTOR` setting, requests which change the workspace say who
+    /// made them. The special value `auto` stands for the local `user@hostname`. With a
+    /// `REQUESTS_PER_MINUTE` setting, requests are spaced out to send no more than that many a
+    /// minute.
+    pub fn from_config(profile: Option<&str>) -> Result<Self, ConfigError> {
+        let api_key =
+            config::get(profile, "API_KEY").ok_or_else(|| ConfigError::MissingApiKey {
+                var: config::var_name(profile, "API_KEY"),
+            })?;
+        let base_url =
+            config::get(profile, "BASE_URL").unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+
+        let mut client = Self::new(&base_url, &api_key)?;
+        if let Some(actor) = config::get(profile, AUDIT_ACTOR) {
+            let actor = if actor == "auto" {
+                local_actor()
+            } else {
+                actor
+            };
+            client.actor = Some(HeaderValue::from_str(&actor).map_err(|_| {
+                ConfigError::InvalidAuditActor {
+                    var: config::var_name(profile, AUDIT_ACTOR),
+                }
+            })?);
+        }
+        if let Some(limit) = config::get(profile, REQUESTS_PER_MINUTE) {
+            let limit =
+                limit
+                    .trim()
+                    .parse::<NonZeroU32>()
+                    .map_err(|_| ConfigError::InvalidRateLimit {
+                        var: config::var_name(profile, REQUESTS_PER_MINUTE),
+                    })?;
+            client.limiter = Some(Arc::new(RateLimiter::per_minute(limit)));
+        }
+
+        Ok(client)
+    }
+
+    pub fn new(base_url: &str, api_key: &str) -> Result<Self, ConfigError> {
+        let mut auth = HeaderValue::from_str(&format!("Bearer {api_key}"))
+            .map_err(|_| ConfigError::InvalidApiKey)?;
+        auth.set_sensitive(true);
+
+        Ok(Self {
+            http: http_client(&auth).build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            auth,
+            actor: None,
+            meter: None,
+            limiter: None,
+            timed: None,
+        })
+    }
+
+    /// A client for the same workspace and with the same settings, which authenticates with
+    /// `api_key` instead.
+    pub fn with_api_key(&self, api_key: &str) -> Result<Self, ConfigError> {
+        let mut auth = HeaderValue::from_str(&format!("Bearer {api_key}"))
+            .map_err(|_| ConfigError::InvalidApiKey)?;
+        auth.set_sensitive(true);
+
+        let client = Self {
+            http: http_client(&auth).build()?,
+            auth,
+            timed: None,
+            ..self.clone()
+        };
+        match &self.timed {
+            Some(timed) => client.with_timings(timed.timings.clone()),
+            None => Ok(client),
+        }
+    }
+
+    /// Counts every request and download against `meter`, refusing to send requests once its
+    /// budget is used up.
+    pub fn with_meter(mut self, meter: Arc<Meter>) -> Self {
+        self.meter = Some(meter);
+        self
+    }
+
+    /// Records how long each request takes in `timings`, split into looking up the server's
+    /// address, connecting to it, the TLS handshake, waiting for the response, and reading it.
+    pub fn with_timings(mut self, timings: Arc<Timings>) -> Result<Self, ConfigError> {
+        let setups = Arc::new(Setups::default());
+        self.http = http_client(&self.auth)
+            .dns_resolver(Arc::new(TimedResolver(setups.clone())))
+            .tls_backend_preconfigured(timing::tls_config(setups.clone())?)
+            .connector_layer(TimingLayer(setups.clone()))
+            .build()?;
+        self.timed = Some(Timed { timings, setups });
+        Ok(self)
+    }
+
+    /// The workspace's URL, which links to documents are relative to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Calls the API method `method` (e.g. `documents.search`) and decodes the response envelope.
+    fn post<B, R>(&self, method: &str, body: &B) -> Result<Envelope<R>, ConnectionError>
+    where
+        B: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let response = self.send(self.request(method).json(body))?;
+        serde_json::from_slice(&response).map_err(|err| ConnectionError::Transport(Box::new(err)))
+    }
+
+    /// Calls the API method `method` for an endpoint which doesn't return any data.
+    fn post_without_data<B>(&self, method: &str, body: &B) -> Result<(), ConnectionError>
+    where
+        B: Serialize + ?Sized,
+    {
+        self.send(self.request(method).json(body)).map(|_| ())
+    }
+
+    /// Calls the API method `method` asking for the raw response body in the `accept` content type,
+    /// for endpoints which can return files rather than JSON.
+    fn post_for_bytes<B>(
+        &self,
+        method: &str,
+        body: &B,
+        accept: &str,
+    ) -> Result<Vec<u8>, ConnectionError>
+    where
+        B: Serialize + ?Sized,
+    {
+        let request = self
+            .request(method)
+            .header(header::ACCEPT, accept)
+            .json(body);
+
+        Ok(self.send(request)?.to_vec())
+    }
+
+    fn request(&self, method: &str) -> RequestBuilder {
+        let request = self.http.post(format!("{}/api/{method}", self.base_url));
+        match &self.actor {
+            Some(actor) if is_mutating(method) => request.header(ACTOR_HEADER, actor.clone()),
+            _ => request,
+        }
+    }
+
+    /// Sends `request`, returning the body of a successful response.
+    fn send(&self, request: RequestBuilder) -> Result<impl Deref<Target = [u8]>, ConnectionError> {
+        let response = self.send_for_response(request)?;
+        self.read_body(response)
+    }
+
+    /// Sends `request`, returning a successful response before its body has been read, for
+    /// callers which need its headers too.
+    fn send_for_response(&self, request: RequestBuilder) -> Result<Response, ConnectionError> {
+        if let Some(meter) = &self.meter {
+            meter.start_request()?;
+        }
+        if let Some(limiter) = &self.limiter {
+            let waited = limiter.wait();
+            if !waited.is_zero() {
+                tracing::debug!("waited {waited:.1?} to stay under the rate limit");
+            }
+        }
+        let request = request.build().map_err(transport_error)?;
+        let method = api_method(request.url()).to_string();
+        tracing::debug!("sending {method} to {}", request.url());
+        tracing::trace!(
+            "{method} request: {}",
+            loggable(&method, request.body().and_then(|body| body.as_bytes()))
+        );
+        let started = Instant::now();
+        let sent = self.http.execute(request);
+        let pending = self.timed.as_ref().map(|timed| PendingTiming {
+            method: method.clone(),
+            setup: timed.setups.claim(),
+            until_headers: started.elapsed(),
+        });
+        let mut response = match sent {
+            Ok(response) => response,
+            Err(err) => {
+                tracing::info!("{method} failed after {:.1?}: {err}", started.elapsed());
+                tracing::debug!("{method} failed: {err:?}");
+                self.record_timing(pending, None, Duration::ZERO, 0);
+                return Err(transport_error(err));
+            }
+        };
+
+        let status = response.status();
+        tracing::info!("{method}: {status} after {:.1?}", started.elapsed());
+        if status.is_success() {
+            if let Some(pending) = pending {
+                response.extensions_mut().insert(pending);
+            }
+            return Ok(response);
+        }
+
+        let reading = Instant::now();
+        let body = response.bytes().unwrap_or_default();
+        tracing::debug!("{method} response: {}", loggable(&method, Some(&body)));
+        self.record_timing(
+            pending,
+            Some(status.as_u16()),
+            reading.elapsed(),
+            body.len() as u64,
+        );
+        let message = serde_json::from_slice::<ErrorBody>(&body)
+            .ok()
+            .and_then(|body| body.message.or(body.error))
+            .unwrap_or_else(|| status.to_string());
+
+        Err(match status.as_u16() {
+            401 | 403 => ConnectionError::Unauthorized {
+                status: status.as_u16(),
+            },
+            404 => ConnectionError::NotFound { message },
+            code => ConnectionError::Api {
+                status: code,
+                message,
+            },
+        })
+    }
+
+    /// Reads the body of `response`, counting it against the meter.
+    fn read_body(
+        &self,
+        mut response: Response,
+    ) -> Result<impl Deref<Target = [u8]>, ConnectionError> {
+        let pending = response.extensions_mut().remove::<PendingTiming>();
+        let status = response.status().as_u16();
+        let method = api_method(response.url()).to_string();
+        let reading = Instant::now();
+        let body = response.bytes();
+        let bytes = body.as_ref().map_or(0, |body| body.len() as u64);
+        self.record_timing(pending, Some(status), reading.elapsed(), bytes);
+
+        let body = body.map_err(transport_error)?;
+        tracing::debug!("{method}: read {bytes} bytes in {:.1?}", reading.elapsed());
+        tracing::trace!("{method} response: {}", loggable(&method, Some(&body)));
+        if let Some(meter) = &self.meter {
+            meter.record_download(body.len() as u64);
+        }
+        Ok(body)
+    }
+
+    /// Finishes timing a request, if this client is measuring them.
+    fn record_timing(
+        &self,
+        pending: Option<PendingTiming>,
+        status: Option<u16>,
+        body: Duration,
+        bytes: u64,
+    ) {
+        if let (Some(timed), Some(pending)) = (&self.timed, pending) {
+            timed.timings.record(pending.finish(status, body, bytes));
+        }
+    }
+}
+
+/// The HTTP client settings every connection starts from, authenticating with `auth`.
+fn http_client(auth: &HeaderValue) -> reqwest::blocking::ClientBuilder {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::AUTHORIZATION, auth.clone());
+    Client::builder().default_headers(headers)
+}
+
+/// Whether the API method `method` changes the workspace.
+fn is_mutating(method: &str) -> bool {
+    !READ_ONLY_METHODS.contains(&method)
+}
+
+/// Who is running the CLI, as `user@hostname`, for profiles with `AUDIT_ACTOR=auto`.
+fn local_actor() -> String {
+    let user = ["USER", "USERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok())
+        .unwrap_or_else(|| "unknown".to_string());
+    let host = ["HOSTNAME", "COMPUTERNAME"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    format!("{user}@{host}")
+}
+
+/// The API method `url` calls, like `documents.info`.
+fn api_method(url: &reqwest::Url) -> &str {
+    let path = url.path();
+    path.rsplit_once("/api/").map_or(path, |(_, method)| method)
+}
+
+/// The start of a request or response `body` for the API method `method` as text, for logging.
+fn loggable(method: &str, body: Option<&[u8]>) -> String {
+    if SECRET_METHOD_PREFIXES
+        .iter()
+        .any(|prefix| method.starts_with(prefix))
+    {
+        return "(not logged, since it can contain an API key)".to_string();
+    }
+    let Some(body) = body else {
+        return "(not logged, since it's sent in parts)".to_string();
+    };
+    let text = String::from_utf8_lossy(body);
+    match text.char_indices().nth(LOGGED_BODY_CHARS) {
+        Some((end, _)) => format!("{}… ({} bytes)", &text[..end], body.len()),
+        None => text.into_owned(),
+    }
+}
+
+fn transport_error(err: reqwest::Error) -> ConnectionError {
+    ConnectionError::Transport(Box::new(err))
+}
+
+/// The body of requests which only identify a single object, like `documents.info`.
+#[derive(Serialize)]
+struct IdRequest<'a> {
+    id: &'a str,
+}
+
+/// The wrapper getOutline puts around every successful response.
+#[derive(Deserialize)]
+struct Envelope<T> {
+    data: T,
+}
+
+/// The body getOutline sends back along with a non-2xx status.
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: Option<String>,
+    message: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_are_told_apart_from_changes_by_their_full_method() {
+        for method in [
+            "documents.info",
+            "documents.export",
+            "documents.users",
+            "documents.memberships",
+            "groups.memberships",
+            "collections.memberships",
+        ] {
+            assert!(!is_mutating(method), "{method} only reads");
+        }
+        for method in [
+            "documents.update",
+            "collections.export",
+            "collections.add_user",
+            "shares.revoke",
+            "someNewEndpoint.list",
+        ] {
+            assert!(is_mutating(method), "{method} makes a change");
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use serde::Serialize;
+
+use super::{GetOutlineClient, IdRequest};
+use crate::logic::pins::{Pin, PinPage, PinReader, PinWriter};
+use crate::logic::{ConnectionError, Page};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collection_id: Option<&'a str>,
+    #[serde(flatten)]
+    page: Page,
+}
+
+impl PinReader for GetOutlineClient {
+    fn pins(&self, collection_id: Option<&str>, page: Page) -> Result<PinPage, ConnectionError> {
+        let response = self.post(
+            "pins.list",
+            &ListRequest {
+                collection_id,
+                page,
+            },
+        )?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateRequest<'a> {
+    document_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collection_id: Option<&'a str>,
+}
+
+impl PinWriter for GetOutlineClient {
+    fn pin(&self, document_id: &str, collection_id: Option<&str>) -> Result<Pin, ConnectionError> {
+        let request = CreateRequest {
+            document_id,
+            collection_id,
+        };
+        let response = self.post("pins.create", &request)?;
+        Ok(response.data)
+    }
+
+    fn unpin(&self, pin_id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data("pins.delete", &IdRequest { id: pin_id })
+    }
+}
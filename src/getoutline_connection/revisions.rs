@@ -0,0 +1,51 @@
+use serde::Serialize;
+
+use super::{GetOutlineClient, IdRequest};
+use crate::logic::documents::DocumentSummary;
+use crate::logic::revisions::{DocumentRestorer, Revision, RevisionReader};
+use crate::logic::{ConnectionError, Page};
+
+impl RevisionReader for GetOutlineClient {
+    fn revision(&self, id: &str) -> Result<Revision, ConnectionError> {
+        let response = self.post("revisions.info", &IdRequest { id })?;
+        Ok(response.data)
+    }
+
+    fn revisions(&self, document_id: &str, page: Page) -> Result<Vec<Revision>, ConnectionError> {
+        let request = ListRequest { document_id, page };
+
+        let response = self.post("revisions.list", &request)?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListRequest<'a> {
+    document_id: &'a str,
+    #[serde(flatten)]
+    page: Page,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestoreRequest<'a> {
+    id: &'a str,
+    revision_id: &'a str,
+}
+
+impl DocumentRestorer for GetOutlineClient {
+    fn restore(
+        &self,
+        document_id: &str,
+        revision_id: &str,
+    ) -> Result<DocumentSummary, ConnectionError> {
+        let request = RestoreRequest {
+            id: document_id,
+            revision_id,
+        };
+
+        let response = self.post("documents.restore", &request)?;
+        Ok(response.data)
+    }
+}
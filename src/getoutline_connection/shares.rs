@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Envelope, GetOutlineClient, IdRequest};
+use crate::logic::documents::Document;
+use crate::logic::shares::{Share, ShareReader, ShareWriter, SharedDocumentReader};
+use crate::logic::{ConnectionError, Page};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentRequest<'a> {
+    document_id: &'a str,
+}
+
+impl ShareReader for GetOutlineClient {
+    fn list(&self, page: Page) -> Result<Vec<Share>, ConnectionError> {
+        let response = self.post("shares.list", &page)?;
+        Ok(response.data)
+    }
+
+    fn for_document(&self, document_id: &str) -> Result<Share, ConnectionError> {
+        let response = self.post("shares.info", &DocumentRequest { document_id })?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SharedRequest<'a> {
+    share_id: &'a str,
+    id: &'a str,
+}
+
+/// What `documents.info` sends back for a document read through a share link, which comes with
+/// the tree of documents shared along with it.
+#[derive(Deserialize)]
+struct SharedResponse {
+    document: Document,
+}
+
+impl SharedDocumentReader for GetOutlineClient {
+    fn shared(&self, share_id: &str, document_id: &str) -> Result<Document, ConnectionError> {
+        let response: Envelope<SharedResponse> = self.post(
+            "documents.info",
+            &SharedRequest {
+                share_id,
+                id: document_id,
+            },
+        )?;
+        Ok(response.data.document)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateRequest<'a> {
+    document_id: &'a str,
+    published: bool,
+}
+
+#[derive(Serialize)]
+struct UpdateRequest<'a> {
+    id: &'a str,
+    published: bool,
+}
+
+impl ShareWriter for GetOutlineClient {
+    fn create(&self, document_id: &str, published: bool) -> Result<Share, ConnectionError> {
+        let response = self.post(
+            "shares.create",
+            &CreateRequest {
+                document_id,
+                published,
+            },
+        )?;
+        Ok(response.data)
+    }
+
+    fn set_published(&self, share_id: &str, published: bool) -> Result<Share, ConnectionError> {
+        let response = self.post(
+            "shares.update",
+            &UpdateRequest {
+                id: share_id,
+                published,
+            },
+        )?;
+        Ok(response.data)
+    }
+
+    fn revoke(&self, share_id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data("shares.revoke", &IdRequest { id: share_id })
+    }
+}
@@ -0,0 +1,29 @@
+use serde::Serialize;
+
+use super::{GetOutlineClient, IdRequest};
+use crate::logic::stars::{Star, StarPage, StarReader, StarWriter};
+use crate::logic::{ConnectionError, Page};
+
+impl StarReader for GetOutlineClient {
+    fn stars(&self, page: Page) -> Result<StarPage, ConnectionError> {
+        let response = self.post("stars.list", &page)?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateRequest<'a> {
+    document_id: &'a str,
+}
+
+impl StarWriter for GetOutlineClient {
+    fn star(&self, document_id: &str) -> Result<Star, ConnectionError> {
+        let response = self.post("stars.create", &CreateRequest { document_id })?;
+        Ok(response.data)
+    }
+
+    fn unstar(&self, star_id: &str) -> Result<(), ConnectionError> {
+        self.post_without_data("stars.delete", &IdRequest { id: star_id })
+    }
+}
@@ -0,0 +1,231 @@
+//! Hooks into the HTTP client which measure how long opening each new connection takes, for
+//! `--timings`.
+//!
+//! Looking up the address and opening the connection as a whole are timed around the client's
+//! resolver and connector. The TLS handshake happens inside the connector, so its start is found
+//! from when TLS asks its session store whether it can resume an earlier session, which is the
+//! first thing it does once the connection is open.
+//!
+//! The client sets up connections on its own, so there's no telling which request a connection
+//! was opened for. Each request takes the oldest setup which hasn't been claimed yet when its
+//! response arrives, which is exact when requests are sent one at a time.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::net::ToSocketAddrs;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use rustls::client::{ClientSessionMemoryCache, ClientSessionStore, Tls12ClientSessionValue};
+use rustls::client::{Resumption, Tls13ClientSessionValue};
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, NamedGroup};
+use rustls_platform_verifier::ConfigVerifierExt;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::logic::timings::{ConnectionSetup, RequestTiming};
+
+/// Connection setups which have finished but haven't been claimed by a request yet.
+#[derive(Debug, Default)]
+pub(super) struct Setups {
+    /// How long the last address lookup took, until the connection it was for is finished.
+    dns: Mutex<Option<Duration>>,
+    /// When the TLS handshake for the connection being opened started, if it has.
+    tls_started: Mutex<Option<Instant>>,
+    finished: Mutex<VecDeque<ConnectionSetup>>,
+}
+
+impl Setups {
+    /// Takes the oldest connection setup which hasn't been claimed yet, if there is one.
+    pub(super) fn claim(&self) -> Option<ConnectionSetup> {
+        self.finished.lock().unwrap().pop_front()
+    }
+
+    /// Finishes timing the connection which started opening at `started`.
+    fn finish(&self, started: Instant) {
+        let total = started.elapsed();
+        let dns = self.dns.lock().unwrap().take().unwrap_or_default();
+        let tls = self
+            .tls_started
+            .lock()
+            .unwrap()
+            .take()
+            .map(|at| at.elapsed());
+        self.finished.lock().unwrap().push_back(ConnectionSetup {
+            dns,
+            connect: total.saturating_sub(dns + tls.unwrap_or_default()),
+            tls,
+        });
+    }
+
+    /// Forgets what was measured of a connection which couldn't be opened, so it isn't counted
+    /// towards the next one.
+    fn abandon(&self) {
+        self.dns.lock().unwrap().take();
+        self.tls_started.lock().unwrap().take();
+    }
+
+    fn start_tls(&self) {
+        self.tls_started
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Instant::now);
+    }
+}
+
+/// The TLS settings reqwest uses by default, with a session store which notes when each
+/// handshake starts in `setups`.
+pub(super) fn tls_config(setups: Arc<Setups>) -> Result<ClientConfig, rustls::Error> {
+    let mut config = ClientConfig::with_platform_verifier()?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    config.resumption = Resumption::store(Arc::new(TimedSessionStore {
+        sessions: ClientSessionMemoryCache::new(256),
+        setups,
+    }));
+    Ok(config)
+}
+
+/// Keeps TLS sessions in memory like rustls does by default. The handshake asks it for a
+/// session to resume (and which key exchange to start with) as it starts.
+#[derive(Debug)]
+struct TimedSessionStore {
+    sessions: ClientSessionMemoryCache,
+    setups: Arc<Setups>,
+}
+
+impl ClientSessionStore for TimedSessionStore {
+    fn set_kx_hint(&self, server_name: ServerName<'static>, group: NamedGroup) {
+        self.sessions.set_kx_hint(server_name, group);
+    }
+
+    fn kx_hint(&self, server_name: &ServerName<'_>) -> Option<NamedGroup> {
+        self.setups.start_tls();
+        self.sessions.kx_hint(server_name)
+    }
+
+    fn set_tls12_session(&self, server_name: ServerName<'static>, value: Tls12ClientSessionValue) {
+        self.sessions.set_tls12_session(server_name, value);
+    }
+
+    fn tls12_session(&self, server_name: &ServerName<'_>) -> Option<Tls12ClientSessionValue> {
+        self.sessions.tls12_session(server_name)
+    }
+
+    fn remove_tls12_session(&self, server_name: &ServerName<'static>) {
+        self.sessions.remove_tls12_session(server_name);
+    }
+
+    fn insert_tls13_ticket(
+        &self,
+        server_name: ServerName<'static>,
+        value: Tls13ClientSessionValue,
+    ) {
+        self.sessions.insert_tls13_ticket(server_name, value);
+    }
+
+    fn take_tls13_ticket(
+        &self,
+        server_name: &ServerName<'static>,
+    ) -> Option<Tls13ClientSessionValue> {
+        self.setups.start_tls();
+        self.sessions.take_tls13_ticket(server_name)
+    }
+}
+
+/// Looks up addresses with the system resolver, timing each lookup.
+pub(super) struct TimedResolver(pub(super) Arc<Setups>);
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let setups = self.0.clone();
+        Box::pin(async move {
+            // This blocks the client's background thread for the lookup, which is fine for a
+            // diagnostic mode and keeps the lookup itself the only thing being timed
+            let started = Instant::now();
+            let addresses: Vec<_> = (name.as_str(), 0).to_socket_addrs()?.collect();
+            *setups.dns.lock().unwrap() = Some(started.elapsed());
+
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Wraps the client's connector to time each new connection, from looking up the address until
+/// any TLS handshake is done.
+#[derive(Clone)]
+pub(super) struct TimingLayer(pub(super) Arc<Setups>);
+
+impl<S> Layer<S> for TimingLayer {
+    type Service = TimedConnector<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TimedConnector {
+            inner,
+            setups: self.0.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct TimedConnector<S> {
+    inner: S,
+    setups: Arc<Setups>,
+}
+
+impl<S, R> Service<R> for TimedConnector<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let started = Instant::now();
+        let setups = self.setups.clone();
+        let connecting = self.inner.call(request);
+        Box::pin(async move {
+            let connection = connecting.await;
+            if connection.is_ok() {
+                setups.finish(started);
+            } else {
+                setups.abandon();
+            }
+            connection
+        })
+    }
+}
+
+/// What's known about a request's timing once its response's headers have arrived, carried in the
+/// response's extensions until its body has been read.
+#[derive(Debug, Clone)]
+pub(super) struct PendingTiming {
+    pub(super) method: String,
+    pub(super) setup: Option<ConnectionSetup>,
+    /// From sending the request until the headers arrived, including any connection setup.
+    pub(super) until_headers: Duration,
+}
+
+impl PendingTiming {
+    pub(super) fn finish(self, status: Option<u16>, body: Duration, bytes: u64) -> RequestTiming {
+        let setup_time = self.setup.map_or(Duration::ZERO, |setup| setup.total());
+        RequestTiming {
+            method: self.method,
+            setup: self.setup,
+            waiting: self.until_headers.saturating_sub(setup_time),
+            body,
+            status,
+            bytes,
+        }
+    }
+}
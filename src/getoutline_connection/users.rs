@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Envelope, GetOutlineClient, IdRequest};
+use crate::logic::users::{Invite, User, UserInviter, UserQuery, UserReader};
+use crate::logic::{ConnectionError, Page};
+
+#[derive(Serialize)]
+struct ListRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'a str>,
+    sort: &'a str,
+    direction: &'a str,
+    #[serde(flatten)]
+    page: Page,
+}
+
+impl UserReader for GetOutlineClient {
+    fn info(&self, id: &str) -> Result<User, ConnectionError> {
+        let response = self.post("users.info", &IdRequest { id })?;
+        Ok(response.data)
+    }
+
+    fn list(&self, query: &UserQuery, page: Page) -> Result<Vec<User>, ConnectionError> {
+        let request = ListRequest {
+            query: query.name,
+            // Without a filter, getOutline leaves suspended users out
+            filter: query.suspended.then_some("suspended"),
+            role: query.admins.then_some("admin"),
+            sort: "name",
+            direction: "ASC",
+            page,
+        };
+
+        let response = self.post("users.list", &request)?;
+        Ok(response.data)
+    }
+}
+
+#[derive(Serialize)]
+struct InviteRequest<'a> {
+    invites: &'a [Invite],
+}
+
+#[derive(Deserialize)]
+struct Invited {
+    users: Vec<User>,
+}
+
+impl UserInviter for GetOutlineClient {
+    fn invite(&self, invites: &[Invite]) -> Result<Vec<User>, ConnectionError> {
+        let response: Envelope<Invited> = self.post("users.invite", &InviteRequest { invites })?;
+        Ok(response.data.users)
+    }
+}
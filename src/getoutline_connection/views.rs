@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+use super::GetOutlineClient;
+use crate::logic::views::{View, ViewReader, ViewRecorder};
+use crate::logic::ConnectionError;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentRequest<'a> {
+    document_id: &'a str,
+}
+
+impl ViewReader for GetOutlineClient {
+    fn list(&self, document_id: &str) -> Result<Vec<View>, ConnectionError> {
+        let response = self.post("views.list", &DocumentRequest { document_id })?;
+        Ok(response.data)
+    }
+}
+
+impl ViewRecorder for GetOutlineClient {
+    fn create(&self, document_id: &str) -> Result<View, ConnectionError> {
+        let response = self.post("views.create", &DocumentRequest { document_id })?;
+        Ok(response.data)
+    }
+}
@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+use super::GetOutlineClient;
+use crate::logic::workspace::{AuthConfig, AuthInfo, WorkspaceReader};
+use crate::logic::ConnectionError;
+
+/// The body of requests which don't take any parameters.
+#[derive(Serialize)]
+struct EmptyRequest {}
+
+impl WorkspaceReader for GetOutlineClient {
+    fn auth_info(&self) -> Result<AuthInfo, ConnectionError> {
+        let response = self.post("auth.info", &EmptyRequest {})?;
+        Ok(response.data)
+    }
+
+    fn auth_config(&self) -> Result<AuthConfig, ConnectionError> {
+        let response = self.post("auth.config", &EmptyRequest {})?;
+        Ok(response.data)
+    }
+}
@@ -0,0 +1,10 @@
+//! A small CLI for working with a getOutline workspace, split into a library so the pieces can be
+//! reused and tested on their own.
+
+pub mod command;
+pub mod config;
+#[cfg(feature = "test-fixtures")]
+pub mod fixtures;
+pub mod getoutline_connection;
+pub mod logic;
+pub mod state;
@@ -0,0 +1,138 @@
+//! API keys, and swapping the one in use for a new one.
+
+use serde::{Deserialize, Serialize};
+
+use super::workspace::{AuthInfo, WorkspaceReader};
+use super::{fetch_all, ConnectionError, Page};
+
+/// An API key belonging to the user whose key lists it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    /// The key itself. getOutline only sends it back when the key is created (older versions
+    /// send it in listings too, as `secret`).
+    #[serde(default, alias = "secret", skip_serializing)]
+    pub value: Option<String>,
+    /// The last four characters of the key, which tell keys apart in listings.
+    #[serde(default)]
+    pub last4: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// When the key stops working, or `None` if it never does.
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+/// Something which can list the API keys of the user it connects as.
+pub trait ApiKeyReader {
+    fn list(&self, page: Page) -> Result<Vec<ApiKey>, ConnectionError>;
+}
+
+/// Something which can create API keys for the user it connects as.
+pub trait ApiKeyWriter {
+    /// Creates a key called `name`, which comes back with its [ApiKey::value].
+    fn create(&self, name: &str) -> Result<ApiKey, ConnectionError>;
+}
+
+/// Something which can revoke API keys.
+pub trait ApiKeyDeleter {
+    fn delete(&self, id: &str) -> Result<(), ConnectionError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiKeyError {
+    #[error("none of your API keys end in {last4}, so the one in use can't be found")]
+    NotFound { last4: String },
+    #[error("you don't have an API key with ID {id}")]
+    UnknownId { id: String },
+    #[error("{count} of your API keys end in {last4}; pick the one in use with --key-id")]
+    Ambiguous { last4: String, count: usize },
+    #[error("the new API key {reason}")]
+    Rejected { reason: String },
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+}
+
+/// A newly created API key, along with the key itself.
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub key: ApiKey,
+    pub value: String,
+}
+
+/// The last four characters of the API key `secret`, as listings show them.
+pub fn last4(secret: &str) -> &str {
+    secret
+        .char_indices()
+        .rev()
+        .nth(3)
+        .map_or(secret, |(index, _)| &secret[index..])
+}
+
+/// Finds which of the user's API keys is `secret`, or the one with ID `id` if it's given.
+///
+/// Listings only show the last four characters of each key, which is all there is to go on, so
+/// `id` picks between keys which end the same way.
+pub fn find_api_key(
+    reader: &impl ApiKeyReader,
+    secret: &str,
+    id: Option<&str>,
+) -> Result<ApiKey, ApiKeyError> {
+    let keys = fetch_all(|page| reader.list(page))?;
+    if let Some(id) = id {
+        return keys
+            .into_iter()
+            .find(|key| key.id == id)
+            .ok_or_else(|| ApiKeyError::UnknownId { id: id.to_string() });
+    }
+
+    let suffix = last4(secret);
+    let mut matching: Vec<_> = keys
+        .into_iter()
+        .filter(|key| match &key.value {
+            Some(value) => value == secret,
+            None => key.last4.as_deref() == Some(suffix),
+        })
+        .collect();
+    match matching.len() {
+        0 => Err(ApiKeyError::NotFound {
+            last4: suffix.to_string(),
+        }),
+        1 => Ok(matching.remove(0)),
+        count => Err(ApiKeyError::Ambiguous {
+            last4: suffix.to_string(),
+            count,
+        }),
+    }
+}
+
+/// Creates an API key called `name` for the user `writer` connects as.
+pub fn create_api_key(writer: &impl ApiKeyWriter, name: &str) -> Result<NewApiKey, ApiKeyError> {
+    let mut key = writer.create(name)?;
+    match key.value.take() {
+        Some(value) => Ok(NewApiKey { key, value }),
+        None => Err(ApiKeyError::Rejected {
+            reason: "was created without being sent back".to_string(),
+        }),
+    }
+}
+
+/// Checks a new API key works by connecting with it, and that it's for the same user in the same
+/// workspace as `owner`, the one the old key was for.
+pub fn verify_api_key(new: &impl WorkspaceReader, owner: &AuthInfo) -> Result<(), ApiKeyError> {
+    let info = new.auth_info().map_err(|err| ApiKeyError::Rejected {
+        reason: format!("doesn't work: {err}"),
+    })?;
+    if info.user.id != owner.user.id || info.team.id != owner.team.id {
+        return Err(ApiKeyError::Rejected {
+            reason: format!(
+                "belongs to {} instead of {}",
+                info.user.name, owner.user.name
+            ),
+        });
+    }
+
+    Ok(())
+}
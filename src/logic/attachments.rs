@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::documents::{DocumentLister, DocumentReader};
+use super::links::rewrite_destinations;
+use super::{fetch_all, ConnectionError, Page};
+
+/// A file uploaded to the workspace, which documents can link to or embed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub id: String,
+    pub name: String,
+    /// Where the file can be downloaded from, relative to the workspace's URL.
+    pub url: String,
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// The size of the file in bytes.
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// The document the file was uploaded for, if any.
+    #[serde(default)]
+    pub document_id: Option<String>,
+    /// When the file was uploaded, as an ISO 8601 timestamp.
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// A local file to upload.
+#[derive(Debug, Clone, Copy)]
+pub struct NewAttachment<'a> {
+    pub name: &'a str,
+    pub content_type: &'a str,
+    pub content: &'a [u8],
+    /// The document the file is for, which decides who can download it.
+    pub document_id: Option<&'a str>,
+}
+
+/// Something which can upload files to the workspace.
+pub trait AttachmentUploader {
+    fn upload(&self, attachment: &NewAttachment) -> Result<Attachment, ConnectionError>;
+}
+
+/// A downloaded attachment's contents.
+#[derive(Debug, Clone)]
+pub struct AttachmentFile {
+    pub content_type: Option<String>,
+    pub content: Vec<u8>,
+}
+
+/// Something which can download the files uploaded to the workspace.
+pub trait AttachmentDownloader {
+    fn download(&self, id: &str) -> Result<AttachmentFile, ConnectionError>;
+}
+
+/// Something which can list the files uploaded to the workspace.
+pub trait AttachmentLister {
+    fn list(&self, page: Page) -> Result<Vec<Attachment>, ConnectionError>;
+}
+
+/// Something which can delete files uploaded to the workspace.
+pub trait AttachmentDeleter {
+    fn delete(&self, id: &str) -> Result<(), ConnectionError>;
+}
+
+/// Uploads `attachment`, returning where it can be linked to from.
+pub fn upload_attachment(
+    uploader: &impl AttachmentUploader,
+    attachment: &NewAttachment,
+) -> Result<Attachment, ConnectionError> {
+    uploader.upload(attachment)
+}
+
+/// File extensions and the MIME types they're uploaded as. The first extension listed for a type
+/// is the one downloads of that type are saved with.
+const FILE_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("pdf", "application/pdf"),
+    ("zip", "application/zip"),
+    ("json", "application/json"),
+    ("csv", "text/csv"),
+    ("md", "text/markdown"),
+    ("markdown", "text/markdown"),
+    ("txt", "text/plain"),
+    ("log", "text/plain"),
+];
+
+/// The MIME type to upload a file called `name` as, guessed from its extension.
+pub fn content_type_for(name: &str) -> &'static str {
+    let extension = name
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+
+    FILE_TYPES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map_or("application/octet-stream", |(_, content_type)| content_type)
+}
+
+/// The extension to save a file of `content_type` with, or `bin` for types it doesn't know.
+pub fn extension_for(content_type: &str) -> &'static str {
+    // Ignore parameters like `; charset=utf-8`
+    let content_type = content_type.split(';').next().unwrap_or_default().trim();
+
+    FILE_TYPES
+        .iter()
+        .find(|(_, known)| known.eq_ignore_ascii_case(content_type))
+        .map_or("bin", |(extension, _)| extension)
+}
+
+/// Markdown which embeds `attachment` in a document: an image for image files, or a link to
+/// download anything else.
+pub fn markdown_for(attachment: &Attachment) -> String {
+    let is_image = attachment
+        .content_type
+        .as_deref()
+        .is_some_and(|content_type| content_type.starts_with("image/"));
+    let bang = if is_image { "!" } else { "" };
+
+    format!("{bang}[{}]({})", attachment.name, attachment.url)
+}
+
+/// The ID of the attachment a link points to, if `destination` is one of getOutline's
+/// `/api/attachments.redirect?id=...` links (with or without the workspace's URL in front).
+pub fn attachment_id(destination: &str) -> Option<&str> {
+    let (path, query) = destination.split_once('?')?;
+    if !path.ends_with("/api/attachments.redirect") {
+        return None;
+    }
+
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("id="))
+        .map(|id| id.split('#').next().unwrap_or(id))
+        // IDs become file names, so anything but a UUID-like ID isn't trusted to be one
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+}
+
+/// A document whose attachments were downloaded so it can be read without the workspace.
+#[derive(Debug, Clone)]
+pub struct LocalAttachments {
+    /// The document, with links to attachments pointing at the downloaded files.
+    pub markdown: String,
+    /// The downloaded files, as names within the assets directory and their contents.
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+/// Downloads every attachment `markdown` links to, and points the links at
+/// `<assets_dir>/<attachment ID>.<extension>` instead. Attachments linked to more than once are
+/// only downloaded once.
+pub fn localize_attachments(
+    downloader: &impl AttachmentDownloader,
+    markdown: &str,
+    assets_dir: &str,
+) -> Result<LocalAttachments, ConnectionError> {
+    let mut ids = Vec::new();
+    rewrite_destinations(markdown, |destination| {
+        if let Some(id) = attachment_id(destination) {
+            if !ids.iter().any(|known| known == id) {
+                ids.push(id.to_string());
+            }
+        }
+        None
+    });
+
+    let mut files = Vec::with_capacity(ids.len());
+    let mut paths = HashMap::with_capacity(ids.len());
+    for id in ids {
+        let file = downloader.download(&id)?;
+        let extension = file.content_type.as_deref().map_or("bin", extension_for);
+        let name = format!("{id}.{extension}");
+        paths.insert(id, format!("{assets_dir}/{name}"));
+        files.push((name, file.content));
+    }
+
+    let (markdown, _) = rewrite_destinations(markdown, |destination| {
+        attachment_id(destination).and_then(|id| paths.get(id).cloned())
+    });
+
+    Ok(LocalAttachments { markdown, files })
+}
+
+/// The attachments which no document refers to, as found by [find_orphaned_attachments].
+#[derive(Debug, Clone)]
+pub struct OrphanedAttachments {
+    pub orphaned: Vec<Attachment>,
+    /// How many attachments were checked.
+    pub attachments: usize,
+    /// How many documents were read to check them against.
+    pub documents: usize,
+}
+
+impl OrphanedAttachments {
+    /// How much storage the orphaned attachments take up, leaving out any of unknown size.
+    pub fn bytes(&self) -> u64 {
+        self.orphaned
+            .iter()
+            .filter_map(|attachment| attachment.size)
+            .sum()
+    }
+}
+
+/// Checks every attachment in the workspace against the text of every document, drafts included,
+/// and returns the ones which none of them refer to.
+///
+/// An attachment counts as used if its ID appears anywhere in a document, not only in a link, so
+/// that nothing which might still be used is reported. Only the documents `client` can read are
+/// checked, so attachments used only by documents it can't see (or ones in the trash) are
+/// reported too.
+pub fn find_orphaned_attachments<C>(client: &C) -> Result<OrphanedAttachments, ConnectionError>
+where
+    C: AttachmentLister + DocumentLister + DocumentReader,
+{
+    let attachments = fetch_all(|page| AttachmentLister::list(client, page))?;
+    let mut documents = fetch_all(|page| DocumentLister::list(client, page))?;
+    documents.extend(fetch_all(|page| client.drafts(page))?);
+
+    let mut read = HashSet::new();
+    let mut mentioned = HashSet::new();
+    for document in documents {
+        if !read.insert(document.id.clone()) {
+            continue;
+        }
+        let text = client.info(&document.id)?.text;
+        mentioned.extend(mentioned_ids(&text).map(str::to_string));
+    }
+
+    let count = attachments.len();
+    let orphaned = attachments
+        .into_iter()
+        .filter(|attachment| !mentioned.contains(&attachment.id))
+        .collect();
+
+    Ok(OrphanedAttachments {
+        orphaned,
+        attachments: count,
+        documents: read.len(),
+    })
+}
+
+/// Every word of `text` which could be an attachment's ID, splitting on anything which can't be
+/// part of one.
+fn mentioned_ids(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .filter(|word| !word.is_empty())
+}
+
+/// Deletes attachment `id` for good, breaking any links to it.
+pub fn delete_attachment(
+    deleter: &impl AttachmentDeleter,
+    id: &str,
+) -> Result<(), ConnectionError> {
+    deleter.delete(id)
+}
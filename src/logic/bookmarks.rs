@@ -0,0 +1,139 @@
+//! Local names for documents, kept in a plain text file so a team can check them into a
+//! repository alongside the scripts which use them.
+//!
+//! The file has one `name = id` bookmark per line. Blank lines and lines starting with `#` are
+//! comments, and are kept as they are when bookmarks are added or removed.
+
+use std::fmt::Write;
+
+/// A set of bookmarks, along with the comments and blank lines around them.
+#[derive(Debug, Clone, Default)]
+pub struct Bookmarks {
+    lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone)]
+enum Line {
+    Bookmark { name: String, id: String },
+    Other(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BookmarkError {
+    #[error("line {line} isn't a bookmark, expected `name = id`")]
+    InvalidLine { line: usize },
+    #[error(
+        "{0:?} can't be used as a bookmark name, use letters, numbers, `-`, `_`, and `.` only"
+    )]
+    InvalidName(String),
+    #[error("there's no bookmark called {0:?}")]
+    UnknownBookmark(String),
+}
+
+impl Bookmarks {
+    /// Reads bookmarks from the contents of a bookmarks file.
+    pub fn parse(text: &str) -> Result<Self, BookmarkError> {
+        let lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let trimmed = line.trim();
+                if trimmed.is_empty() || trimmed.starts_with('#') {
+                    return Ok(Line::Other(line.to_string()));
+                }
+
+                let invalid = || BookmarkError::InvalidLine { line: index + 1 };
+                let (name, id) = trimmed.split_once('=').ok_or_else(invalid)?;
+                let (name, id) = (name.trim(), id.trim());
+                if !is_valid_name(name) || id.is_empty() {
+                    return Err(invalid());
+                }
+
+                Ok(Line::Bookmark {
+                    name: name.to_string(),
+                    id: id.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { lines })
+    }
+
+    /// The ID bookmarked as `name`, if there is one.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter()
+            .find(|(bookmark, _)| *bookmark == name)
+            .map(|(_, id)| id)
+    }
+
+    /// Every bookmark as `(name, id)`, in the order they appear in the file.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Bookmark { name, id } => Some((name.as_str(), id.as_str())),
+            Line::Other(_) => None,
+        })
+    }
+
+    /// Bookmarks `id` as `name`, returning the ID the name used to point at, if any.
+    pub fn add(&mut self, name: &str, id: &str) -> Result<Option<String>, BookmarkError> {
+        if !is_valid_name(name) {
+            return Err(BookmarkError::InvalidName(name.to_string()));
+        }
+
+        for line in &mut self.lines {
+            if let Line::Bookmark {
+                name: existing,
+                id: existing_id,
+            } = line
+            {
+                if existing == name {
+                    return Ok(Some(std::mem::replace(existing_id, id.to_string())));
+                }
+            }
+        }
+
+        self.lines.push(Line::Bookmark {
+            name: name.to_string(),
+            id: id.to_string(),
+        });
+        Ok(None)
+    }
+
+    /// Removes the bookmark `name`, returning the ID it pointed at.
+    pub fn remove(&mut self, name: &str) -> Result<String, BookmarkError> {
+        let position = self
+            .lines
+            .iter()
+            .position(
+                |line| matches!(line, Line::Bookmark { name: existing, .. } if existing == name),
+            )
+            .ok_or_else(|| BookmarkError::UnknownBookmark(name.to_string()))?;
+
+        match self.lines.remove(position) {
+            Line::Bookmark { id, .. } => Ok(id),
+            Line::Other(_) => unreachable!("position only matches bookmarks"),
+        }
+    }
+
+    /// Writes the bookmarks back out in the file format, keeping comments where they were.
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        for line in &self.lines {
+            match line {
+                Line::Bookmark { name, id } => writeln!(text, "{name} = {id}"),
+                Line::Other(other) => writeln!(text, "{other}"),
+            }
+            .expect("writing to a String can't fail");
+        }
+
+        text
+    }
+}
+
+/// Bookmark names have to be easy to type, and can't be mistaken for `@` shortcuts or links.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
@@ -0,0 +1,145 @@
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Limits on how much one run of the CLI may ask of getOutline, to protect self-hosted servers
+/// and metered connections from a bulk job which runs away.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Budget {
+    pub max_requests: Option<u64>,
+    /// How many bytes of response bodies may be downloaded.
+    pub max_bytes: Option<u64>,
+    /// How long after the meter starts requests may still be sent, so a job can stop cleanly
+    /// before whatever runs it (like a CI job or a cron window) times out.
+    pub max_duration: Option<Duration>,
+}
+
+/// The limit of a [Budget] which was used up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum BudgetExhausted {
+    #[error("used up the budget of {0} requests")]
+    Requests(u64),
+    #[error("used up the budget of {0} bytes downloaded")]
+    Bytes(u64),
+    #[error("used up the time budget of {} seconds", .0.as_secs())]
+    Duration(Duration),
+}
+
+/// Counts what's been used against a [Budget]. One meter can be shared by several connections, so
+/// the budget covers everything a command does.
+#[derive(Debug)]
+pub struct Meter {
+    budget: Budget,
+    started: Instant,
+    requests: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl Meter {
+    /// Starts counting against `budget`, including the time it allows.
+    pub fn new(budget: Budget) -> Self {
+        Self {
+            budget,
+            started: Instant::now(),
+            requests: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts a request which is about to be sent, or refuses it if the budget doesn't leave room
+    /// for it.
+    pub fn start_request(&self) -> Result<(), BudgetExhausted> {
+        if let Some(max) = self.budget.max_duration {
+            if self.started.elapsed() >= max {
+                return Err(BudgetExhausted::Duration(max));
+            }
+        }
+        if let Some(max) = self.budget.max_bytes {
+            if self.bytes.load(Ordering::Relaxed) >= max {
+                return Err(BudgetExhausted::Bytes(max));
+            }
+        }
+        if let Some(max) = self.budget.max_requests {
+            let sent = self
+                .requests
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sent| {
+                    (sent < max).then_some(sent + 1)
+                });
+            if sent.is_err() {
+                return Err(BudgetExhausted::Requests(max));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Counts `bytes` of a response which was downloaded. A response is never cut short, so the
+    /// total can go past the budget, but no more requests are sent once it has.
+    pub fn record_download(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+}
+
+/// Spaces out the requests sent to one workspace so no more than a set number go out each minute,
+/// to stay under the rate limit of its server. Unlike a [Meter], each workspace gets its own, so a
+/// command using two workspaces is only slowed down by the one which is limited.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    /// When the next request may be sent, or `None` before the first one.
+    next: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn per_minute(requests: NonZeroU32) -> Self {
+        Self {
+            interval: Duration::from_secs(60) / requests.get(),
+            next: Mutex::new(None),
+        }
+    }
+
+    /// Waits until another request may be sent, returning how long that took.
+    pub fn wait(&self) -> Duration {
+        let wait = self.reserve(Instant::now());
+        thread::sleep(wait);
+        wait
+    }
+
+    /// Takes the next free slot for a request at `now`, returning how long it is until then.
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut next = self
+            .next
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let slot = next.map_or(now, |next| next.max(now));
+        *next = Some(slot + self.interval);
+        slot - now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_spaced_out_evenly() {
+        let limiter = RateLimiter::per_minute(NonZeroU32::new(120).unwrap());
+        let now = Instant::now();
+
+        let waits = [(); 3].map(|_| limiter.reserve(now));
+        assert_eq!(waits, [0, 500, 1000].map(Duration::from_millis));
+    }
+
+    #[test]
+    fn idle_time_isnt_saved_up_for_a_burst() {
+        let limiter = RateLimiter::per_minute(NonZeroU32::new(60).unwrap());
+        let now = Instant::now();
+        limiter.reserve(now);
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,194 @@
+//! Copies of documents kept on disk, so reading the same one again soon is instant and works
+//! offline.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::documents::{ContentSource, Document, DocumentExporter, DocumentReader};
+use super::ConnectionError;
+
+/// A copy of a document, as it was when it was fetched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedDocument {
+    pub document: Document,
+    /// When the copy was fetched, in seconds since the Unix epoch.
+    pub fetched_at: u64,
+}
+
+/// Somewhere to keep copies of documents, one for each document and [ContentSource].
+pub trait DocumentCache {
+    /// The copy of document `id` read from `source`, if there is one.
+    fn get(&self, id: &str, source: ContentSource) -> Option<CachedDocument>;
+    /// Keeps `cached` as the copy of document `id` read from `source`, replacing any older one.
+    /// Failing to keep it isn't an error, since the document was read anyway.
+    fn put(&self, id: &str, source: ContentSource, cached: &CachedDocument);
+}
+
+/// Fetches document `id` like [super::documents::fetch_document], unless `cache` has a copy
+/// fetched less than `ttl` before `now` (in seconds since the Unix epoch).
+///
+/// A copy which is too old is still used for `documents.export`'s markdown if the document's
+/// revision hasn't changed since, which saves rendering it again.
+pub fn fetch_document_cached<C>(
+    client: &C,
+    cache: &impl DocumentCache,
+    id: &str,
+    source: ContentSource,
+    ttl: Duration,
+    now: u64,
+) -> Result<Document, ConnectionError>
+where
+    C: DocumentReader + DocumentExporter,
+{
+    let cached = cache.get(id, source);
+    if let Some(cached) = &cached {
+        if now.saturating_sub(cached.fetched_at) < ttl.as_secs() {
+            tracing::debug!("using the cached copy of {id}");
+            return Ok(cached.document.clone());
+        }
+    }
+
+    let mut document = client.info(id)?;
+    if source == ContentSource::Export {
+        document.text = match cached {
+            Some(cached)
+                if document.revision.is_some() && cached.document.revision == document.revision =>
+            {
+                tracing::debug!("reusing the cached export of {id}, which hasn't changed");
+                cached.document.text
+            }
+            _ => client.export(id)?,
+        };
+    }
+
+    let cached = CachedDocument {
+        document,
+        fetched_at: now,
+    };
+    cache.put(id, source, &cached);
+    Ok(cached.document)
+}
+
+/// Limits on how much the cache keeps, past which [entries_to_prune] picks copies to delete.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrunePolicy {
+    /// The most the copies may take up together, in bytes.
+    pub max_bytes: Option<u64>,
+    /// How long a copy is kept after it was fetched.
+    pub max_age: Option<Duration>,
+}
+
+impl PrunePolicy {
+    /// Whether there are any limits at all.
+    pub fn is_set(&self) -> bool {
+        self.max_bytes.is_some() || self.max_age.is_some()
+    }
+}
+
+/// A cached copy, as far as pruning is concerned.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheEntry {
+    pub bytes: u64,
+    /// How long ago the copy was fetched.
+    pub age: Duration,
+}
+
+/// The indexes of the `entries` to delete to keep within `policy`: all the ones older than its
+/// maximum age, and then the oldest of the rest until the others fit in its maximum size.
+pub fn entries_to_prune(entries: &[CacheEntry], policy: PrunePolicy) -> Vec<usize> {
+    let mut oldest_first: Vec<_> = (0..entries.len()).collect();
+    oldest_first.sort_by_key(|&index| std::cmp::Reverse(entries[index].age));
+
+    let mut kept_bytes: u64 = entries.iter().map(|entry| entry.bytes).sum();
+    let mut pruned = Vec::new();
+    for index in oldest_first {
+        let entry = entries[index];
+        let too_old = policy.max_age.is_some_and(|max_age| entry.age > max_age);
+        let too_big = policy
+            .max_bytes
+            .is_some_and(|max_bytes| kept_bytes > max_bytes);
+        if !too_old && !too_big {
+            break;
+        }
+        kept_bytes -= entry.bytes;
+        pruned.push(index);
+    }
+
+    pruned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Entries of `(kilobytes, age in days)`.
+    fn entries(sizes_and_ages: &[(u64, u64)]) -> Vec<CacheEntry> {
+        sizes_and_ages
+            .iter()
+            .map(|&(kilobytes, days)| CacheEntry {
+                bytes: kilobytes * 1000,
+                age: Duration::from_secs(days * 86_400),
+            })
+            .collect()
+    }
+
+    fn days(days: u64) -> Option<Duration> {
+        Some(Duration::from_secs(days * 86_400))
+    }
+
+    #[test]
+    fn copies_older_than_the_maximum_age_are_pruned() {
+        let entries = entries(&[(1, 3), (1, 10), (1, 7), (1, 8)]);
+        let policy = PrunePolicy {
+            max_bytes: None,
+            max_age: days(7),
+        };
+
+        // A copy exactly as old as the limit is kept
+        assert_eq!(entries_to_prune(&entries, policy), [1, 3]);
+    }
+
+    #[test]
+    fn the_oldest_copies_are_pruned_until_the_rest_fit() {
+        let entries = entries(&[(40, 1), (30, 5), (20, 2), (10, 9)]);
+        let policy = PrunePolicy {
+            max_bytes: Some(65_000),
+            max_age: None,
+        };
+
+        assert_eq!(entries_to_prune(&entries, policy), [3, 1]);
+    }
+
+    #[test]
+    fn both_limits_are_kept_to() {
+        let entries = entries(&[(10, 1), (10, 30), (50, 2), (10, 3)]);
+        let policy = PrunePolicy {
+            max_bytes: Some(60_000),
+            max_age: days(14),
+        };
+
+        // Pruning the old copy alone leaves 70K, so the next oldest goes too, which is enough
+        assert_eq!(entries_to_prune(&entries, policy), [1, 3]);
+    }
+
+    #[test]
+    fn copies_of_the_same_age_are_pruned_in_the_order_given() {
+        let entries = entries(&[(10, 4), (10, 4), (10, 4)]);
+        let policy = PrunePolicy {
+            max_bytes: Some(15_000),
+            max_age: None,
+        };
+
+        assert_eq!(entries_to_prune(&entries, policy), [0, 1]);
+    }
+
+    #[test]
+    fn nothing_is_pruned_without_limits() {
+        let entries = entries(&[(1_000_000, 365), (1, 0)]);
+
+        assert!(entries_to_prune(&entries, PrunePolicy::default()).is_empty());
+        assert!(entries_to_prune(&[], PrunePolicy::default()).is_empty());
+    }
+}
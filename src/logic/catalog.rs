@@ -0,0 +1,96 @@
+//! An inventory of everything in the workspace, without any document content, which is small
+//! enough to keep around and compare between runs.
+
+use serde::{Deserialize, Serialize};
+
+use super::collections::{Collection, CollectionReader};
+use super::{fetch_all, Author, ConnectionError, Page};
+
+/// The metadata for every collection and published document in the workspace.
+#[derive(Debug, Clone, Serialize)]
+pub struct Catalog {
+    pub collections: Vec<CatalogCollection>,
+    pub documents: Vec<CatalogDocument>,
+}
+
+/// A collection's entry in a [Catalog].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogCollection {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl From<Collection> for CatalogCollection {
+    fn from(collection: Collection) -> Self {
+        Self {
+            id: collection.id,
+            name: collection.name,
+            description: collection.description,
+            created_at: collection.created_at,
+            updated_at: collection.updated_at,
+        }
+    }
+}
+
+/// A document's entry in a [Catalog], read from getOutline's document listing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogDocument {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub url_id: Option<String>,
+    #[serde(default)]
+    pub collection_id: Option<String>,
+    /// The document this one is nested under, or `None` if it's at the top of its collection.
+    #[serde(default)]
+    pub parent_document_id: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub created_by: Option<Author>,
+    #[serde(default)]
+    pub updated_by: Option<Author>,
+}
+
+/// Something which can list the metadata of the workspace's documents.
+pub trait CatalogReader {
+    fn documents(&self, page: Page) -> Result<Vec<CatalogDocument>, ConnectionError>;
+}
+
+/// Reads the metadata of every collection and published document in the workspace.
+///
+/// Collections keep their sidebar order. Documents are grouped by collection in the same order,
+/// then sorted by title, so catalogs taken at different times line up when compared.
+pub fn build_catalog<C>(client: &C) -> Result<Catalog, ConnectionError>
+where
+    C: CollectionReader + CatalogReader,
+{
+    let collections: Vec<CatalogCollection> =
+        fetch_all(|page| CollectionReader::list(client, page))?
+            .into_iter()
+            .map(CatalogCollection::from)
+            .collect();
+    let mut documents = fetch_all(|page| client.documents(page))?;
+
+    let position = |document: &CatalogDocument| {
+        collections
+            .iter()
+            .position(|collection| document.collection_id.as_ref() == Some(&collection.id))
+            .unwrap_or(collections.len())
+    };
+    documents.sort_by(|a, b| (position(a), &a.title, &a.id).cmp(&(position(b), &b.title, &b.id)));
+
+    Ok(Catalog {
+        collections,
+        documents,
+    })
+}
@@ -0,0 +1,610 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::documents::{
+    DocumentReader, DocumentUpdate, DocumentUpdater, DocumentWriter, NewDocument,
+};
+use super::file_operations::{
+    self, FileOperation, FileOperationError, FileOperationReader, FileOperationState,
+};
+use super::memberships::Permission;
+use super::{fetch_all, ConnectionError, Page};
+
+/// A collection of documents in the workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The access every workspace member gets (`read` or `read_write`), or `None` if the
+    /// collection is private to its members.
+    #[serde(default)]
+    pub permission: Option<String>,
+    /// Whether documents in the collection can be shared publicly.
+    #[serde(default)]
+    pub sharing: bool,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+/// A document's position in a collection's hierarchy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentNode {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub children: Vec<DocumentNode>,
+}
+
+/// Something which can look up collections.
+pub trait CollectionReader {
+    fn info(&self, id: &str) -> Result<Collection, ConnectionError>;
+    fn list(&self, page: Page) -> Result<Vec<Collection>, ConnectionError>;
+}
+
+/// Something which can read the nested structure of documents within a collection.
+pub trait CollectionTreeReader {
+    fn document_tree(&self, collection_id: &str) -> Result<Vec<DocumentNode>, ConnectionError>;
+}
+
+/// A collection along with how many documents it holds, counting nested documents.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionStats {
+    #[serde(flatten)]
+    pub collection: Collection,
+    pub document_count: usize,
+}
+
+/// Lists every collection in the workspace the user can see, in sidebar order.
+///
+/// getOutline doesn't report document counts in listings, so this reads each collection's
+/// document tree to count them.
+pub fn list_collections<C>(client: &C) -> Result<Vec<CollectionStats>, ConnectionError>
+where
+    C: CollectionReader + CollectionTreeReader,
+{
+    let collections = fetch_all(|page| client.list(page))?;
+
+    collections
+        .into_iter()
+        .map(|collection| with_stats(client, collection))
+        .collect()
+}
+
+/// Lists every collection in the workspace the user can see, in sidebar order, without counting
+/// their documents.
+pub fn all_collections(client: &impl CollectionReader) -> Result<Vec<Collection>, ConnectionError> {
+    fetch_all(|page| client.list(page))
+}
+
+/// A document in a collection's [outline].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineEntry {
+    pub id: String,
+    pub title: String,
+    /// How many documents it's nested under.
+    pub depth: usize,
+}
+
+/// Every document in `nodes` in the order they're shown in the sidebar, each followed by the
+/// documents nested under it.
+pub fn outline(nodes: &[DocumentNode]) -> Vec<OutlineEntry> {
+    fn add(entries: &mut Vec<OutlineEntry>, nodes: &[DocumentNode], depth: usize) {
+        for node in nodes {
+            entries.push(OutlineEntry {
+                id: node.id.clone(),
+                title: node.title.clone(),
+                depth,
+            });
+            add(entries, &node.children, depth + 1);
+        }
+    }
+
+    let mut entries = Vec::new();
+    add(&mut entries, nodes, 0);
+    entries
+}
+
+/// Looks up collection `id` and counts the documents in it.
+pub fn collection_info<C>(client: &C, id: &str) -> Result<CollectionStats, ConnectionError>
+where
+    C: CollectionReader + CollectionTreeReader,
+{
+    let collection = client.info(id)?;
+    with_stats(client, collection)
+}
+
+fn with_stats(
+    client: &impl CollectionTreeReader,
+    collection: Collection,
+) -> Result<CollectionStats, ConnectionError> {
+    let tree = client.document_tree(&collection.id)?;
+    Ok(CollectionStats {
+        document_count: count_documents(&tree),
+        collection,
+    })
+}
+
+fn count_documents(nodes: &[DocumentNode]) -> usize {
+    nodes
+        .iter()
+        .map(|node| 1 + count_documents(&node.children))
+        .sum()
+}
+
+/// Settings for a collection which is about to be created.
+#[derive(Debug)]
+pub struct NewCollection<'a> {
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    /// The access every workspace member gets, or `None` to keep the collection private to the
+    /// members it's shared with.
+    pub permission: Option<Permission>,
+}
+
+/// Something which can create new collections.
+pub trait CollectionWriter {
+    fn create(&self, collection: &NewCollection) -> Result<Collection, ConnectionError>;
+}
+
+/// Changes to make to an existing collection. Settings which are `None` are left as they are.
+#[derive(Debug)]
+pub struct CollectionUpdate<'a> {
+    pub id: &'a str,
+    pub name: Option<&'a str>,
+    pub description: Option<&'a str>,
+    /// The collection's icon color, as `#rrggbb`.
+    pub color: Option<&'a str>,
+}
+
+/// Something which can change a collection's settings.
+pub trait CollectionUpdater {
+    fn update(&self, update: &CollectionUpdate) -> Result<Collection, ConnectionError>;
+}
+
+/// Applies `update` to a collection, returning the collection as it is afterwards.
+pub fn update_collection(
+    updater: &impl CollectionUpdater,
+    update: &CollectionUpdate,
+) -> Result<Collection, ConnectionError> {
+    updater.update(update)
+}
+
+/// Something which can delete collections, along with every document in them.
+pub trait CollectionDeleter {
+    fn delete(&self, id: &str) -> Result<(), ConnectionError>;
+}
+
+/// Deletes collection `id` and every document in it.
+pub fn delete_collection(
+    deleter: &impl CollectionDeleter,
+    id: &str,
+) -> Result<(), ConnectionError> {
+    deleter.delete(id)
+}
+
+/// Creates a collection called `name`. Unless it's `private`, every workspace member can edit it.
+pub fn create_collection(
+    writer: &impl CollectionWriter,
+    name: &str,
+    description: Option<&str>,
+    private: bool,
+) -> Result<Collection, ConnectionError> {
+    writer.create(&NewCollection {
+        name,
+        description,
+        permission: (!private).then_some(Permission::ReadWrite),
+    })
+}
+
+/// The formats getOutline can export collections in. Each one is delivered as a zip archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExportFormat {
+    /// A markdown file per document, with attachments alongside.
+    OutlineMarkdown,
+    /// getOutline's own JSON format, which keeps everything needed to import it again.
+    Json,
+    /// An HTML file per document.
+    Html,
+}
+
+/// Something which can start exporting a collection in the background.
+pub trait CollectionExporter {
+    fn export(&self, id: &str, format: ExportFormat) -> Result<FileOperation, ConnectionError>;
+}
+
+/// Exports collection `id` as a zip archive in `format`, waiting for getOutline to finish
+/// building it. `on_state` is called as the export progresses.
+pub fn export_collection<C>(
+    client: &C,
+    id: &str,
+    format: ExportFormat,
+    on_state: impl FnMut(FileOperationState),
+) -> Result<Vec<u8>, FileOperationError>
+where
+    C: CollectionExporter + FileOperationReader,
+{
+    let operation = client.export(id, format)?;
+    file_operations::wait_and_download(client, operation, on_state)
+}
+
+/// Something which can start exporting every collection in the workspace in the background.
+pub trait WorkspaceExporter {
+    fn export_all(&self, format: ExportFormat) -> Result<FileOperation, ConnectionError>;
+}
+
+/// Exports every collection the API key can see as one zip archive in `format`, waiting for
+/// getOutline to finish building it. `on_state` is called as the export progresses.
+pub fn export_workspace<C>(
+    client: &C,
+    format: ExportFormat,
+    on_state: impl FnMut(FileOperationState),
+) -> Result<Vec<u8>, FileOperationError>
+where
+    C: WorkspaceExporter + FileOperationReader,
+{
+    let operation = client.export_all(format)?;
+    file_operations::wait_and_download(client, operation, on_state)
+}
+
+/// The outcome of copying a collection.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopiedCollection {
+    pub collection: Collection,
+    pub documents_copied: usize,
+    /// How many of the documents were copies from an earlier migration which got updated, rather
+    /// than new documents.
+    pub documents_updated: usize,
+}
+
+/// Creates a new collection called `new_name` in `destination` containing a copy of every document
+/// in the collection `source_id` from `source`, keeping their titles, content, and nesting.
+/// `on_copied` is called after each document has been copied.
+///
+/// `source` and `destination` may be the same workspace, or two entirely separate ones.
+pub fn copy_collection<S, D>(
+    source: &S,
+    destination: &D,
+    source_id: &str,
+    new_name: &str,
+    mut on_copied: impl FnMut(&DocumentNode),
+) -> Result<CopiedCollection, ConnectionError>
+where
+    S: CollectionTreeReader + DocumentReader,
+    D: CollectionWriter + DocumentWriter,
+{
+    // Read the whole tree up front so a bad source ID fails before anything gets created
+    let tree = source.document_tree(source_id)?;
+    let collection = create_copy(destination, new_name)?;
+
+    let mut documents_copied = 0;
+    copy_nodes(
+        source,
+        &tree,
+        &collection.id,
+        None,
+        &mut |_, document| DocumentWriter::create(destination, &document).map(|copy| copy.id),
+        &mut |node| {
+            documents_copied += 1;
+            on_copied(node);
+        },
+    )?;
+
+    Ok(CopiedCollection {
+        collection,
+        documents_copied,
+        documents_updated: 0,
+    })
+}
+
+/// Which collections and documents in one workspace were copied to which in another, keyed by
+/// their IDs in the workspace they were copied from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MigrationMap {
+    #[serde(default)]
+    pub collections: BTreeMap<String, String>,
+    #[serde(default)]
+    pub documents: BTreeMap<String, String>,
+}
+
+/// Copies a collection into `destination`, as [copy_collection] does, naming the new collection
+/// after the original unless `new_name` is given.
+///
+/// Everything copied is recorded in `mapping`, and anything `mapping` says was copied before is
+/// updated in place instead of being copied again, which makes it safe to run a migration more
+/// than once. Copies which have since been deleted are made again. `mapping` is kept up to date
+/// even when the migration fails part way through.
+pub fn migrate_collection<S, D>(
+    source: &S,
+    destination: &D,
+    source_id: &str,
+    new_name: Option<&str>,
+    mapping: &mut MigrationMap,
+    mut on_copied: impl FnMut(&DocumentNode),
+) -> Result<CopiedCollection, ConnectionError>
+where
+    S: CollectionReader + CollectionTreeReader + DocumentReader,
+    D: CollectionReader + CollectionWriter + DocumentWriter + DocumentUpdater,
+{
+    let tree = source.document_tree(source_id)?;
+    let earlier_copy = match mapping.collections.get(source_id) {
+        Some(id) => match CollectionReader::info(destination, id) {
+            Ok(collection) => Some(collection),
+            Err(ConnectionError::NotFound { .. }) => None,
+            Err(err) => return Err(err),
+        },
+        None => None,
+    };
+    let collection = match earlier_copy {
+        Some(collection) => collection,
+        None => {
+            let new_name = match new_name {
+                Some(name) => name.to_string(),
+                None => CollectionReader::info(source, source_id)?.name,
+            };
+            let collection = create_copy(destination, &new_name)?;
+            mapping
+                .collections
+                .insert(source_id.to_string(), collection.id.clone());
+            collection
+        }
+    };
+
+    let mut documents_copied = 0;
+    let mut documents_updated = 0;
+    copy_nodes(
+        source,
+        &tree,
+        &collection.id,
+        None,
+        &mut |node, document| {
+            if let Some(copy_id) = mapping.documents.get(&node.id) {
+                let update = DocumentUpdate {
+                    id: copy_id,
+                    text: document.text,
+                    title: Some(document.title),
+                    append: false,
+                    expected_revision: None,
+                };
+                match destination.update(&update) {
+                    Ok(copy) => {
+                        documents_updated += 1;
+                        return Ok(copy.id);
+                    }
+                    Err(ConnectionError::NotFound { .. }) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+
+            let copy = DocumentWriter::create(destination, &document)?;
+            mapping.documents.insert(node.id.clone(), copy.id.clone());
+            Ok(copy.id)
+        },
+        &mut |node| {
+            documents_copied += 1;
+            on_copied(node);
+        },
+    )?;
+
+    Ok(CopiedCollection {
+        collection,
+        documents_copied,
+        documents_updated,
+    })
+}
+
+fn create_copy(
+    destination: &impl CollectionWriter,
+    name: &str,
+) -> Result<Collection, ConnectionError> {
+    CollectionWriter::create(
+        destination,
+        &NewCollection {
+            name,
+            description: None,
+            permission: None,
+        },
+    )
+}
+
+/// Copies `nodes` and everything nested under them, reading each document from `source` and
+/// handing it to `write`, which returns the ID of the copy.
+fn copy_nodes<S>(
+    source: &S,
+    nodes: &[DocumentNode],
+    collection_id: &str,
+    parent_document_id: Option<&str>,
+    write: &mut impl FnMut(&DocumentNode, NewDocument) -> Result<String, ConnectionError>,
+    on_copied: &mut impl FnMut(&DocumentNode),
+) -> Result<(), ConnectionError>
+where
+    S: DocumentReader,
+{
+    for node in nodes {
+        let original = source.info(&node.id)?;
+        let copy_id = write(
+            node,
+            NewDocument {
+                title: &original.title,
+                text: &original.text,
+                collection_id,
+                parent_document_id,
+            },
+        )?;
+        on_copied(node);
+
+        copy_nodes(
+            source,
+            &node.children,
+            collection_id,
+            Some(&copy_id),
+            write,
+            on_copied,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::documents::DocumentDeleter;
+    use crate::logic::fake::{MemoryWorkspace, StoredDocument};
+
+    /// A source workspace with an "Engineering" collection holding a runbook with a nested page.
+    fn source() -> (MemoryWorkspace, String) {
+        let source = MemoryWorkspace::default();
+        let collection_id = source.add_collection("Engineering");
+        let runbook = source.add_to_collection(&collection_id, None, "Runbook", "Restart it");
+        source.add_to_collection(&collection_id, Some(&runbook), "Rollback", "Revert it");
+        (source, collection_id)
+    }
+
+    #[test]
+    fn copies_keep_each_documents_title_text_and_nesting() {
+        let (source, collection_id) = source();
+        let destination = MemoryWorkspace::default();
+        let mut copied = Vec::new();
+        let copy = copy_collection(&source, &destination, &collection_id, "Copy", |node| {
+            copied.push(node.title.clone())
+        })
+        .unwrap();
+
+        assert_eq!(copy.collection.name, "Copy");
+        assert_eq!(copy.documents_copied, 2);
+        assert_eq!(copied, ["Runbook", "Rollback"]);
+        let copies = destination.documents_in(&copy.collection.id);
+        let summary = |copy: &StoredDocument| {
+            (
+                copy.summary.title.clone(),
+                copy.text.clone(),
+                copy.parent_id.clone(),
+            )
+        };
+        assert_eq!(
+            copies.iter().map(summary).collect::<Vec<_>>(),
+            [
+                ("Runbook".to_string(), "Restart it".to_string(), None),
+                (
+                    "Rollback".to_string(),
+                    "Revert it".to_string(),
+                    Some(copies[0].summary.id.clone())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn copying_a_missing_collection_creates_nothing() {
+        let (source, _) = source();
+        let destination = MemoryWorkspace::default();
+        let copy = copy_collection(&source, &destination, "missing", "Copy", |_| {});
+
+        assert!(matches!(copy, Err(ConnectionError::NotFound { .. })));
+        assert_eq!(destination.collection_count(), 0);
+    }
+
+    fn migrate(
+        source: &MemoryWorkspace,
+        destination: &MemoryWorkspace,
+        collection_id: &str,
+        mapping: &mut MigrationMap,
+    ) -> Result<CopiedCollection, ConnectionError> {
+        migrate_collection(source, destination, collection_id, None, mapping, |_| {})
+    }
+
+    #[test]
+    fn rerunning_a_migration_updates_the_copies_instead_of_duplicating_them() {
+        let (source, collection_id) = source();
+        let destination = MemoryWorkspace::default();
+        let mut mapping = MigrationMap::default();
+        let first = migrate(&source, &destination, &collection_id, &mut mapping).unwrap();
+
+        let runbook = source.documents_in(&collection_id)[0].summary.id.clone();
+        DocumentUpdater::update(
+            &source,
+            &DocumentUpdate {
+                id: &runbook,
+                text: "Restart it twice",
+                title: None,
+                append: false,
+                expected_revision: None,
+            },
+        )
+        .unwrap();
+        let second = migrate(&source, &destination, &collection_id, &mut mapping).unwrap();
+
+        assert_eq!((first.documents_copied, first.documents_updated), (2, 0));
+        assert_eq!((second.documents_copied, second.documents_updated), (2, 2));
+        let copies = destination.documents_in(&first.collection.id);
+        assert_eq!(copies.len(), 2);
+        assert_eq!(copies[0].summary.id, mapping.documents[&runbook]);
+        assert_eq!(copies[0].text, "Restart it twice");
+        assert_eq!(copies[1].parent_id.as_deref(), Some(&*copies[0].summary.id));
+    }
+
+    #[test]
+    fn rerunning_a_migration_reuses_the_collection_it_copied_into() {
+        let (source, collection_id) = source();
+        let destination = MemoryWorkspace::default();
+        let mut mapping = MigrationMap::default();
+        let first = migrate(&source, &destination, &collection_id, &mut mapping).unwrap();
+        let second = migrate(&source, &destination, &collection_id, &mut mapping).unwrap();
+
+        assert_eq!(first.collection.name, "Engineering");
+        assert_eq!(second.collection.id, first.collection.id);
+        assert_eq!(mapping.collections[&collection_id], first.collection.id);
+        assert_eq!(destination.collection_count(), 1);
+    }
+
+    #[test]
+    fn copies_deleted_since_the_last_migration_are_made_again() {
+        let (source, collection_id) = source();
+        let destination = MemoryWorkspace::default();
+        let mut mapping = MigrationMap::default();
+        let first = migrate(&source, &destination, &collection_id, &mut mapping).unwrap();
+
+        let runbook = source.documents_in(&collection_id)[0].summary.id.clone();
+        let deleted = mapping.documents[&runbook].clone();
+        destination.delete(&deleted, true).unwrap();
+        let second = migrate(&source, &destination, &collection_id, &mut mapping).unwrap();
+
+        // The rollback page's copy was still there, so only it is updated
+        assert_eq!((second.documents_copied, second.documents_updated), (2, 1));
+        let copies = destination.documents_in(&first.collection.id);
+        assert_eq!(copies.len(), 2);
+        let recreated = &mapping.documents[&runbook];
+        assert_ne!(recreated, &deleted);
+        assert!(copies
+            .iter()
+            .any(|copy| &copy.summary.id == recreated && copy.text == "Restart it"));
+    }
+
+    #[test]
+    fn the_mapping_keeps_what_was_copied_before_a_migration_failed() {
+        let (source, collection_id) = source();
+        let destination = MemoryWorkspace::default();
+        let mut mapping = MigrationMap::default();
+        destination.fail_creating("Rollback");
+        assert!(migrate(&source, &destination, &collection_id, &mut mapping).is_err());
+
+        let runbook = source.documents_in(&collection_id)[0].summary.id.clone();
+        assert_eq!(mapping.collections.len(), 1);
+        assert_eq!(mapping.documents.keys().collect::<Vec<_>>(), [&runbook]);
+
+        destination.stop_failing();
+        let rerun = migrate(&source, &destination, &collection_id, &mut mapping).unwrap();
+        assert_eq!((rerun.documents_copied, rerun.documents_updated), (2, 1));
+        assert_eq!(destination.documents_in(&rerun.collection.id).len(), 2);
+        assert_eq!(destination.collection_count(), 1);
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use super::ConnectionError;
+
+/// A comment left on a document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub id: String,
+    pub document_id: String,
+}
+
+/// Something which can comment on documents.
+pub trait CommentWriter {
+    /// Leaves a comment on document `document_id`, with `text` in markdown.
+    fn create(&self, document_id: &str, text: &str) -> Result<Comment, ConnectionError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommentError {
+    #[error("the comment is empty")]
+    Empty,
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+}
+
+/// Comments `text` on document `document_id`, refusing to leave a comment with nothing in it.
+pub fn comment_on_document(
+    writer: &impl CommentWriter,
+    document_id: &str,
+    text: &str,
+) -> Result<Comment, CommentError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(CommentError::Empty);
+    }
+
+    Ok(writer.create(document_id, text)?)
+}
@@ -0,0 +1,77 @@
+//! Document and collection IDs to offer when completing a command line in the shell, from a list
+//! of the workspace's documents and collections kept on disk.
+
+use serde::{Deserialize, Serialize};
+
+use super::collections::CollectionReader;
+use super::documents::DocumentLister;
+use super::{fetch_all, ConnectionError};
+
+/// How long the list is used before it's fetched again, in seconds.
+pub const COMPLETION_LIST_TTL: u64 = 60 * 60;
+
+/// The workspace's documents and collections, as of when it was fetched.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionList {
+    /// When the list was fetched, in seconds since the Unix epoch.
+    pub fetched_at: u64,
+    pub documents: Vec<Candidate>,
+    pub collections: Vec<Candidate>,
+}
+
+impl CompletionList {
+    /// Whether the list is too old to use at `now`, or was never fetched at all.
+    pub fn is_stale(&self, now: u64) -> bool {
+        now.saturating_sub(self.fetched_at) >= COMPLETION_LIST_TTL
+    }
+}
+
+/// Something a command line could be completed with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candidate {
+    pub id: String,
+    /// The document's title or collection's name, shown next to the ID by shells which can.
+    pub title: String,
+}
+
+impl Candidate {
+    /// Whether the candidate fits what's been typed so far: the start of its ID, or of a word in
+    /// its title, ignoring case.
+    pub fn matches(&self, typed: &str) -> bool {
+        let typed = typed.to_lowercase();
+        self.id.to_lowercase().starts_with(&typed)
+            || self
+                .title
+                .to_lowercase()
+                .split_whitespace()
+                .any(|word| word.starts_with(&typed))
+    }
+}
+
+/// Fetches every published document and collection the client can see, as of `now`.
+pub fn fetch_completion_list<C>(client: &C, now: u64) -> Result<CompletionList, ConnectionError>
+where
+    C: DocumentLister + CollectionReader,
+{
+    let documents = fetch_all(|page| DocumentLister::list(client, page))?
+        .into_iter()
+        .map(|document| Candidate {
+            id: document.url_id.unwrap_or(document.id),
+            title: document.title,
+        })
+        .collect();
+    let collections = fetch_all(|page| CollectionReader::list(client, page))?
+        .into_iter()
+        .map(|collection| Candidate {
+            id: collection.id,
+            title: collection.name,
+        })
+        .collect();
+
+    Ok(CompletionList {
+        fetched_at: now,
+        documents,
+        collections,
+    })
+}
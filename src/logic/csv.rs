@@ -0,0 +1,21 @@
+//! Writing listings as CSV, for spreadsheets and other tools which read it.
+
+use std::borrow::Cow;
+
+/// One line of CSV with `fields` in order, without the line ending. Fields with commas, quotes, or
+/// line breaks in them are quoted, with any quotes doubled, as RFC 4180 describes.
+pub fn csv_record<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|field| csv_field(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
@@ -0,0 +1,158 @@
+//! A summary of what changed in the workspace recently, to post for everyone who doesn't follow
+//! every edit.
+
+use serde::Serialize;
+
+use super::catalog::CatalogDocument;
+use super::documents::web_path;
+use super::{Author, ConnectionError, Page};
+
+/// How many documents to request at a time while looking for ones changed since a date.
+const DIGEST_PAGE_SIZE: u32 = 100;
+
+/// Something which can list documents, most recently updated first.
+pub trait RecentlyUpdatedReader {
+    /// Lists one page of the published documents in collection `collection_id`, or the whole
+    /// workspace if it's `None`, most recently updated first.
+    fn recently_updated(
+        &self,
+        collection_id: Option<&str>,
+        page: Page,
+    ) -> Result<Vec<CatalogDocument>, ConnectionError>;
+}
+
+/// The documents created or updated since some point in time.
+#[derive(Debug, Clone, Serialize)]
+pub struct Digest {
+    /// When the digest starts, as an ISO 8601 date or timestamp in UTC.
+    pub since: String,
+    /// Documents created since then, newest first.
+    pub created: Vec<CatalogDocument>,
+    /// Documents created before then but updated since, most recently updated first.
+    pub updated: Vec<CatalogDocument>,
+}
+
+/// Finds the documents in collection `collection_id` (or the whole workspace) which were created
+/// or updated from `since` onwards, an ISO 8601 date or timestamp in UTC like `2024-05-01`.
+///
+/// getOutline can't filter documents by date, so this reads pages of the most recently updated
+/// ones until it gets to older ones.
+pub fn build_digest(
+    reader: &impl RecentlyUpdatedReader,
+    collection_id: Option<&str>,
+    since: &str,
+) -> Result<Digest, ConnectionError> {
+    let mut digest = Digest {
+        since: since.to_string(),
+        created: Vec::new(),
+        updated: Vec::new(),
+    };
+    let mut page = Page {
+        offset: 0,
+        limit: DIGEST_PAGE_SIZE,
+    };
+
+    loop {
+        let fetched = reader.recently_updated(collection_id, page)?;
+        let count = fetched.len() as u32;
+        let mut reached_older = false;
+        for document in fetched {
+            // Timestamps in the same format and time zone sort the same way as the times they're
+            // for
+            if document.updated_at.as_deref().unwrap_or_default() < since {
+                reached_older = true;
+                break;
+            }
+            if document.created_at.as_deref().unwrap_or_default() >= since {
+                digest.created.push(document);
+            } else {
+                digest.updated.push(document);
+            }
+        }
+
+        if reached_older || count < page.limit {
+            digest
+                .created
+                .sort_by(|a, b| b.created_at.cmp(&a.created_at));
+            return Ok(digest);
+        }
+        page.offset += count;
+    }
+}
+
+/// The digest as a markdown document, linking to each document in the workspace at `base_url`.
+pub fn digest_markdown(digest: &Digest, base_url: &str) -> String {
+    if digest.created.is_empty() && digest.updated.is_empty() {
+        return format!(
+            "Nothing was created or updated since {}.\n",
+            date(&digest.since)
+        );
+    }
+
+    let mut markdown = String::new();
+    push_section(
+        &mut markdown,
+        "New documents",
+        &digest.created,
+        base_url,
+        ("created", |document| {
+            (document.created_by.as_ref(), document.created_at.as_deref())
+        }),
+    );
+    push_section(
+        &mut markdown,
+        "Updated documents",
+        &digest.updated,
+        base_url,
+        ("updated", |document| {
+            (document.updated_by.as_ref(), document.updated_at.as_deref())
+        }),
+    );
+    markdown
+}
+
+/// Who changed a document and when, for one of the ways it can have changed.
+type Change = fn(&CatalogDocument) -> (Option<&Author>, Option<&str>);
+
+/// Adds a list of `documents` under `heading` to `markdown`, saying how each one changed, unless
+/// there aren't any.
+fn push_section(
+    markdown: &mut String,
+    heading: &str,
+    documents: &[CatalogDocument],
+    base_url: &str,
+    (verb, change): (&str, Change),
+) {
+    if documents.is_empty() {
+        return;
+    }
+    if !markdown.is_empty() {
+        markdown.push('\n');
+    }
+
+    markdown.push_str(&format!("## {heading}\n\n"));
+    for document in documents {
+        let link = web_path(None, document.url_id.as_deref().unwrap_or(&document.id));
+        let title = document.title.replace('[', "\\[").replace(']', "\\]");
+        markdown.push_str(&format!("- [{title}]({base_url}{link}), {verb}"));
+
+        let (author, when) = change(document);
+        if let Some(author) = author {
+            markdown.push_str(&format!(" by {}", author.name));
+        }
+        if let Some(when) = when {
+            markdown.push_str(&format!(" on {}", date(when)));
+        }
+        markdown.push('\n');
+    }
+}
+
+/// The title to publish the digest under when no other is given.
+pub fn digest_title(digest: &Digest) -> String {
+    format!("What changed since {}", date(&digest.since))
+}
+
+/// The date part of an ISO 8601 timestamp, like `2024-05-01` for `2024-05-01T09:00:00Z`.
+fn date(timestamp: &str) -> &str {
+    timestamp.get(..10).unwrap_or(timestamp)
+}
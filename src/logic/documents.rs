@@ -0,0 +1,669 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use super::collections::CollectionReader;
+use super::sections::Section;
+use super::{fetch_all, parsers, ConnectionError, Page};
+
+/// The identifying details of a document, as returned in listings and search results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSummary {
+    pub id: String,
+    pub title: String,
+    /// The short ID at the end of the document's link, which works anywhere `id` does.
+    #[serde(default)]
+    pub url_id: Option<String>,
+    /// Where the document can be found in the web app, relative to the workspace's URL.
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl DocumentSummary {
+    /// Whether `reference` is this document's full ID or its short [url_id](Self::url_id).
+    pub fn is(&self, reference: &str) -> bool {
+        self.id == reference || self.url_id.as_deref() == Some(reference)
+    }
+
+    /// Where the document can be found in the web app, even when getOutline didn't send a `url`.
+    pub fn web_path(&self) -> String {
+        web_path(
+            self.url.as_deref(),
+            self.url_id.as_deref().unwrap_or(&self.id),
+        )
+    }
+}
+
+/// The path to a document in the web app, relative to the workspace's URL: `url` if getOutline
+/// sent one, or else a link by `id`, which getOutline redirects to the document.
+pub fn web_path(url: Option<&str>, id: &str) -> String {
+    match url {
+        Some(url) => url.to_string(),
+        None => format!("/doc/{id}"),
+    }
+}
+
+/// A document along with its markdown content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    /// The document's full ID, which is what everything else getOutline sends back refers to it
+    /// by.
+    #[serde(default)]
+    pub id: String,
+    pub title: String,
+    pub text: String,
+    /// When the document was published, or `None` if it's still a draft.
+    pub published_at: Option<String>,
+    /// Where the document can be found in the web app, relative to the workspace's URL.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// How many times the document has been saved, which goes up whenever anyone edits it.
+    #[serde(default)]
+    pub revision: Option<u64>,
+}
+
+/// Something which can list the documents in the workspace.
+pub trait DocumentLister {
+    fn list(&self, page: Page) -> Result<Vec<DocumentSummary>, ConnectionError>;
+    /// Lists the drafts of the user the connection acts as, which `list` leaves out.
+    fn drafts(&self, page: Page) -> Result<Vec<DocumentSummary>, ConnectionError>;
+}
+
+/// Something which can fetch the full content of a document.
+pub trait DocumentReader {
+    fn info(&self, id: &str) -> Result<Document, ConnectionError>;
+}
+
+/// The full ID of document `reference`, which can also be its short ID from a link.
+///
+/// Things getOutline sends back, like revisions, only refer to documents by their full ID, so a
+/// short one has to be looked up before it can be compared with them.
+pub fn canonical_id(
+    reader: &impl DocumentReader,
+    reference: &str,
+) -> Result<String, ConnectionError> {
+    if parsers::is_uuid(reference) {
+        return Ok(reference.to_string());
+    }
+    let document = reader.info(reference)?;
+
+    Ok(if document.id.is_empty() {
+        reference.to_string()
+    } else {
+        document.id
+    })
+}
+
+/// Something which can have getOutline render a document to markdown on the server.
+///
+/// This resolves embeds and attachments differently from the raw text returned by
+/// [DocumentReader::info], so the two can differ for complex documents.
+pub trait DocumentExporter {
+    fn export(&self, id: &str) -> Result<String, ConnectionError>;
+    /// Has getOutline render the document into a non-markdown file format.
+    fn export_file(&self, id: &str, format: FileFormat) -> Result<Vec<u8>, ConnectionError>;
+}
+
+/// A file format other than markdown which getOutline can render documents into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Html,
+    Pdf,
+}
+
+impl FileFormat {
+    /// The file extension documents in this format should be saved with.
+    pub fn extension(self) -> &'static str {
+        match self {
+            FileFormat::Html => "html",
+            FileFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// A document rendered into a [FileFormat] by getOutline.
+#[derive(Debug)]
+pub struct ExportedFile {
+    pub title: String,
+    pub content: Vec<u8>,
+}
+
+/// Where a document's markdown should come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentSource {
+    /// The document's stored text, from `documents.info`.
+    Info,
+    /// Server-rendered markdown, from `documents.export`.
+    Export,
+}
+
+/// A single document matching a search query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "RawSearchResult")]
+pub struct SearchResult {
+    /// The snippet of document text surrounding the match.
+    pub context: String,
+    pub document: DocumentSummary,
+    /// The section of the document the match is in, if it comes after a heading.
+    pub section: Option<Section>,
+}
+
+/// A search result as getOutline sends it, with the document's full text.
+#[derive(Deserialize)]
+struct RawSearchResult {
+    context: String,
+    document: SearchedDocument,
+}
+
+#[derive(Deserialize)]
+struct SearchedDocument {
+    #[serde(flatten)]
+    summary: DocumentSummary,
+    #[serde(default)]
+    text: String,
+}
+
+impl From<RawSearchResult> for SearchResult {
+    fn from(raw: RawSearchResult) -> Self {
+        // getOutline marks the words which matched in bold
+        let matched = raw
+            .context
+            .split_once("<b>")
+            .and_then(|(_, rest)| rest.split_once("</b>"))
+            .map(|(term, _)| term);
+        let section =
+            matched.and_then(|term| parsers::section_containing(&raw.document.text, term));
+
+        Self {
+            context: raw.context,
+            document: raw.document.summary,
+            section,
+        }
+    }
+}
+
+/// What to search for, and where.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchQuery<'a> {
+    pub text: &'a str,
+    /// Limits the search to a single collection.
+    pub collection_id: Option<&'a str>,
+}
+
+/// Something which can run a full-text search over the workspace's documents.
+pub trait SearchReader {
+    fn search(&self, query: &SearchQuery, page: Page)
+        -> Result<Vec<SearchResult>, ConnectionError>;
+}
+
+/// A document to be published from local markdown.
+#[derive(Debug)]
+pub struct NewDocument<'a> {
+    pub title: &'a str,
+    pub text: &'a str,
+    pub collection_id: &'a str,
+    /// The document to nest the new document under, if it shouldn't be at the collection's top
+    /// level.
+    pub parent_document_id: Option<&'a str>,
+}
+
+/// Something which can publish new documents to the workspace.
+pub trait DocumentWriter {
+    fn create(&self, document: &NewDocument) -> Result<DocumentSummary, ConnectionError>;
+}
+
+/// Changes to push to an existing document.
+#[derive(Debug)]
+pub struct DocumentUpdate<'a> {
+    pub id: &'a str,
+    pub text: &'a str,
+    /// The new title, if it should change.
+    pub title: Option<&'a str>,
+    /// Whether `text` should be added to the end of the document instead of replacing it.
+    pub append: bool,
+    /// The [Document::revision] the change was worked out from. If the document has been saved
+    /// since, the update is refused with [ConnectionError::RemoteChanged] instead of overwriting
+    /// someone else's edits.
+    pub expected_revision: Option<u64>,
+}
+
+/// Something which can change the contents of existing documents.
+pub trait DocumentUpdater {
+    fn update(&self, update: &DocumentUpdate) -> Result<DocumentSummary, ConnectionError>;
+}
+
+/// Something which can remove documents from the workspace.
+pub trait DocumentDeleter {
+    /// Moves document `id` to the trash, or destroys it outright if `permanent` is set.
+    fn delete(&self, id: &str, permanent: bool) -> Result<(), ConnectionError>;
+}
+
+/// Something which can move published documents back to drafts.
+pub trait DocumentUnpublisher {
+    fn unpublish(&self, id: &str) -> Result<DocumentSummary, ConnectionError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnpublishError {
+    #[error("\"{title}\" is already a draft")]
+    AlreadyDraft { title: String },
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+}
+
+/// Lists every published document the user can see.
+pub fn all_documents(
+    lister: &impl DocumentLister,
+) -> Result<Vec<DocumentSummary>, ConnectionError> {
+    fetch_all(|page| lister.list(page))
+}
+
+/// Searches for documents matching `query`, returning one page of results with each result's
+/// context snippet cleaned up for display.
+pub fn search_documents(
+    reader: &impl SearchReader,
+    query: &str,
+    page: Page,
+) -> Result<Vec<SearchResult>, ConnectionError> {
+    let query = SearchQuery {
+        text: query,
+        collection_id: None,
+    };
+
+    reader
+        .search(&query, page)
+        .map(|results| results.into_iter().map(clean_result).collect())
+}
+
+/// Searches for documents matching `query` like [search_documents], but returns every page of
+/// results.
+pub fn search_every_page(
+    reader: &impl SearchReader,
+    query: &str,
+) -> Result<Vec<SearchResult>, ConnectionError> {
+    let query = SearchQuery {
+        text: query,
+        collection_id: None,
+    };
+
+    let results = fetch_all(|page| reader.search(&query, page))?;
+    Ok(results.into_iter().map(clean_result).collect())
+}
+
+fn clean_result(mut result: SearchResult) -> SearchResult {
+    result.context = clean_context(&result.context);
+    result
+}
+
+/// Collects every document matching `query` across all pages of search results.
+pub fn search_all_documents(
+    reader: &impl SearchReader,
+    query: &SearchQuery,
+) -> Result<Vec<DocumentSummary>, ConnectionError> {
+    let results = fetch_all(|page| reader.search(query, page))?;
+    Ok(results.into_iter().map(|result| result.document).collect())
+}
+
+/// How many collections [search_workspace] searches at once.
+const PARALLEL_SEARCHES: usize = 4;
+
+/// Collects every document matching `query`, like [search_all_documents].
+///
+/// A search of the whole workspace is split up by collection, with a few collections searched at
+/// once, which is quicker than paging through one long list on workspaces with many collections.
+/// Documents come back grouped by collection, in the order the collections are listed.
+pub fn search_workspace<C>(
+    client: &C,
+    query: &SearchQuery,
+) -> Result<Vec<DocumentSummary>, ConnectionError>
+where
+    C: SearchReader + CollectionReader + Sync,
+{
+    if query.collection_id.is_some() {
+        return search_all_documents(client, query);
+    }
+
+    let collections = fetch_all(|page| CollectionReader::list(client, page))?;
+    let next = AtomicUsize::new(0);
+    let searched = Mutex::new(Vec::with_capacity(collections.len()));
+    thread::scope(|scope| {
+        for _ in 0..PARALLEL_SEARCHES.min(collections.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(collection) = collections.get(index) else {
+                    break;
+                };
+                let query = SearchQuery {
+                    text: query.text,
+                    collection_id: Some(&collection.id),
+                };
+                let result = search_all_documents(client, &query);
+                let failed = result.is_err();
+                searched.lock().unwrap().push((index, result));
+                if failed {
+                    // Stop the other searches from picking up more collections
+                    next.store(collections.len(), Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    let mut searched = searched.into_inner().unwrap();
+    searched.sort_by_key(|(index, _)| *index);
+
+    // A document can turn up twice if it was moved between collections while they were searched
+    let mut seen = HashSet::new();
+    let mut documents = Vec::new();
+    for (_, result) in searched {
+        for document in result? {
+            if seen.insert(document.id.clone()) {
+                documents.push(document);
+            }
+        }
+    }
+
+    Ok(documents)
+}
+
+/// Strips the `<b>` highlight markup getOutline puts around matched terms and flattens the snippet
+/// onto a single line.
+fn clean_context(context: &str) -> String {
+    context
+        .replace("<b>", "")
+        .replace("</b>", "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Publishes `markdown` as a new document in the collection `collection_id`.
+///
+/// Any front matter block is left out of the document. If no `title` is given, a `title` in the
+/// front matter is used, then a leading `# Heading` in the markdown (which is removed from the body
+/// so it isn't repeated), falling back to `fallback_title`.
+pub fn create_document(
+    writer: &impl DocumentWriter,
+    collection_id: &str,
+    markdown: &str,
+    title: Option<&str>,
+    fallback_title: &str,
+) -> Result<DocumentSummary, ConnectionError> {
+    let (front_matter, markdown) = parsers::split_front_matter(markdown);
+    let title = title.or_else(|| {
+        front_matter
+            .and_then(|fields| fields.get("title"))
+            .filter(|title| !title.is_empty())
+    });
+
+    let (heading, body) = split_title(markdown);
+    let (title, text) = match (title, heading) {
+        (Some(title), _) => (title, markdown),
+        (None, Some(heading)) => (heading, body),
+        (None, None) => (fallback_title, markdown),
+    };
+
+    writer.create(&NewDocument {
+        title,
+        text,
+        collection_id,
+        parent_document_id: None,
+    })
+}
+
+/// Splits a leading level-one heading off of `markdown`, returning the heading text and the rest
+/// of the document.
+fn split_title(markdown: &str) -> (Option<&str>, &str) {
+    let trimmed = markdown.trim_start();
+    let (first_line, rest) = trimmed.split_once('\n').unwrap_or((trimmed, ""));
+
+    match first_line.strip_prefix("# ") {
+        Some(heading) if !heading.trim().is_empty() => (Some(heading.trim()), rest.trim_start()),
+        _ => (None, markdown),
+    }
+}
+
+/// Replaces the content of document `id` with `markdown`, or adds it to the end if `append` is set.
+///
+/// With an `expected_revision`, the update only goes through if nobody has saved the document since
+/// that revision.
+pub fn update_document(
+    updater: &impl DocumentUpdater,
+    id: &str,
+    markdown: &str,
+    title: Option<&str>,
+    append: bool,
+    expected_revision: Option<u64>,
+) -> Result<DocumentSummary, ConnectionError> {
+    updater.update(&DocumentUpdate {
+        id,
+        text: markdown,
+        title,
+        append,
+        expected_revision,
+    })
+}
+
+/// Deletes document `id`, either by moving it to the trash or permanently.
+pub fn delete_document(
+    deleter: &impl DocumentDeleter,
+    id: &str,
+    permanent: bool,
+) -> Result<(), ConnectionError> {
+    deleter.delete(id, permanent)
+}
+
+/// Moves published document `id` back to the author's drafts.
+pub fn unpublish_document<C>(client: &C, id: &str) -> Result<DocumentSummary, UnpublishError>
+where
+    C: DocumentReader + DocumentUnpublisher,
+{
+    let document = client.info(id)?;
+    if document.published_at.is_none() {
+        return Err(UnpublishError::AlreadyDraft {
+            title: document.title,
+        });
+    }
+
+    Ok(client.unpublish(id)?)
+}
+
+/// Fetches document `id` with its markdown taken from `source`.
+pub fn fetch_document<C>(
+    client: &C,
+    id: &str,
+    source: ContentSource,
+) -> Result<Document, ConnectionError>
+where
+    C: DocumentReader + DocumentExporter,
+{
+    let mut document = client.info(id)?;
+    if source == ContentSource::Export {
+        document.text = client.export(id)?;
+    }
+
+    Ok(document)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PatchError {
+    #[error("the patch is not a valid unified diff")]
+    InvalidPatch(#[source] diffy::ParsePatchError),
+    #[error("the patch does not apply to the document's current content")]
+    DoesNotApply(#[source] diffy::ApplyError),
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+}
+
+/// What happened when a patch was applied to a document.
+#[derive(Debug)]
+pub enum PatchOutcome {
+    Updated(DocumentSummary),
+    /// The patch applied, but left the content exactly as it was, so nothing was pushed.
+    Unchanged {
+        title: String,
+    },
+}
+
+/// Applies the unified diff `patch` to the current content of document `id` and pushes the result.
+///
+/// The patch must apply cleanly to the remote content; nothing is updated if any hunk fails.
+pub fn patch_document<C>(client: &C, id: &str, patch: &str) -> Result<PatchOutcome, PatchError>
+where
+    C: DocumentReader + DocumentUpdater,
+{
+    let patch = diffy::Patch::from_str(patch).map_err(PatchError::InvalidPatch)?;
+    let document = client.info(id)?;
+    let patched = diffy::apply(&document.text, &patch).map_err(PatchError::DoesNotApply)?;
+
+    if patched == document.text {
+        return Ok(PatchOutcome::Unchanged {
+            title: document.title,
+        });
+    }
+
+    let updated = client.update(&DocumentUpdate {
+        id,
+        text: &patched,
+        title: None,
+        append: false,
+        expected_revision: document.revision,
+    })?;
+    Ok(PatchOutcome::Updated(updated))
+}
+
+/// Fetches document `id` rendered into `format` by getOutline.
+pub fn export_file<C>(
+    client: &C,
+    id: &str,
+    format: FileFormat,
+) -> Result<ExportedFile, ConnectionError>
+where
+    C: DocumentReader + DocumentExporter,
+{
+    let title = client.info(id)?.title;
+    let content = client.export_file(id, format)?;
+
+    Ok(ExportedFile { title, content })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::fake::MemoryWorkspace;
+
+    /// Creates `markdown` as a document in a new collection, returning its title and text.
+    fn create(markdown: &str, title: Option<&str>) -> (String, String) {
+        let workspace = MemoryWorkspace::default();
+        let collection_id = workspace.add_collection("Engineering");
+        create_document(&workspace, &collection_id, markdown, title, "notes").unwrap();
+
+        let created = workspace.documents_in(&collection_id).remove(0);
+        (created.summary.title, created.text)
+    }
+
+    #[test]
+    fn a_leading_heading_becomes_the_title() {
+        assert_eq!(
+            create("# Runbook\n\nRestart it.", None),
+            ("Runbook".to_string(), "Restart it.".to_string())
+        );
+    }
+
+    #[test]
+    fn blank_lines_before_the_heading_are_skipped() {
+        assert_eq!(
+            create("\n\n# Runbook\nRestart it.", None),
+            ("Runbook".to_string(), "Restart it.".to_string())
+        );
+    }
+
+    #[test]
+    fn documents_without_a_heading_get_the_fallback_title() {
+        let markdown = "Restart it.\n\n# Later heading";
+        assert_eq!(
+            create(markdown, None),
+            ("notes".to_string(), markdown.to_string())
+        );
+    }
+
+    #[test]
+    fn only_a_level_one_heading_becomes_the_title() {
+        for markdown in [
+            "## Runbook\nRestart it.",
+            "#Runbook\nRestart it.",
+            "# \nRestart it.",
+        ] {
+            assert_eq!(
+                create(markdown, None),
+                ("notes".to_string(), markdown.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn an_explicit_title_keeps_the_heading_in_the_document() {
+        let markdown = "# Runbook\nRestart it.";
+        assert_eq!(
+            create(markdown, Some("Deploys")),
+            ("Deploys".to_string(), markdown.to_string())
+        );
+    }
+
+    #[test]
+    fn a_front_matter_title_is_used_and_the_front_matter_left_out() {
+        assert_eq!(
+            create("---\ntitle: Deploys\n---\n# Runbook\nRestart it.", None),
+            ("Deploys".to_string(), "# Runbook\nRestart it.".to_string())
+        );
+        assert_eq!(
+            create(
+                "---\ntitle: Deploys\n---\n# Runbook\nRestart it.",
+                Some("Rollback")
+            )
+            .0,
+            "Rollback"
+        );
+    }
+
+    #[test]
+    fn patches_are_applied_to_the_current_content() {
+        let workspace = MemoryWorkspace::default();
+        let collection_id = workspace.add_collection("Engineering");
+        let id = workspace.add_to_collection(&collection_id, None, "Runbook", "Restart it.\n");
+        let patch = "--- a\n+++ b\n@@ -1 +1 @@\n-Restart it.\n+Restart it twice.\n";
+
+        assert!(matches!(
+            patch_document(&workspace, &id, patch),
+            Ok(PatchOutcome::Updated(_))
+        ));
+        assert_eq!(
+            DocumentReader::info(&workspace, &id).unwrap().text,
+            "Restart it twice.\n"
+        );
+        assert!(matches!(
+            patch_document(&workspace, &id, patch),
+            Err(PatchError::DoesNotApply(_))
+        ));
+        assert_eq!(
+            DocumentReader::info(&workspace, &id).unwrap().text,
+            "Restart it twice.\n"
+        );
+    }
+
+    #[test]
+    fn patches_which_change_nothing_arent_pushed() {
+        let workspace = MemoryWorkspace::default();
+        let collection_id = workspace.add_collection("Engineering");
+        let id = workspace.add_to_collection(&collection_id, None, "Runbook", "Restart it.\n");
+
+        assert!(matches!(
+            patch_document(&workspace, &id, "--- a\n+++ b\n"),
+            Ok(PatchOutcome::Unchanged { title }) if title == "Runbook"
+        ));
+    }
+}
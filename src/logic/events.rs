@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Author, ConnectionError, Page};
+
+/// How many events to request at a time when reading back to a date.
+const SINCE_PAGE_SIZE: u32 = 100;
+
+/// Something which happened in the workspace, as recorded in its audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub id: String,
+    /// What happened, e.g. `documents.update` or `users.invite`.
+    pub name: String,
+    #[serde(default)]
+    pub actor_id: Option<String>,
+    #[serde(default)]
+    pub actor: Option<Author>,
+    #[serde(default)]
+    pub document_id: Option<String>,
+    #[serde(default)]
+    pub collection_id: Option<String>,
+    /// When it happened, as an ISO 8601 timestamp.
+    pub created_at: String,
+}
+
+/// Which events to list.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EventQuery<'a> {
+    /// Only lists events caused by this user.
+    pub actor_id: Option<&'a str>,
+    /// Only lists events for this document.
+    pub document_id: Option<&'a str>,
+}
+
+/// Something which can read the workspace's events, newest first.
+pub trait EventReader {
+    fn list(&self, query: &EventQuery, page: Page) -> Result<Vec<Event>, ConnectionError>;
+}
+
+/// Lists one page of the events matching `query`, newest first.
+pub fn list_events(
+    reader: &impl EventReader,
+    query: &EventQuery,
+    page: Page,
+) -> Result<Vec<Event>, ConnectionError> {
+    reader.list(query, page)
+}
+
+/// Lists every event matching `query` from `since` onwards, newest first. `since` is an ISO 8601
+/// date or timestamp in UTC, like `2024-05-01` or `2024-05-01T09:00:00Z`.
+///
+/// getOutline can't filter events by date, so this reads pages until it gets to older ones.
+pub fn events_since(
+    reader: &impl EventReader,
+    query: &EventQuery,
+    since: &str,
+) -> Result<Vec<Event>, ConnectionError> {
+    let mut events = Vec::new();
+    let mut page = Page {
+        offset: 0,
+        limit: SINCE_PAGE_SIZE,
+    };
+
+    loop {
+        let fetched = reader.list(query, page)?;
+        let count = fetched.len() as u32;
+        // Timestamps in the same format and time zone sort the same way as the times they're for
+        let newer = fetched
+            .into_iter()
+            .take_while(|event| event.created_at.as_str() >= since);
+        let before = events.len();
+        events.extend(newer);
+
+        let reached_older = events.len() - before < count as usize;
+        if reached_older || count < page.limit {
+            return Ok(events);
+        }
+        page.offset += count;
+    }
+}
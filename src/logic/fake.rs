@@ -0,0 +1,406 @@
+//! A workspace kept in memory, for testing `logic` without a getOutline server.
+
+use std::cell::{Cell, RefCell};
+
+use super::collections::{
+    Collection, CollectionReader, CollectionTreeReader, CollectionWriter, DocumentNode,
+    NewCollection,
+};
+use super::documents::{
+    Document, DocumentDeleter, DocumentReader, DocumentSummary, DocumentUpdate, DocumentUpdater,
+    DocumentWriter, NewDocument,
+};
+use super::memberships::Permission;
+use super::pins::{Pin, PinPage, PinReader, PinWriter};
+use super::revisions::{DocumentRestorer, Revision, RevisionReader};
+use super::stars::{Star, StarPage, StarReader, StarWriter};
+use super::{Author, ConnectionError, Page};
+
+/// Collections and documents, along with the documents' stars, pins, and revisions. Like
+/// getOutline, it accepts a document's full or short ID wherever it takes a document ID, but only
+/// ever hands out full ones.
+#[derive(Default)]
+pub(super) struct MemoryWorkspace {
+    collections: RefCell<Vec<Collection>>,
+    documents: RefCell<Vec<StoredDocument>>,
+    stars: RefCell<Vec<Star>>,
+    pins: RefCell<Vec<Pin>>,
+    revisions: RefCell<Vec<Revision>>,
+    next_id: Cell<u32>,
+    /// How many times a document has been looked up with `documents.info`.
+    info_calls: Cell<usize>,
+    /// A title which creating a document with fails, as if getOutline had gone down.
+    failing_title: RefCell<Option<String>>,
+}
+
+/// A document in a [MemoryWorkspace].
+#[derive(Debug, Clone)]
+pub(super) struct StoredDocument {
+    pub summary: DocumentSummary,
+    pub text: String,
+    pub collection_id: Option<String>,
+    pub parent_id: Option<String>,
+}
+
+impl MemoryWorkspace {
+    /// Adds a document called `title`, which isn't in any collection.
+    pub fn add_document(&self, title: &str) -> DocumentSummary {
+        self.store(title, "", None, None)
+    }
+
+    /// Adds a collection called `name`, returning its ID.
+    pub fn add_collection(&self, name: &str) -> String {
+        let collection = CollectionWriter::create(
+            self,
+            &NewCollection {
+                name,
+                description: None,
+                permission: None,
+            },
+        );
+        collection.expect("collections can always be added").id
+    }
+
+    /// Adds a document called `title` to collection `collection_id`, nested under `parent_id` if
+    /// it's given, returning its ID.
+    pub fn add_to_collection(
+        &self,
+        collection_id: &str,
+        parent_id: Option<&str>,
+        title: &str,
+        text: &str,
+    ) -> String {
+        self.store(title, text, Some(collection_id), parent_id).id
+    }
+
+    /// Makes creating a document called `title` fail until [MemoryWorkspace::stop_failing] is called.
+    pub fn fail_creating(&self, title: &str) {
+        *self.failing_title.borrow_mut() = Some(title.to_string());
+    }
+
+    /// Undoes [MemoryWorkspace::fail_creating].
+    pub fn stop_failing(&self) {
+        *self.failing_title.borrow_mut() = None;
+    }
+
+    /// Every document in collection `collection_id`, in the order they were created.
+    pub fn documents_in(&self, collection_id: &str) -> Vec<StoredDocument> {
+        self.documents
+            .borrow()
+            .iter()
+            .filter(|document| document.collection_id.as_deref() == Some(collection_id))
+            .cloned()
+            .collect()
+    }
+
+    /// How many collections there are.
+    pub fn collection_count(&self) -> usize {
+        self.collections.borrow().len()
+    }
+
+    /// Adds a revision of document `document_id`, returning its ID.
+    pub fn add_revision(&self, document_id: &str) -> String {
+        let id = self.new_id();
+        self.revisions.borrow_mut().push(Revision {
+            id: id.clone(),
+            document_id: document_id.to_string(),
+            created_at: "2024-01-01T00:00:00.000Z".to_string(),
+            created_by: Author {
+                name: "Ada".to_string(),
+            },
+            text: String::new(),
+        });
+        id
+    }
+
+    /// How many times a document has been looked up with `documents.info`.
+    pub fn info_calls(&self) -> usize {
+        self.info_calls.get()
+    }
+
+    fn new_id(&self) -> String {
+        let next = self.next_id.get() + 1;
+        self.next_id.set(next);
+        format!("00000000-0000-4000-8000-{next:012}")
+    }
+
+    fn store(
+        &self,
+        title: &str,
+        text: &str,
+        collection_id: Option<&str>,
+        parent_id: Option<&str>,
+    ) -> DocumentSummary {
+        let id = self.new_id();
+        let summary = DocumentSummary {
+            url_id: Some(short_id(&id)),
+            id,
+            title: title.to_string(),
+            url: None,
+        };
+        self.documents.borrow_mut().push(StoredDocument {
+            summary: summary.clone(),
+            text: text.to_string(),
+            collection_id: collection_id.map(str::to_string),
+            parent_id: parent_id.map(str::to_string),
+        });
+        summary
+    }
+
+    /// The document with the full or short ID `reference`.
+    fn document(&self, reference: &str) -> Result<DocumentSummary, ConnectionError> {
+        self.stored(reference).map(|document| document.summary)
+    }
+
+    fn stored(&self, reference: &str) -> Result<StoredDocument, ConnectionError> {
+        self.documents
+            .borrow()
+            .iter()
+            .find(|document| document.summary.is(reference))
+            .cloned()
+            .ok_or_else(|| not_found("such document"))
+    }
+
+    fn summaries(&self) -> Vec<DocumentSummary> {
+        self.documents
+            .borrow()
+            .iter()
+            .map(|document| document.summary.clone())
+            .collect()
+    }
+
+    fn tree(&self, collection_id: &str, parent_id: Option<&str>) -> Vec<DocumentNode> {
+        self.documents_in(collection_id)
+            .into_iter()
+            .filter(|document| document.parent_id.as_deref() == parent_id)
+            .map(|document| DocumentNode {
+                children: self.tree(collection_id, Some(&document.summary.id)),
+                id: document.summary.id,
+                title: document.summary.title,
+            })
+            .collect()
+    }
+}
+
+/// The short ID a document with the full ID `id` gets in its links.
+pub(super) fn short_id(id: &str) -> String {
+    let serial = id.rsplit('-').next().unwrap_or(id);
+    format!("short{}", serial.trim_start_matches('0'))
+}
+
+fn not_found(what: &str) -> ConnectionError {
+    ConnectionError::NotFound {
+        message: format!("there is no {what}"),
+    }
+}
+
+impl DocumentReader for MemoryWorkspace {
+    fn info(&self, id: &str) -> Result<Document, ConnectionError> {
+        self.info_calls.set(self.info_calls.get() + 1);
+        let document = self.stored(id)?;
+        Ok(Document {
+            id: document.summary.id,
+            title: document.summary.title,
+            text: document.text,
+            published_at: None,
+            url: None,
+            revision: None,
+        })
+    }
+}
+
+impl DocumentWriter for MemoryWorkspace {
+    fn create(&self, document: &NewDocument) -> Result<DocumentSummary, ConnectionError> {
+        if self.failing_title.borrow().as_deref() == Some(document.title) {
+            return Err(ConnectionError::Api {
+                status: 503,
+                message: "unavailable".to_string(),
+            });
+        }
+        CollectionReader::info(self, document.collection_id)?;
+        Ok(self.store(
+            document.title,
+            document.text,
+            Some(document.collection_id),
+            document.parent_document_id,
+        ))
+    }
+}
+
+impl DocumentUpdater for MemoryWorkspace {
+    fn update(&self, update: &DocumentUpdate) -> Result<DocumentSummary, ConnectionError> {
+        let mut documents = self.documents.borrow_mut();
+        let document = documents
+            .iter_mut()
+            .find(|document| document.summary.is(update.id))
+            .ok_or_else(|| not_found("such document"))?;
+        if update.append {
+            document.text.push_str(update.text);
+        } else {
+            document.text = update.text.to_string();
+        }
+        if let Some(title) = update.title {
+            document.summary.title = title.to_string();
+        }
+        Ok(document.summary.clone())
+    }
+}
+
+impl DocumentDeleter for MemoryWorkspace {
+    /// Removes the document either way, since there's no trash to look in.
+    fn delete(&self, id: &str, _: bool) -> Result<(), ConnectionError> {
+        let id = self.document(id)?.id;
+        self.documents
+            .borrow_mut()
+            .retain(|document| document.summary.id != id);
+        Ok(())
+    }
+}
+
+impl CollectionReader for MemoryWorkspace {
+    fn info(&self, id: &str) -> Result<Collection, ConnectionError> {
+        self.collections
+            .borrow()
+            .iter()
+            .find(|collection| collection.id == id)
+            .cloned()
+            .ok_or_else(|| not_found("such collection"))
+    }
+
+    /// Every collection, all on the first page.
+    fn list(&self, _: Page) -> Result<Vec<Collection>, ConnectionError> {
+        Ok(self.collections.borrow().clone())
+    }
+}
+
+impl CollectionTreeReader for MemoryWorkspace {
+    fn document_tree(&self, collection_id: &str) -> Result<Vec<DocumentNode>, ConnectionError> {
+        CollectionReader::info(self, collection_id)?;
+        Ok(self.tree(collection_id, None))
+    }
+}
+
+impl CollectionWriter for MemoryWorkspace {
+    fn create(&self, collection: &NewCollection) -> Result<Collection, ConnectionError> {
+        let created = Collection {
+            id: self.new_id(),
+            name: collection.name.to_string(),
+            description: collection.description.map(str::to_string),
+            permission: collection.permission.map(|permission| match permission {
+                Permission::Read => "read".to_string(),
+                Permission::ReadWrite => "read_write".to_string(),
+            }),
+            sharing: false,
+            created_at: None,
+            updated_at: None,
+        };
+        self.collections.borrow_mut().push(created.clone());
+        Ok(created)
+    }
+}
+
+impl StarReader for MemoryWorkspace {
+    /// Every star, all on the first page.
+    fn stars(&self, _: Page) -> Result<StarPage, ConnectionError> {
+        Ok(StarPage {
+            stars: self.stars.borrow().clone(),
+            documents: self.summaries(),
+        })
+    }
+}
+
+impl StarWriter for MemoryWorkspace {
+    fn star(&self, document_id: &str) -> Result<Star, ConnectionError> {
+        let star = Star {
+            id: self.new_id(),
+            document_id: Some(self.document(document_id)?.id),
+        };
+        self.stars.borrow_mut().push(star.clone());
+        Ok(star)
+    }
+
+    fn unstar(&self, star_id: &str) -> Result<(), ConnectionError> {
+        let mut stars = self.stars.borrow_mut();
+        let before = stars.len();
+        stars.retain(|star| star.id != star_id);
+        if stars.len() == before {
+            return Err(not_found("such star"));
+        }
+        Ok(())
+    }
+}
+
+impl PinReader for MemoryWorkspace {
+    /// Every pin in `collection_id`, all on the first page.
+    fn pins(&self, collection_id: Option<&str>, _: Page) -> Result<PinPage, ConnectionError> {
+        Ok(PinPage {
+            pins: self
+                .pins
+                .borrow()
+                .iter()
+                .filter(|pin| pin.collection_id.as_deref() == collection_id)
+                .cloned()
+                .collect(),
+            documents: self.summaries(),
+        })
+    }
+}
+
+impl PinWriter for MemoryWorkspace {
+    fn pin(&self, document_id: &str, collection_id: Option<&str>) -> Result<Pin, ConnectionError> {
+        let pin = Pin {
+            id: self.new_id(),
+            document_id: self.document(document_id)?.id,
+            collection_id: collection_id.map(str::to_string),
+        };
+        self.pins.borrow_mut().push(pin.clone());
+        Ok(pin)
+    }
+
+    fn unpin(&self, pin_id: &str) -> Result<(), ConnectionError> {
+        let mut pins = self.pins.borrow_mut();
+        let before = pins.len();
+        pins.retain(|pin| pin.id != pin_id);
+        if pins.len() == before {
+            return Err(not_found("such pin"));
+        }
+        Ok(())
+    }
+}
+
+impl RevisionReader for MemoryWorkspace {
+    fn revision(&self, id: &str) -> Result<Revision, ConnectionError> {
+        self.revisions
+            .borrow()
+            .iter()
+            .find(|revision| revision.id == id)
+            .cloned()
+            .ok_or_else(|| not_found("such revision"))
+    }
+
+    /// Every revision of document `document_id`, all on the first page.
+    fn revisions(&self, document_id: &str, _: Page) -> Result<Vec<Revision>, ConnectionError> {
+        let id = self.document(document_id)?.id;
+        Ok(self
+            .revisions
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|revision| revision.document_id == id)
+            .cloned()
+            .collect())
+    }
+}
+
+impl DocumentRestorer for MemoryWorkspace {
+    /// Only checks the document and revision exist, since documents have no content to restore.
+    fn restore(
+        &self,
+        document_id: &str,
+        revision_id: &str,
+    ) -> Result<DocumentSummary, ConnectionError> {
+        let document = self.document(document_id)?;
+        self.revision(revision_id)?;
+        Ok(document)
+    }
+}
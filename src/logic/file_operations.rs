@@ -0,0 +1,172 @@
+//! Background jobs getOutline runs for long operations like exports, which finish some time after
+//! the request which started them.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Author, ConnectionError, Page};
+
+/// How long to wait between checks on a running job.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long to wait for a job before giving up on it.
+const MAX_WAIT: Duration = Duration::from_secs(30 * 60);
+
+/// A background job, like an export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperation {
+    pub id: String,
+    pub state: FileOperationState,
+    /// Why the job failed, if it did.
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(rename = "type", default)]
+    pub kind: Option<FileOperationKind>,
+    /// The name of the file the job produces or reads.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The format of the file, like `outline-markdown`.
+    #[serde(default)]
+    pub format: Option<String>,
+    /// The collection the job is for, or nothing if it covers the whole workspace.
+    #[serde(default)]
+    pub collection_id: Option<String>,
+    /// Who started the job.
+    #[serde(default)]
+    pub user: Option<Author>,
+    /// When the job was started, as an ISO 8601 timestamp.
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// Whether a background job exports content from the workspace or imports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileOperationKind {
+    Export,
+    Import,
+}
+
+impl FileOperationKind {
+    pub fn description(self) -> &'static str {
+        match self {
+            FileOperationKind::Export => "export",
+            FileOperationKind::Import => "import",
+        }
+    }
+}
+
+/// How far along a background job is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileOperationState {
+    Creating,
+    Uploading,
+    Complete,
+    Error,
+    /// The job finished, but its file has since been cleaned up.
+    Expired,
+}
+
+impl FileOperationState {
+    pub fn description(self) -> &'static str {
+        match self {
+            FileOperationState::Creating => "creating",
+            FileOperationState::Uploading => "uploading",
+            FileOperationState::Complete => "complete",
+            FileOperationState::Error => "failed",
+            FileOperationState::Expired => "expired",
+        }
+    }
+}
+
+/// Something which can check on background jobs and fetch the files they produce.
+pub trait FileOperationReader {
+    fn list(
+        &self,
+        kind: FileOperationKind,
+        page: Page,
+    ) -> Result<Vec<FileOperation>, ConnectionError>;
+    fn info(&self, id: &str) -> Result<FileOperation, ConnectionError>;
+    fn download(&self, id: &str) -> Result<Vec<u8>, ConnectionError>;
+}
+
+/// Something which can delete background jobs, along with the files they produced.
+pub trait FileOperationDeleter {
+    fn delete(&self, id: &str) -> Result<(), ConnectionError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FileOperationError {
+    #[error("getOutline couldn't finish the job: {}", message.as_deref().unwrap_or("no reason given"))]
+    Failed { message: Option<String> },
+    #[error("the job's file has expired and can't be downloaded any more")]
+    Expired,
+    #[error("gave up waiting for the job after {} minutes", MAX_WAIT.as_secs() / 60)]
+    TimedOut,
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+}
+
+/// Lists one page of the workspace's background jobs of one `kind`, newest first.
+pub fn list_file_operations(
+    reader: &impl FileOperationReader,
+    kind: FileOperationKind,
+    page: Page,
+) -> Result<Vec<FileOperation>, ConnectionError> {
+    reader.list(kind, page)
+}
+
+/// Looks up background job `id`.
+pub fn file_operation_info(
+    reader: &impl FileOperationReader,
+    id: &str,
+) -> Result<FileOperation, ConnectionError> {
+    reader.info(id)
+}
+
+/// Deletes background job `id` and the file it produced, which can't be downloaded afterwards.
+pub fn delete_file_operation(
+    deleter: &impl FileOperationDeleter,
+    id: &str,
+) -> Result<(), ConnectionError> {
+    deleter.delete(id)
+}
+
+/// Waits for the job `operation` to finish and downloads the file it produced. `on_state` is
+/// called whenever the job moves on to a new state.
+pub fn wait_and_download(
+    reader: &impl FileOperationReader,
+    mut operation: FileOperation,
+    mut on_state: impl FnMut(FileOperationState),
+) -> Result<Vec<u8>, FileOperationError> {
+    let started = Instant::now();
+    on_state(operation.state);
+
+    loop {
+        match operation.state {
+            FileOperationState::Complete => return Ok(reader.download(&operation.id)?),
+            FileOperationState::Error => {
+                return Err(FileOperationError::Failed {
+                    message: operation.error,
+                })
+            }
+            FileOperationState::Expired => return Err(FileOperationError::Expired),
+            FileOperationState::Creating | FileOperationState::Uploading => {}
+        }
+
+        if started.elapsed() >= MAX_WAIT {
+            return Err(FileOperationError::TimedOut);
+        }
+        thread::sleep(POLL_INTERVAL);
+
+        let previous = operation.state;
+        operation = reader.info(&operation.id)?;
+        if operation.state != previous {
+            on_state(operation.state);
+        }
+    }
+}
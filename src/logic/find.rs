@@ -0,0 +1,64 @@
+use regex::Regex;
+use serde::Serialize;
+
+/// A line of a document printed by a search within it: either one which matched, or one shown
+/// around a match for context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundLine<'a> {
+    /// The line's number, starting from 1.
+    pub number: usize,
+    pub text: &'a str,
+    pub is_match: bool,
+}
+
+/// Finds the lines of `text` which `pattern` matches, along with up to `before` and `after` lines
+/// around each one, the way `grep -B` and `-A` do.
+///
+/// Matches whose context overlaps or touches are merged, so each group is a run of consecutive
+/// lines, and groups come back in the order they appear.
+pub fn find_lines<'a>(
+    text: &'a str,
+    pattern: &Regex,
+    before: usize,
+    after: usize,
+) -> Vec<Vec<FoundLine<'a>>> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut groups: Vec<Vec<FoundLine>> = Vec::new();
+    // Lines up to (but not including) this index have already been added to the last group
+    let mut shown_until = 0;
+
+    for (index, line) in lines.iter().enumerate() {
+        if !pattern.is_match(line) {
+            continue;
+        }
+
+        let start = index.saturating_sub(before);
+        let end = (index + after + 1).min(lines.len());
+        if groups.is_empty() || start > shown_until {
+            groups.push(Vec::new());
+        }
+        let group = groups
+            .last_mut()
+            .expect("there is a group for the first match onwards");
+
+        // A line already shown as context for an earlier match may match too
+        if let Some(shown) = group.iter_mut().find(|line| line.number == index + 1) {
+            shown.is_match = true;
+        }
+        let from = start.max(shown_until);
+        group.extend(
+            lines[from..end]
+                .iter()
+                .enumerate()
+                .map(|(offset, text)| FoundLine {
+                    number: from + offset + 1,
+                    text,
+                    is_match: from + offset == index,
+                }),
+        );
+        shown_until = shown_until.max(end);
+    }
+
+    groups
+}
@@ -0,0 +1,147 @@
+//! Fuzzy matching, for picking something from a list by typing a few of the letters in it.
+//!
+//! A query matches text when the text has the query's characters in the same order, ignoring
+//! case, though not necessarily next to each other. Words in the query separated by spaces can
+//! match in any order.
+
+/// Points for each character of the query matched.
+const SCORE_MATCH: i32 = 16;
+/// Extra points for matching the first character of a word.
+const BONUS_WORD_START: i32 = 8;
+/// Extra points for matching the character right after the previous match.
+const BONUS_CONSECUTIVE: i32 = 6;
+/// Points taken off for a gap between matched characters, plus [PENALTY_GAP_EXTENSION] for each
+/// character of it after the first.
+const PENALTY_GAP_START: i32 = 3;
+const PENALTY_GAP_EXTENSION: i32 = 1;
+
+/// How well a query matched some text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better.
+    pub score: i32,
+    /// Which characters of the text were matched (counting characters, not bytes), in order.
+    pub positions: Vec<usize>,
+}
+
+/// How well `query` matches `text`, or `None` if it doesn't. An empty query matches anything.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let text: Vec<char> = text.chars().collect();
+    let mut found = FuzzyMatch {
+        score: 0,
+        positions: Vec::new(),
+    };
+    for word in query.split_whitespace() {
+        let word: Vec<char> = word.chars().map(fold).collect();
+        let positions = match_word(&word, &text)?;
+        found.score += score(&positions, &text);
+        found.positions.extend(positions);
+    }
+    found.positions.sort_unstable();
+    found.positions.dedup();
+
+    Some(found)
+}
+
+/// The indices of the `candidates` which `query` matches, best match first, along with how well
+/// each matched. Matches which score the same keep their order, shorter ones first.
+pub fn fuzzy_rank<'a>(
+    query: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<(usize, FuzzyMatch)> {
+    let mut ranked: Vec<_> = candidates
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, text)| {
+            let found = fuzzy_match(query, text)?;
+            Some((index, text.chars().count(), found))
+        })
+        .collect();
+    if !query.trim().is_empty() {
+        ranked.sort_by(|(a, a_len, a_match), (b, b_len, b_match)| {
+            b_match
+                .score
+                .cmp(&a_match.score)
+                .then(a_len.cmp(b_len))
+                .then(a.cmp(b))
+        });
+    }
+
+    ranked
+        .into_iter()
+        .map(|(index, _, found)| (index, found))
+        .collect()
+}
+
+/// Where the characters of `word` (already [fold]ed) are found in `text`.
+///
+/// This finds the first place `word` ends, then works backwards from there to the latest start,
+/// so that the match is as tight as it can be around that end.
+fn match_word(word: &[char], text: &[char]) -> Option<Vec<usize>> {
+    let mut next = 0;
+    let mut end = None;
+    for (index, &c) in text.iter().enumerate() {
+        if fold(c) == word[next] {
+            next += 1;
+            if next == word.len() {
+                end = Some(index);
+                break;
+            }
+        }
+    }
+
+    let end = end?;
+    let mut positions = Vec::with_capacity(word.len());
+    let mut remaining = word.iter().rev().peekable();
+    for index in (0..=end).rev() {
+        match remaining.peek() {
+            Some(&&c) if fold(text[index]) == c => {
+                positions.push(index);
+                remaining.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    positions.reverse();
+    Some(positions)
+}
+
+/// The score for matching the characters of `text` at `positions`.
+fn score(positions: &[usize], text: &[char]) -> i32 {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    for &position in positions {
+        score += SCORE_MATCH;
+        if is_word_start(text, position) {
+            score += BONUS_WORD_START;
+        }
+        match previous {
+            Some(previous) if position == previous + 1 => score += BONUS_CONSECUTIVE,
+            Some(previous) => {
+                let gap = i32::try_from(position - previous - 1).unwrap_or(i32::MAX / 2);
+                score -= PENALTY_GAP_START + (gap - 1) * PENALTY_GAP_EXTENSION;
+            }
+            None => {}
+        }
+        previous = Some(position);
+    }
+
+    score
+}
+
+/// Whether the character at `index` starts a word: it comes first, after something which isn't
+/// a letter or digit, or is a capital after a lowercase letter, like the `C` in `getOutlineCLI`.
+fn is_word_start(text: &[char], index: usize) -> bool {
+    let Some(before) = index.checked_sub(1).map(|before| text[before]) else {
+        return true;
+    };
+    let c = text[index];
+    (!before.is_alphanumeric() && c.is_alphanumeric())
+        || (before.is_lowercase() && c.is_uppercase())
+}
+
+/// `c` as it's compared, ignoring case.
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
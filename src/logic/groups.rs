@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use super::users::User;
+use super::{fetch_all, ConnectionError, Page};
+
+/// A named set of users, which collections and documents can be shared with all at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub member_count: u32,
+    /// When the group was created, as an ISO 8601 timestamp.
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// Something which can look up the groups in the workspace and who is in them.
+pub trait GroupReader {
+    fn list(&self, page: Page) -> Result<Vec<Group>, ConnectionError>;
+    fn info(&self, id: &str) -> Result<Group, ConnectionError>;
+    fn members(&self, group_id: &str, page: Page) -> Result<Vec<User>, ConnectionError>;
+}
+
+/// Lists one page of the groups in the workspace.
+pub fn list_groups(reader: &impl GroupReader, page: Page) -> Result<Vec<Group>, ConnectionError> {
+    reader.list(page)
+}
+
+/// Looks up group `id`.
+pub fn group_info(reader: &impl GroupReader, id: &str) -> Result<Group, ConnectionError> {
+    reader.info(id)
+}
+
+/// Lists everyone in group `group_id`, across every page of members.
+pub fn group_members(
+    reader: &impl GroupReader,
+    group_id: &str,
+) -> Result<Vec<User>, ConnectionError> {
+    fetch_all(|page| reader.members(group_id, page))
+}
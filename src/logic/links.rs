@@ -0,0 +1,411 @@
+use std::ops::Range;
+
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag};
+use serde::Serialize;
+
+use super::collections::CollectionReader;
+use super::documents::{
+    search_workspace, DocumentReader, DocumentSummary, DocumentUpdate, DocumentUpdater,
+    SearchQuery, SearchReader,
+};
+use super::ConnectionError;
+
+/// A single link destination which was changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RewrittenLink {
+    pub old: String,
+    pub new: String,
+}
+
+/// A link rewrite which would change one document.
+#[derive(Debug)]
+pub struct LinkRewrite {
+    pub document: DocumentSummary,
+    pub rewritten: String,
+    pub links: Vec<RewrittenLink>,
+    /// The document's revision when it was read, so the rewrite isn't pushed over later edits.
+    pub revision: Option<u64>,
+}
+
+/// Finds the documents which mention the host `from` at all, which are the only ones
+/// [plan_link_rewrite] could change.
+pub fn documents_mentioning<C>(
+    client: &C,
+    from: &str,
+    collection_id: Option<&str>,
+) -> Result<Vec<DocumentSummary>, ConnectionError>
+where
+    C: SearchReader + CollectionReader + Sync,
+{
+    search_workspace(
+        client,
+        &SearchQuery {
+            text: from,
+            collection_id,
+        },
+    )
+}
+
+/// Works out how the links in one document would look pointing at the host `to` instead of `from`,
+/// or returns `None` if none of them point at `from`.
+pub fn plan_link_rewrite(
+    reader: &impl DocumentReader,
+    summary: DocumentSummary,
+    from: &str,
+    to: &str,
+) -> Result<Option<LinkRewrite>, ConnectionError> {
+    let document = reader.info(&summary.id)?;
+    let (rewritten, links) = rewrite_links(&document.text, from, to);
+    if links.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(LinkRewrite {
+        document: summary,
+        rewritten,
+        links,
+        revision: document.revision,
+    }))
+}
+
+/// Pushes a planned link rewrite to its document.
+pub fn apply_link_rewrite(
+    updater: &impl DocumentUpdater,
+    rewrite: &LinkRewrite,
+) -> Result<DocumentSummary, ConnectionError> {
+    updater.update(&DocumentUpdate {
+        id: &rewrite.document.id,
+        text: &rewrite.rewritten,
+        title: None,
+        append: false,
+        expected_revision: rewrite.revision,
+    })
+}
+
+/// Points every link and image in `markdown` whose destination is on the host `from` at the host
+/// `to` instead, leaving the rest of the destination (and the rest of the document) untouched.
+///
+/// Only real link destinations are changed: mentions of `from` in plain text, link text, or code
+/// are left alone.
+pub fn rewrite_links(markdown: &str, from: &str, to: &str) -> (String, Vec<RewrittenLink>) {
+    rewrite_destinations(markdown, |dest| rewrite_destination(dest, from, to))
+}
+
+/// Replaces the destination of every link and image in `markdown` (including reference-style
+/// definitions) for which `rewrite` returns a new one, leaving the rest of the document untouched.
+pub fn rewrite_destinations(
+    markdown: &str,
+    rewrite: impl FnMut(&str) -> Option<String>,
+) -> (String, Vec<RewrittenLink>) {
+    let mut edits = Vec::new();
+    let mut events = Parser::new_ext(markdown, Options::all()).into_offset_iter();
+
+    for (event, range) in events.by_ref() {
+        let (link_type, dest, title) = match event {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                ..
+            }) => (link_type, dest_url, title),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                ..
+            }) => (link_type, dest_url, title),
+            _ => continue,
+        };
+        // Reference-style links get their destination from a definition elsewhere in the
+        // document, which is rewritten below
+        if !matches!(link_type, LinkType::Inline | LinkType::Autolink) {
+            continue;
+        }
+
+        let source = &markdown[range.clone()];
+        let found = if link_type == LinkType::Autolink {
+            // Autolinks are written out as they are, between `<` and `>`
+            source.rfind(&*dest).map(|start| start..start + dest.len())
+        } else {
+            // Skip past the title so a URL repeated in it doesn't get mistaken for the destination
+            let searchable = if title.is_empty() {
+                source
+            } else {
+                source.rfind(&*title).map_or(source, |end| &source[..end])
+            };
+            // The last `](` is the one after the link text, which can have images of its own
+            searchable
+                .match_indices("](")
+                .filter_map(|(start, _)| destination_at(source, start + 2, &dest))
+                .last()
+        };
+        if let Some(found) = found {
+            edits.push(Destination {
+                range: range.start + found.start..range.start + found.end,
+                dest: dest.into_string(),
+            });
+        }
+    }
+
+    for (_, definition) in events.reference_definitions().iter() {
+        let source = &markdown[definition.span.clone()];
+        let after_label = source.find("]:").map_or(0, |end| end + 2);
+        if let Some(found) = destination_at(source, after_label, &definition.dest) {
+            let start = definition.span.start;
+            edits.push(Destination {
+                range: start + found.start..start + found.end,
+                dest: definition.dest.to_string(),
+            });
+        }
+    }
+
+    apply_edits(markdown, edits, rewrite)
+}
+
+/// A link destination in a document.
+struct Destination {
+    /// Where it's written in the document, which can spell it with backslash escapes or entities.
+    range: Range<usize>,
+    /// What it reads as, with those decoded.
+    dest: String,
+}
+
+/// Finds the destination written at `start` in `source` (after any whitespace), as long as it reads
+/// as `dest`, returning where it's written.
+fn destination_at(source: &str, start: usize, dest: &str) -> Option<Range<usize>> {
+    let text = &source[start..];
+    let start = start + (text.len() - text.trim_start().len());
+    let text = &source[start..];
+
+    let range = if let Some(inner) = text.strip_prefix('<') {
+        let mut escaped = false;
+        let end = inner.find(|c| {
+            let end = !escaped && matches!(c, '>' | '\n');
+            escaped = !escaped && c == '\\';
+            end
+        })?;
+        start + 1..start + 1 + end
+    } else {
+        let mut depth = 0usize;
+        let mut escaped = false;
+        let end = text
+            .find(|c: char| {
+                if escaped {
+                    escaped = false;
+                    return false;
+                }
+                match c {
+                    '\\' => escaped = true,
+                    '(' => depth += 1,
+                    ')' if depth == 0 => return true,
+                    ')' => depth -= 1,
+                    c => return c.is_whitespace() || c.is_control(),
+                }
+                false
+            })
+            .unwrap_or(text.len());
+        start..start + end
+    };
+
+    let written = &source[range.clone()];
+    (written == dest || decode(written).0 == dest).then_some(range)
+}
+
+/// Decodes the backslash escapes and entities in `written`, a link destination as it's written in
+/// a document. Along with the decoded text comes where each piece of it starts in both the decoded
+/// text and `written`, in order.
+fn decode(written: &str) -> (String, Vec<(usize, usize)>) {
+    let mut decoded = String::with_capacity(written.len());
+    let mut pieces = Vec::new();
+    let mut rest = written;
+    while let Some(c) = rest.chars().next() {
+        pieces.push((decoded.len(), written.len() - rest.len()));
+        let escaped = rest[c.len_utf8()..]
+            .chars()
+            .next()
+            .filter(|next| c == '\\' && next.is_ascii_punctuation());
+        if let Some(next) = escaped {
+            decoded.push(next);
+            rest = &rest[2..];
+            continue;
+        }
+        // Entities are decoded by parsing them on their own, which leaves anything else as it is
+        let entity = rest
+            .find(';')
+            .filter(|&end| c == '&' && end <= 32)
+            .map(|end| &rest[..=end])
+            .and_then(|entity| {
+                let mut text = String::new();
+                for event in Parser::new(entity) {
+                    if let Event::Text(piece) = event {
+                        text.push_str(&piece);
+                    }
+                }
+                (text != entity).then_some((entity.len(), text))
+            });
+        match entity {
+            Some((length, text)) => {
+                decoded.push_str(&text);
+                rest = &rest[length..];
+            }
+            None => {
+                decoded.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    (decoded, pieces)
+}
+
+/// Replaces each of the `edits` destinations for which `rewrite` returns a new one.
+fn apply_edits(
+    text: &str,
+    mut edits: Vec<Destination>,
+    mut rewrite: impl FnMut(&str) -> Option<String>,
+) -> (String, Vec<RewrittenLink>) {
+    edits.sort_by_key(|edit| edit.range.start);
+    edits.dedup_by_key(|edit| edit.range.clone());
+
+    let mut result = String::with_capacity(text.len());
+    let mut rewritten = Vec::new();
+    let mut copied_up_to = 0;
+    for edit in edits {
+        if edit.range.start < copied_up_to {
+            continue;
+        }
+
+        if let Some(new) = rewrite(&edit.dest) {
+            result.push_str(&text[copied_up_to..edit.range.start]);
+            result.push_str(&respell(&text[edit.range.clone()], &edit.dest, &new));
+            copied_up_to = edit.range.end;
+            rewritten.push(RewrittenLink {
+                old: edit.dest,
+                new,
+            });
+        }
+    }
+    result.push_str(&text[copied_up_to..]);
+
+    (result, rewritten)
+}
+
+/// `new`, the rewritten version of the destination `dest`, spelled the way `written` spells
+/// `dest` wherever the two are the same at the end. Rewriting the host of
+/// `https://old.example/a\_b` then gives `https://new.example/a\_b`, which keeps the escape meaning
+/// what it did.
+fn respell(written: &str, dest: &str, new: &str) -> String {
+    if written == dest {
+        return new.to_string();
+    }
+
+    let same = dest
+        .bytes()
+        .rev()
+        .zip(new.bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (_, pieces) = decode(written);
+    let (kept_from, written_from) = pieces
+        .into_iter()
+        .find(|&(start, _)| start >= dest.len() - same)
+        .unwrap_or((dest.len(), written.len()));
+
+    format!(
+        "{}{}",
+        &new[..new.len() - (dest.len() - kept_from)],
+        &written[written_from..]
+    )
+}
+
+/// Swaps the host of the absolute URL `dest` from `from` to `to`, or returns `None` if it's on some
+/// other host (or isn't an absolute URL at all).
+fn rewrite_destination(dest: &str, from: &str, to: &str) -> Option<String> {
+    let (scheme, rest) = dest.split_once("://")?;
+    if scheme.is_empty()
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    {
+        return None;
+    }
+
+    let host_end = rest.find(['/', ':', '?', '#']).unwrap_or(rest.len());
+    if !rest[..host_end].eq_ignore_ascii_case(from) {
+        return None;
+    }
+
+    Some(format!("{scheme}://{to}{}", &rest[host_end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite(markdown: &str) -> (String, Vec<RewrittenLink>) {
+        rewrite_links(markdown, "old.example", "new.example")
+    }
+
+    #[test]
+    fn escaped_destinations_are_rewritten_and_keep_their_escapes() {
+        let (rewritten, links) =
+            rewrite("[a](https://old.example/a\\_b?x=1&amp;y=2) [b](<https://old.example/c d>)");
+
+        assert_eq!(
+            rewritten,
+            "[a](https://new.example/a\\_b?x=1&amp;y=2) [b](<https://new.example/c d>)"
+        );
+        assert_eq!(
+            links,
+            [
+                RewrittenLink {
+                    old: "https://old.example/a_b?x=1&y=2".to_string(),
+                    new: "https://new.example/a_b?x=1&y=2".to_string(),
+                },
+                RewrittenLink {
+                    old: "https://old.example/c d".to_string(),
+                    new: "https://new.example/c d".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn hosts_spelled_with_entities_are_rewritten() {
+        let (rewritten, links) = rewrite("[a](https://old&#46;example/docs)");
+
+        // Only the part which changed is spelled differently
+        assert_eq!(rewritten, "[a](https://new&#46;example/docs)");
+        assert_eq!(links[0].new, "https://new.example/docs");
+    }
+
+    #[test]
+    fn links_around_images_have_their_own_destination_rewritten() {
+        let markdown = "[![logo](https://old.example/logo.png)](https://old.example/home \"Home\")";
+        let (rewritten, links) = rewrite(markdown);
+
+        assert_eq!(
+            rewritten,
+            "[![logo](https://new.example/logo.png)](https://new.example/home \"Home\")"
+        );
+        assert_eq!(links.len(), 2);
+    }
+
+    #[test]
+    fn reference_definitions_and_autolinks_are_rewritten() {
+        let (rewritten, links) =
+            rewrite("[docs][d] <https://old.example/x>\n\n[d]: https://old.example/a\\(1\\)\n");
+
+        assert_eq!(
+            rewritten,
+            "[docs][d] <https://new.example/x>\n\n[d]: https://new.example/a\\(1\\)\n"
+        );
+        assert_eq!(links.len(), 2);
+    }
+
+    #[test]
+    fn mentions_outside_destinations_are_left_alone() {
+        let markdown = "old.example in `https://old.example` and [https://old.example](/local)";
+        assert_eq!(rewrite(markdown), (markdown.to_string(), Vec::new()));
+    }
+}
@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+
+use super::{fetch_all, ConnectionError, Page};
+
+/// A member of the workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    pub name: String,
+    /// Only visible to admins, or for users who share their email with the workspace.
+    pub email: Option<String>,
+}
+
+/// A user's direct access to a document, on top of whatever their collection membership gives.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Membership {
+    pub user_id: String,
+    /// The access level, e.g. `read` or `read_write`.
+    pub permission: String,
+}
+
+/// One page of a document's memberships, along with the users they refer to.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MembershipPage {
+    pub users: Vec<User>,
+    pub memberships: Vec<Membership>,
+}
+
+/// A user who has been given access to a document, and what they can do with it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentAccess {
+    pub user: User,
+    pub permission: String,
+}
+
+/// The access level a document can be shared with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    Read,
+    ReadWrite,
+}
+
+/// The access level a user or group can be given to a collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionPermission {
+    Read,
+    ReadWrite,
+    /// Edit the collection's documents and manage the collection itself, including who it's
+    /// shared with.
+    Admin,
+}
+
+/// Something which can list who a document has been shared with.
+pub trait MembershipReader {
+    fn memberships(&self, document_id: &str, page: Page)
+        -> Result<MembershipPage, ConnectionError>;
+}
+
+/// Something which can give users access to a document, or take it away.
+pub trait MembershipWriter {
+    fn add_user(
+        &self,
+        document_id: &str,
+        user_id: &str,
+        permission: Permission,
+    ) -> Result<(), ConnectionError>;
+    fn remove_user(&self, document_id: &str, user_id: &str) -> Result<(), ConnectionError>;
+}
+
+/// Lists every user document `document_id` has been shared with directly, with their permission.
+pub fn document_access(
+    reader: &impl MembershipReader,
+    document_id: &str,
+) -> Result<Vec<DocumentAccess>, ConnectionError> {
+    let mut users = Vec::new();
+    let memberships = fetch_all(|page| {
+        let page = reader.memberships(document_id, page)?;
+        users.extend(page.users);
+        Ok(page.memberships)
+    })?;
+
+    Ok(memberships
+        .into_iter()
+        .filter_map(|membership| {
+            let user = users.iter().find(|user| user.id == membership.user_id)?;
+            Some(DocumentAccess {
+                user: user.clone(),
+                permission: membership.permission,
+            })
+        })
+        .collect())
+}
+
+/// Shares document `document_id` with user `user_id`, replacing any access they had before.
+pub fn share_document(
+    writer: &impl MembershipWriter,
+    document_id: &str,
+    user_id: &str,
+    permission: Permission,
+) -> Result<(), ConnectionError> {
+    writer.add_user(document_id, user_id, permission)
+}
+
+/// Takes away the access user `user_id` was given to document `document_id`. Access they have
+/// through the document's collection isn't affected.
+pub fn revoke_access(
+    writer: &impl MembershipWriter,
+    document_id: &str,
+    user_id: &str,
+) -> Result<(), ConnectionError> {
+    writer.remove_user(document_id, user_id)
+}
+
+/// Something which can give users and groups access to a collection, or take it away.
+pub trait CollectionMembershipWriter {
+    fn add_user(
+        &self,
+        collection_id: &str,
+        user_id: &str,
+        permission: CollectionPermission,
+    ) -> Result<(), ConnectionError>;
+    fn remove_user(&self, collection_id: &str, user_id: &str) -> Result<(), ConnectionError>;
+    fn add_group(
+        &self,
+        collection_id: &str,
+        group_id: &str,
+        permission: CollectionPermission,
+    ) -> Result<(), ConnectionError>;
+    fn remove_group(&self, collection_id: &str, group_id: &str) -> Result<(), ConnectionError>;
+}
+
+/// Makes user `user_id` a member of collection `collection_id`, replacing any access they had
+/// to it before.
+pub fn add_collection_user(
+    writer: &impl CollectionMembershipWriter,
+    collection_id: &str,
+    user_id: &str,
+    permission: CollectionPermission,
+) -> Result<(), ConnectionError> {
+    writer.add_user(collection_id, user_id, permission)
+}
+
+/// Removes user `user_id` from collection `collection_id`. They keep any access they have
+/// through a group, or which was given to them on individual documents.
+pub fn remove_collection_user(
+    writer: &impl CollectionMembershipWriter,
+    collection_id: &str,
+    user_id: &str,
+) -> Result<(), ConnectionError> {
+    writer.remove_user(collection_id, user_id)
+}
+
+/// Gives every member of group `group_id` access to collection `collection_id`, replacing any
+/// access the group had to it before.
+pub fn add_collection_group(
+    writer: &impl CollectionMembershipWriter,
+    collection_id: &str,
+    group_id: &str,
+    permission: CollectionPermission,
+) -> Result<(), ConnectionError> {
+    writer.add_group(collection_id, group_id, permission)
+}
+
+/// Takes away the access group `group_id` was given to collection `collection_id`.
+pub fn remove_collection_group(
+    writer: &impl CollectionMembershipWriter,
+    collection_id: &str,
+    group_id: &str,
+) -> Result<(), ConnectionError> {
+    writer.remove_group(collection_id, group_id)
+}
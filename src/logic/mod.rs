@@ -0,0 +1,105 @@
+//! Business logic for the CLI.
+//!
+//! Nothing in here talks to the network directly. Each submodule declares the traits it needs from
+//! the outside world, and `getoutline_connection` provides the real implementations.
+
+pub mod api_keys;
+pub mod attachments;
+pub mod bookmarks;
+pub mod budget;
+pub mod cache;
+pub mod catalog;
+pub mod collections;
+pub mod comments;
+pub mod completion;
+pub mod csv;
+pub mod digest;
+pub mod documents;
+pub mod events;
+#[cfg(test)]
+mod fake;
+pub mod file_operations;
+pub mod find;
+pub mod fuzzy;
+pub mod groups;
+pub mod links;
+pub mod memberships;
+pub mod parsers;
+pub mod pins;
+pub mod policy;
+pub mod progress;
+pub mod recent;
+pub mod replace;
+pub mod revisions;
+pub mod secrets;
+pub mod sections;
+pub mod shares;
+pub mod stars;
+pub mod suggestions;
+pub mod time;
+pub mod timings;
+pub mod users;
+pub mod views;
+pub mod workspace;
+pub mod wrap;
+
+use serde::{Deserialize, Serialize};
+
+/// Which slice of a paginated listing to fetch.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Page {
+    pub offset: u32,
+    pub limit: u32,
+}
+
+/// How many items to request at a time when reading every page of a listing.
+const FETCH_ALL_PAGE_SIZE: u32 = 100;
+
+/// Reads every page of a paginated listing, calling `fetch_page` until it returns a short page.
+fn fetch_all<T>(
+    mut fetch_page: impl FnMut(Page) -> Result<Vec<T>, ConnectionError>,
+) -> Result<Vec<T>, ConnectionError> {
+    let mut items = Vec::new();
+    let mut page = Page {
+        offset: 0,
+        limit: FETCH_ALL_PAGE_SIZE,
+    };
+
+    loop {
+        let fetched = fetch_page(page)?;
+        let count = fetched.len() as u32;
+        items.extend(fetched);
+
+        if count < page.limit {
+            return Ok(items);
+        }
+        page.offset += count;
+    }
+}
+
+/// The user responsible for a change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Author {
+    pub name: String,
+}
+
+/// A failure reported by an adapter while talking to getOutline.
+#[derive(Debug, thiserror::Error)]
+pub enum ConnectionError {
+    #[error("getOutline rejected the API key (HTTP {status})")]
+    Unauthorized { status: u16 },
+    #[error("not found: {message}")]
+    NotFound { message: String },
+    #[error("getOutline returned HTTP {status}: {message}")]
+    Api { status: u16, message: String },
+    #[error("could not communicate with getOutline")]
+    Transport(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("stopped before sending another request")]
+    OverBudget(#[from] budget::BudgetExhausted),
+    /// An update was refused because the document was saved again after it was read.
+    #[error(
+        "document {id} has changed since revision {expected} was read, so the update was refused \
+         instead of overwriting those changes; look at the latest version and try again"
+    )]
+    RemoteChanged { id: String, expected: u64 },
+}
@@ -0,0 +1,133 @@
+//! Small pure functions which interpret text from outside the CLI: document content, titles, and
+//! whatever was typed on the command line. They never panic and never touch the network or the
+//! file system, which keeps them easy to test with arbitrary input.
+
+pub use super::links::rewrite_links;
+pub use super::sections::{section_containing, Section};
+pub use super::shares::share_id_from_url;
+pub use super::wrap::{fit_lines, rewrap, Wrap};
+
+/// Turns a document title into a name which is safe to use for a file on any platform.
+pub fn file_name_for(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    // Windows won't allow names ending in dots or spaces, and a leading dot would hide the file
+    let name = name.trim_matches(|c: char| c == '.' || c.is_whitespace());
+
+    if name.is_empty() {
+        "untitled".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// The `key: value` settings from a block of front matter at the top of a markdown file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrontMatter<'a> {
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> FrontMatter<'a> {
+    /// The value of the first `key` setting, with any surrounding quotes removed.
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == key)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Splits a front matter block delimited by `---` lines off the top of `markdown`, returning its
+/// settings and the rest of the document.
+///
+/// Only flat `key: value` lines are understood; anything else in the block (nested YAML, comments)
+/// is skipped. A block with no closing delimiter isn't front matter, so `markdown` comes back as-is.
+pub fn split_front_matter(markdown: &str) -> (Option<FrontMatter<'_>>, &str) {
+    let Some(rest) = strip_delimiter_line(markdown, &["---"]) else {
+        return (None, markdown);
+    };
+
+    let mut fields = Vec::new();
+    let mut remaining = rest;
+    while !remaining.is_empty() {
+        if let Some(body) = strip_delimiter_line(remaining, &["---", "..."]) {
+            return (Some(FrontMatter { fields }), body);
+        }
+
+        let (line, next) = remaining.split_once('\n').unwrap_or((remaining, ""));
+        if let Some((key, value)) = line.split_once(':') {
+            let key = key.trim();
+            if !key.is_empty() && !key.starts_with('#') && !line.starts_with(char::is_whitespace) {
+                fields.push((key, unquote(value.trim())));
+            }
+        }
+        remaining = next;
+    }
+
+    (None, markdown)
+}
+
+/// Strips the first line off of `text` if it's one of `delimiters`, give or take trailing
+/// whitespace.
+fn strip_delimiter_line<'a>(text: &'a str, delimiters: &[&str]) -> Option<&'a str> {
+    let (line, rest) = text.split_once('\n').unwrap_or((text, ""));
+    delimiters.contains(&line.trim_end()).then_some(rest)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+
+    value
+}
+
+/// Picks the document ID out of a link to a document, like
+/// `https://team.getoutline.com/doc/deploy-runbook-Xk3f9aQ2Ab/edit`, so links copied from the
+/// browser can be used wherever an ID is expected. getOutline accepts the short ID at the end of
+/// the slug (`Xk3f9aQ2Ab`) anywhere it accepts a full ID.
+///
+/// Anything which isn't a link to a document is returned unchanged.
+pub fn document_id_from_url(reference: &str) -> &str {
+    let Some((_, after_scheme)) = reference.split_once("://") else {
+        return reference;
+    };
+    let path = after_scheme
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(after_scheme);
+
+    let mut segments = path.split('/').skip(1);
+    while let Some(segment) = segments.next() {
+        if segment != "doc" {
+            continue;
+        }
+        return match segments.next() {
+            Some(slug) if !slug.is_empty() => slug.rsplit('-').next().unwrap_or(slug),
+            _ => reference,
+        };
+    }
+
+    reference
+}
+
+/// Whether `reference` is a full ID like `9bcb2e6b-5a8e-4b38-8b2b-0d6ae8c6f4e1`, rather than the
+/// short one from a document's link.
+pub fn is_uuid(reference: &str) -> bool {
+    reference.len() == 36
+        && reference.char_indices().all(|(index, c)| match index {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
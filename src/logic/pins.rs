@@ -0,0 +1,161 @@
+use serde::Deserialize;
+
+use super::documents::DocumentSummary;
+use super::{fetch_all, ConnectionError, Page};
+
+/// A document pinned to the top of a collection's page, or of the workspace's home page.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pin {
+    pub id: String,
+    pub document_id: String,
+    /// The collection the document is pinned in, or `None` if it's pinned to the home page.
+    #[serde(default)]
+    pub collection_id: Option<String>,
+}
+
+/// One page of pins, along with the documents they refer to.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PinPage {
+    pub pins: Vec<Pin>,
+    pub documents: Vec<DocumentSummary>,
+}
+
+/// Something which can list pinned documents.
+pub trait PinReader {
+    /// Lists the pins in collection `collection_id`, or on the home page if it's `None`.
+    fn pins(&self, collection_id: Option<&str>, page: Page) -> Result<PinPage, ConnectionError>;
+}
+
+/// Something which can pin and unpin documents.
+pub trait PinWriter {
+    fn pin(&self, document_id: &str, collection_id: Option<&str>) -> Result<Pin, ConnectionError>;
+    fn unpin(&self, pin_id: &str) -> Result<(), ConnectionError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PinError {
+    #[error("document {document_id} isn't pinned {}", place(.collection_id.as_deref()))]
+    NotPinned {
+        document_id: String,
+        collection_id: Option<String>,
+    },
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+}
+
+fn place(collection_id: Option<&str>) -> String {
+    match collection_id {
+        Some(id) => format!("in collection {id}"),
+        None => "to the home page".to_string(),
+    }
+}
+
+/// Pins document `document_id` in collection `collection_id`, or to the home page if it's `None`.
+pub fn pin_document(
+    writer: &impl PinWriter,
+    document_id: &str,
+    collection_id: Option<&str>,
+) -> Result<(), ConnectionError> {
+    writer.pin(document_id, collection_id).map(|_| ())
+}
+
+/// Unpins document `document_id` (a full or short ID) from collection `collection_id`, or from the
+/// home page if it's `None`.
+pub fn unpin_document<C>(
+    client: &C,
+    document_id: &str,
+    collection_id: Option<&str>,
+) -> Result<(), PinError>
+where
+    C: PinReader + PinWriter,
+{
+    let (pins, documents) = all_pins(client, collection_id)?;
+    let id = documents
+        .iter()
+        .find(|document| document.is(document_id))
+        .map_or(document_id, |document| document.id.as_str());
+    let pin = pins
+        .iter()
+        .find(|pin| pin.document_id == id)
+        .ok_or_else(|| PinError::NotPinned {
+            document_id: document_id.to_string(),
+            collection_id: collection_id.map(str::to_string),
+        })?;
+
+    Ok(client.unpin(&pin.id)?)
+}
+
+/// Lists the documents pinned in collection `collection_id`, or to the home page if it's `None`,
+/// in the order they're pinned in.
+pub fn pinned_documents(
+    reader: &impl PinReader,
+    collection_id: Option<&str>,
+) -> Result<Vec<DocumentSummary>, ConnectionError> {
+    let (pins, documents) = all_pins(reader, collection_id)?;
+
+    Ok(pins
+        .iter()
+        .filter_map(|pin| {
+            documents
+                .iter()
+                .find(|document| document.id == pin.document_id)
+        })
+        .cloned()
+        .collect())
+}
+
+fn all_pins(
+    reader: &impl PinReader,
+    collection_id: Option<&str>,
+) -> Result<(Vec<Pin>, Vec<DocumentSummary>), ConnectionError> {
+    let mut documents = Vec::new();
+    let pins = fetch_all(|page| {
+        let page = reader.pins(collection_id, page)?;
+        documents.extend(page.documents);
+        Ok(page.pins)
+    })?;
+
+    Ok((pins, documents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::fake::{short_id, MemoryWorkspace};
+
+    #[test]
+    fn documents_are_unpinned_by_their_full_or_short_id() {
+        let workspace = MemoryWorkspace::default();
+        let document = workspace.add_document("Deploy runbook");
+
+        for reference in [document.id.clone(), short_id(&document.id)] {
+            pin_document(&workspace, &document.id, Some("runbooks")).unwrap();
+            unpin_document(&workspace, &reference, Some("runbooks")).unwrap();
+            assert!(pinned_documents(&workspace, Some("runbooks"))
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn unpinning_a_document_pinned_somewhere_else_fails() {
+        let workspace = MemoryWorkspace::default();
+        let document = workspace.add_document("Deploy runbook");
+        pin_document(&workspace, &document.id, Some("runbooks")).unwrap();
+
+        assert!(matches!(
+            unpin_document(&workspace, &short_id(&document.id), None),
+            Err(PinError::NotPinned {
+                collection_id: None,
+                ..
+            })
+        ));
+        assert_eq!(
+            pinned_documents(&workspace, Some("runbooks"))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,83 @@
+/// The setting listing which commands a profile may run.
+pub const ALLOWED_COMMANDS: &str = "ALLOWED_COMMANDS";
+
+/// A list of the commands a profile is allowed to run.
+///
+/// Each entry is a command path like `documents save`. An entry also allows every subcommand
+/// beneath it, so `documents` allows all of the document commands.
+#[derive(Debug, Clone)]
+pub struct CommandPolicy {
+    allowed: Vec<Vec<String>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("`{command}` isn't one of the allowed commands ({allowed})")]
+pub struct CommandNotAllowed {
+    command: String,
+    allowed: String,
+}
+
+impl CommandPolicy {
+    /// Reads a comma-separated list of command paths, e.g. `documents list, documents save`.
+    pub fn parse(spec: &str) -> Self {
+        let allowed = spec
+            .split(',')
+            .map(|entry| entry.split_whitespace().map(str::to_string).collect())
+            .filter(|entry: &Vec<String>| !entry.is_empty())
+            .collect();
+
+        Self { allowed }
+    }
+
+    /// Checks whether the command at `command_path` (e.g. `["documents", "save"]`) may be run.
+    pub fn check(&self, command_path: &[String]) -> Result<(), CommandNotAllowed> {
+        let allowed = self
+            .allowed
+            .iter()
+            .any(|entry| command_path.starts_with(entry));
+        if allowed {
+            return Ok(());
+        }
+
+        Err(CommandNotAllowed {
+            command: command_path.join(" "),
+            allowed: self
+                .allowed
+                .iter()
+                .map(|entry| entry.join(" "))
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(command: &str) -> Vec<String> {
+        command.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn entries_allow_every_command_beneath_them() {
+        let policy = CommandPolicy::parse("documents, collections  list,");
+
+        assert!(policy.check(&path("documents save")).is_ok());
+        assert!(policy.check(&path("documents")).is_ok());
+        assert!(policy.check(&path("collections list")).is_ok());
+        assert!(policy.check(&path("collections")).is_err());
+        assert!(policy.check(&path("collections delete")).is_err());
+    }
+
+    #[test]
+    fn entries_only_match_whole_command_names() {
+        let policy = CommandPolicy::parse("doc");
+        let err = policy.check(&path("documents save")).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "`documents save` isn't one of the allowed commands (doc)"
+        );
+    }
+}
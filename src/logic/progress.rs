@@ -0,0 +1,57 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Which documents a bulk operation has finished with, so a run which stopped early can be
+/// picked up again without redoing them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// What the operation was, like `rewrite-links --from a.example.com --to b.example.com`, so
+    /// progress isn't picked up by a different one.
+    pub operation: String,
+    done: BTreeSet<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProgressError {
+    #[error("there's no saved progress to resume")]
+    NothingToResume,
+    #[error("the saved progress is for `{saved}`, which is a different operation")]
+    DifferentOperation { saved: String },
+}
+
+impl Progress {
+    /// Starts `operation` from the beginning.
+    pub fn new(operation: &str) -> Self {
+        Self {
+            operation: operation.to_string(),
+            done: BTreeSet::new(),
+        }
+    }
+
+    /// Carries on with `operation` from the `saved` progress, which must be for the same
+    /// operation.
+    pub fn resume(saved: Option<Progress>, operation: &str) -> Result<Self, ProgressError> {
+        let saved = saved.ok_or(ProgressError::NothingToResume)?;
+        if saved.operation != operation {
+            return Err(ProgressError::DifferentOperation {
+                saved: saved.operation,
+            });
+        }
+
+        Ok(saved)
+    }
+
+    pub fn is_done(&self, document_id: &str) -> bool {
+        self.done.contains(document_id)
+    }
+
+    pub fn mark_done(&mut self, document_id: &str) {
+        self.done.insert(document_id.to_string());
+    }
+
+    /// How many documents have been finished with, including in earlier runs.
+    pub fn done_count(&self) -> usize {
+        self.done.len()
+    }
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// How many documents are remembered.
+const MAX_RECENT: usize = 20;
+
+/// A document which was recently saved or opened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentDocument {
+    pub id: String,
+    pub title: String,
+}
+
+/// The documents used most recently, newest first, so they can be referred to as `@last` or
+/// `@<n>` instead of by ID.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecentDocuments {
+    documents: Vec<RecentDocument>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RecentError {
+    #[error("{0} isn't a recent document shortcut, use @last or @<number> (e.g. @2)")]
+    InvalidShortcut(String),
+    #[error("there's no recent document {reference}, only {count} are remembered")]
+    NoSuchDocument { reference: String, count: usize },
+}
+
+impl RecentDocuments {
+    /// Moves document `id` to the front of the list, adding it if it wasn't there.
+    pub fn record(&mut self, id: &str, title: &str) {
+        self.documents.retain(|document| document.id != id);
+        self.documents.insert(
+            0,
+            RecentDocument {
+                id: id.to_string(),
+                title: title.to_string(),
+            },
+        );
+        self.documents.truncate(MAX_RECENT);
+    }
+
+    /// Turns a shortcut like `@last` or `@2` into the ID it stands for. Anything which doesn't
+    /// start with `@` is already an ID, and comes back unchanged.
+    pub fn resolve<'a>(&'a self, reference: &'a str) -> Result<&'a str, RecentError> {
+        let Some(shortcut) = reference.strip_prefix('@') else {
+            return Ok(reference);
+        };
+
+        let position = match shortcut {
+            "last" => 1,
+            number => number
+                .parse::<usize>()
+                .ok()
+                .filter(|&position| position > 0)
+                .ok_or_else(|| RecentError::InvalidShortcut(reference.to_string()))?,
+        };
+
+        self.documents
+            .get(position - 1)
+            .map(|document| document.id.as_str())
+            .ok_or_else(|| RecentError::NoSuchDocument {
+                reference: reference.to_string(),
+                count: self.documents.len(),
+            })
+    }
+
+    /// The remembered documents, newest first.
+    pub fn documents(&self) -> &[RecentDocument] {
+        &self.documents
+    }
+}
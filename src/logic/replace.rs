@@ -0,0 +1,107 @@
+use regex::Regex;
+
+use super::documents::{DocumentReader, DocumentSummary, DocumentUpdate, DocumentUpdater};
+use super::ConnectionError;
+
+/// A find-and-replace which would change one document.
+#[derive(Debug)]
+pub struct Replacement {
+    pub document: DocumentSummary,
+    pub original: String,
+    pub replaced: String,
+    /// How many times the pattern matched in the document.
+    pub matches: usize,
+    /// The document's revision when it was read, so the replacement isn't pushed over later edits.
+    pub revision: Option<u64>,
+}
+
+/// Works out how replacing `find` with `replace` would change one document, without changing
+/// anything, or returns `None` if it wouldn't change.
+///
+/// `replace` may refer to capture groups in `find` using `$1` or `${name}`, the same as
+/// [Regex::replace_all].
+pub fn plan_replacement(
+    reader: &impl DocumentReader,
+    summary: DocumentSummary,
+    find: &Regex,
+    replace: &str,
+) -> Result<Option<Replacement>, ConnectionError> {
+    let document = reader.info(&summary.id)?;
+    let matches = find.find_iter(&document.text).count();
+    if matches == 0 {
+        return Ok(None);
+    }
+
+    let replaced = find.replace_all(&document.text, replace).into_owned();
+    if replaced == document.text {
+        return Ok(None);
+    }
+
+    Ok(Some(Replacement {
+        document: summary,
+        original: document.text,
+        replaced,
+        matches,
+        revision: document.revision,
+    }))
+}
+
+/// Pushes a planned replacement to its document.
+pub fn apply_replacement(
+    updater: &impl DocumentUpdater,
+    replacement: &Replacement,
+) -> Result<DocumentSummary, ConnectionError> {
+    updater.update(&DocumentUpdate {
+        id: &replacement.document.id,
+        text: &replacement.replaced,
+        title: None,
+        append: false,
+        expected_revision: replacement.revision,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::fake::MemoryWorkspace;
+
+    /// A workspace with one document holding `text`, and that document.
+    fn workspace(text: &str) -> (MemoryWorkspace, DocumentSummary) {
+        let workspace = MemoryWorkspace::default();
+        let collection_id = workspace.add_collection("Engineering");
+        workspace.add_to_collection(&collection_id, None, "Runbook", text);
+        let summary = workspace.documents_in(&collection_id).remove(0).summary;
+        (workspace, summary)
+    }
+
+    #[test]
+    fn replacements_can_use_capture_groups() {
+        let (workspace, document) = workspace("Call ext 1234, or ext 5678.");
+        let find = Regex::new(r"ext (?<number>\d+)").unwrap();
+        let replacement = plan_replacement(&workspace, document, &find, "+1 555 ${number}")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(replacement.matches, 2);
+        assert_eq!(replacement.replaced, "Call +1 555 1234, or +1 555 5678.");
+        apply_replacement(&workspace, &replacement).unwrap();
+        let updated = workspace.info(&replacement.document.id).unwrap();
+        assert_eq!(updated.text, replacement.replaced);
+    }
+
+    #[test]
+    fn documents_which_wouldnt_change_are_left_out() {
+        let (workspace, document) = workspace("Restart the server.");
+        let missing = Regex::new("database").unwrap();
+        let same = Regex::new("(server)").unwrap();
+
+        assert!(
+            plan_replacement(&workspace, document.clone(), &missing, "db")
+                .unwrap()
+                .is_none()
+        );
+        assert!(plan_replacement(&workspace, document, &same, "$1")
+            .unwrap()
+            .is_none());
+    }
+}
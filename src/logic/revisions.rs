@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+
+use super::documents::{self, DocumentReader, DocumentSummary};
+use super::{Author, ConnectionError, Page};
+
+/// A snapshot of a document's content at some point in its history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Revision {
+    pub id: String,
+    pub document_id: String,
+    /// When the revision was made, as an ISO 8601 timestamp.
+    pub created_at: String,
+    pub created_by: Author,
+    /// The document's markdown as of this revision. Only filled in when a single revision is
+    /// looked up.
+    #[serde(default)]
+    pub text: String,
+}
+
+/// Something which can look up a document's past revisions.
+pub trait RevisionReader {
+    fn revision(&self, id: &str) -> Result<Revision, ConnectionError>;
+    /// Lists the revisions of document `document_id`, newest first.
+    fn revisions(&self, document_id: &str, page: Page) -> Result<Vec<Revision>, ConnectionError>;
+}
+
+/// Something which can roll a document back to one of its past revisions.
+pub trait DocumentRestorer {
+    fn restore(
+        &self,
+        document_id: &str,
+        revision_id: &str,
+    ) -> Result<DocumentSummary, ConnectionError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RevisionError {
+    #[error("document {document_id} has no revision {revision_id}")]
+    RevisionNotFound {
+        document_id: String,
+        revision_id: String,
+    },
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+}
+
+/// Lists one page of document `document_id`'s revision history, newest first.
+pub fn list_revisions(
+    reader: &impl RevisionReader,
+    document_id: &str,
+    page: Page,
+) -> Result<Vec<Revision>, ConnectionError> {
+    reader.revisions(document_id, page)
+}
+
+/// Restores document `document_id` (a full or short ID) to revision `revision_id`, after checking
+/// that the revision actually belongs to that document.
+pub fn restore_revision<C>(
+    client: &C,
+    document_id: &str,
+    revision_id: &str,
+) -> Result<DocumentSummary, RevisionError>
+where
+    C: RevisionReader + DocumentRestorer + DocumentReader,
+{
+    let canonical = documents::canonical_id(client, document_id)?;
+    find_revision(client, document_id, &canonical, revision_id)?;
+    Ok(client.restore(&canonical, revision_id)?)
+}
+
+/// Fetches revisions `from_id` and `to_id` of document `document_id` (a full or short ID) so they
+/// can be compared.
+pub fn revision_pair<C>(
+    client: &C,
+    document_id: &str,
+    from_id: &str,
+    to_id: &str,
+) -> Result<(Revision, Revision), RevisionError>
+where
+    C: RevisionReader + DocumentReader,
+{
+    let canonical = documents::canonical_id(client, document_id)?;
+    let from = find_revision(client, document_id, &canonical, from_id)?;
+    let to = find_revision(client, document_id, &canonical, to_id)?;
+
+    Ok((from, to))
+}
+
+/// Looks up revision `revision_id`, treating a revision of some other document than the one with
+/// the full ID `canonical` as missing. `document_id` is how the document was referred to.
+fn find_revision(
+    reader: &impl RevisionReader,
+    document_id: &str,
+    canonical: &str,
+    revision_id: &str,
+) -> Result<Revision, RevisionError> {
+    let not_found = || RevisionError::RevisionNotFound {
+        document_id: document_id.to_string(),
+        revision_id: revision_id.to_string(),
+    };
+
+    let revision = match reader.revision(revision_id) {
+        Ok(revision) => revision,
+        Err(ConnectionError::NotFound { .. }) => return Err(not_found()),
+        Err(err) => return Err(err.into()),
+    };
+    if revision.document_id != canonical {
+        return Err(not_found());
+    }
+
+    Ok(revision)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::fake::{short_id, MemoryWorkspace};
+
+    /// A workspace with a document which has one revision, and another document with another.
+    fn history() -> (MemoryWorkspace, DocumentSummary, String, String) {
+        let workspace = MemoryWorkspace::default();
+        let document = workspace.add_document("Deploy");
+        let other = workspace.add_document("Rollback");
+        let revision = workspace.add_revision(&document.id);
+        let other_revision = workspace.add_revision(&other.id);
+        (workspace, document, revision, other_revision)
+    }
+
+    #[test]
+    fn documents_referred_to_by_their_short_id_can_be_restored() {
+        let (workspace, document, revision, _) = history();
+
+        let restored = restore_revision(&workspace, &short_id(&document.id), &revision).unwrap();
+        assert_eq!(restored.id, document.id);
+    }
+
+    #[test]
+    fn full_ids_are_used_without_looking_them_up() {
+        let (workspace, document, revision, _) = history();
+
+        let (from, to) = revision_pair(&workspace, &document.id, &revision, &revision).unwrap();
+        assert_eq!((from.id, to.id), (revision.clone(), revision));
+        assert_eq!(workspace.info_calls(), 0);
+    }
+
+    #[test]
+    fn revisions_of_other_documents_are_not_restored() {
+        let (workspace, document, _, other_revision) = history();
+        let short_id = short_id(&document.id);
+
+        for revision in [other_revision.as_str(), "missing"] {
+            let err = restore_revision(&workspace, &short_id, revision).unwrap_err();
+            assert!(matches!(
+                err,
+                RevisionError::RevisionNotFound { document_id, .. } if document_id == short_id
+            ));
+        }
+    }
+}
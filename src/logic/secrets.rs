@@ -0,0 +1,35 @@
+//! Hiding the details of a workspace which shouldn't be shown to an audience, like API keys and
+//! share links, from text about to be printed.
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// getOutline API keys, like `ol_api_0123...`.
+const TOKEN: &str = r"ol_api_[A-Za-z0-9]+";
+/// Share links, which anyone who sees them can follow: `https://<host>/s/<id>` and anything after.
+const SHARE_URL: &str = r#"https?://[^\s/]+/s/[^\s)\]>"'`]*"#;
+const EMAIL: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)+";
+
+/// Replaces every API key, share link, and email address in `text` with a placeholder saying what
+/// was there, leaving the rest of the text as it is.
+pub fn hide_secrets(text: &str) -> Cow<'_, str> {
+    static PATTERNS: OnceLock<[(Regex, &str); 3]> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        let pattern = |source| Regex::new(source).expect("secret patterns are valid");
+        [
+            (pattern(TOKEN), "[API key hidden]"),
+            (pattern(SHARE_URL), "[share link hidden]"),
+            (pattern(EMAIL), "[email hidden]"),
+        ]
+    });
+
+    let mut text = Cow::Borrowed(text);
+    for (pattern, placeholder) in patterns {
+        if let Cow::Owned(hidden) = pattern.replace_all(&text, *placeholder) {
+            text = Cow::Owned(hidden);
+        }
+    }
+    text
+}
@@ -0,0 +1,99 @@
+//! Working out which section of a document some text is in, and how to link straight to it.
+
+use serde::Serialize;
+
+/// The heading a part of a document falls under.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Section {
+    /// The heading's text, without the leading `#`s.
+    pub heading: String,
+    /// The fragment getOutline gives the heading in the web app, like `h-deploy-steps`.
+    pub anchor: String,
+    /// The fragment markdown renderers like GitHub's give the heading in a saved copy, like
+    /// `deploy-steps`.
+    pub file_anchor: String,
+}
+
+/// Finds the section of `markdown` the first mention of `term` is in, ignoring case, or `None`
+/// if `term` doesn't appear or comes before the first heading.
+///
+/// A mention in a heading counts as being in that heading's section. Lines in code blocks are
+/// never taken for headings.
+pub fn section_containing(markdown: &str, term: &str) -> Option<Section> {
+    let term = term.trim().to_lowercase();
+    if term.is_empty() {
+        return None;
+    }
+
+    let mut section = None;
+    let mut anchors_seen: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if let Some(heading) = heading_text(line).filter(|_| !in_fence) {
+            let slug = outline_slug(heading);
+            let repeats = anchors_seen.iter().filter(|seen| **seen == slug).count();
+            anchors_seen.push(slug.clone());
+            section = Some(Section {
+                heading: heading.to_string(),
+                anchor: match repeats {
+                    0 => format!("h-{slug}"),
+                    n => format!("h-{slug}-{n}"),
+                },
+                file_anchor: match repeats {
+                    0 => file_slug(heading),
+                    n => format!("{}-{n}", file_slug(heading)),
+                },
+            });
+        }
+
+        if line.to_lowercase().contains(&term) {
+            return section;
+        }
+    }
+
+    None
+}
+
+/// The text of an ATX heading like `## Deploy steps`, if `line` is one.
+fn heading_text(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() >= 4 {
+        return None;
+    }
+
+    let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+    let rest = &trimmed[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    (!text.is_empty()).then_some(text)
+}
+
+/// The slug getOutline's editor makes from heading text: lowercased, with punctuation removed and
+/// whitespace turned into dashes.
+fn outline_slug(heading: &str) -> String {
+    slug(heading, |c| c.is_ascii_punctuation() && c != '-')
+}
+
+/// The slug GitHub and most other markdown renderers make from heading text, which keeps
+/// underscores.
+fn file_slug(heading: &str) -> String {
+    slug(heading, |c| {
+        c.is_ascii_punctuation() && c != '-' && c != '_'
+    })
+}
+
+fn slug(heading: &str, removed: impl Fn(char) -> bool) -> String {
+    heading
+        .chars()
+        .filter(|c| !removed(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_whitespace() { '-' } else { c })
+        .collect()
+}
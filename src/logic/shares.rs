@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::documents::{Document, DocumentReader};
+use super::{fetch_all, time, Author, ConnectionError, Page};
+
+/// A link which lets people read a document without being invited to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Share {
+    pub id: String,
+    /// The link to hand out.
+    pub url: String,
+    /// Whether the link works for anyone, rather than only members of the workspace.
+    pub published: bool,
+    pub document_title: String,
+    #[serde(default)]
+    pub created_by: Option<Author>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// When someone last followed the link, as an ISO 8601 timestamp, or `None` if nobody has.
+    #[serde(default)]
+    pub last_accessed_at: Option<String>,
+}
+
+/// Something which can list the share links in the workspace.
+pub trait ShareReader {
+    fn list(&self, page: Page) -> Result<Vec<Share>, ConnectionError>;
+    /// Looks up the share link for document `document_id`.
+    fn for_document(&self, document_id: &str) -> Result<Share, ConnectionError>;
+}
+
+/// Something which can read a document the way it's seen through a share link.
+pub trait SharedDocumentReader {
+    /// Reads document `document_id` through share link `share_id`, which can be the document's
+    /// own link or one for a document it's nested under.
+    fn shared(&self, share_id: &str, document_id: &str) -> Result<Document, ConnectionError>;
+}
+
+/// Something which can create share links for documents.
+pub trait ShareWriter {
+    fn create(&self, document_id: &str, published: bool) -> Result<Share, ConnectionError>;
+    fn set_published(&self, share_id: &str, published: bool) -> Result<Share, ConnectionError>;
+    fn revoke(&self, share_id: &str) -> Result<(), ConnectionError>;
+}
+
+/// Creates a read-only share link for document `document_id`. Unless `published` is set, only
+/// members of the workspace can follow it.
+///
+/// getOutline keeps one link per document, so a document which has already been shared gets its
+/// existing link back, published or not as asked.
+pub fn share_link(
+    writer: &impl ShareWriter,
+    document_id: &str,
+    published: bool,
+) -> Result<Share, ConnectionError> {
+    let share = writer.create(document_id, published)?;
+    if share.published == published {
+        return Ok(share);
+    }
+
+    writer.set_published(&share.id, published)
+}
+
+/// Lists one page of the share links the API key can see, most recently updated first.
+pub fn list_shares(reader: &impl ShareReader, page: Page) -> Result<Vec<Share>, ConnectionError> {
+    reader.list(page)
+}
+
+/// A published share link found by [audit_shares].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditedShare {
+    #[serde(flatten)]
+    pub share: Share,
+    /// How long ago the link was created, or `None` if getOutline didn't say.
+    #[serde(rename = "ageSeconds", serialize_with = "seconds")]
+    pub age: Option<Duration>,
+    /// Whether the link is older than the audit's threshold, and so due for review.
+    pub flagged: bool,
+}
+
+fn seconds<S: serde::Serializer>(age: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    age.map(|age| age.as_secs()).serialize(serializer)
+}
+
+/// Lists every published share link, which anyone on the internet can follow, oldest first,
+/// flagging the ones created more than `threshold` before `now` (in seconds since the Unix epoch).
+pub fn audit_shares(
+    reader: &impl ShareReader,
+    now: u64,
+    threshold: Duration,
+) -> Result<Vec<AuditedShare>, ConnectionError> {
+    let mut shares: Vec<_> = fetch_all(|page| reader.list(page))?
+        .into_iter()
+        .filter(|share| share.published)
+        .map(|share| {
+            let age = share
+                .created_at
+                .as_deref()
+                .and_then(time::unix_time)
+                .map(|created| Duration::from_secs(now.saturating_sub(created)));
+            AuditedShare {
+                flagged: age.is_some_and(|age| age > threshold),
+                age,
+                share,
+            }
+        })
+        .collect();
+    shares.sort_by_key(|audited| std::cmp::Reverse(audited.age));
+
+    Ok(shares)
+}
+
+/// Stops share link `share_id` from working. Creating a new link for the document afterwards gives
+/// it a different URL.
+pub fn revoke_share(writer: &impl ShareWriter, share_id: &str) -> Result<(), ConnectionError> {
+    writer.revoke(share_id)
+}
+
+/// Stops the share link for document `document_id` from working, returning the link which was
+/// revoked.
+pub fn revoke_document_share<C>(client: &C, document_id: &str) -> Result<Share, ConnectionError>
+where
+    C: ShareReader + ShareWriter,
+{
+    let share = client.for_document(document_id)?;
+    client.revoke(&share.id)?;
+
+    Ok(share)
+}
+
+/// Picks the share's ID out of a share link, like `https://team.getoutline.com/s/Jf4kSx2aPq` or
+/// `https://team.getoutline.com/s/Jf4kSx2aPq/doc/intro-Xk3f9aQ2Ab` for a document nested under the
+/// shared one.
+///
+/// Anything which isn't a share link is returned unchanged, so the ID itself works too.
+pub fn share_id_from_url(reference: &str) -> &str {
+    let Some((_, after_scheme)) = reference.split_once("://") else {
+        return reference;
+    };
+    let path = after_scheme
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(after_scheme);
+
+    let mut segments = path.split('/').skip(1);
+    while let Some(segment) = segments.next() {
+        if segment != "s" {
+            continue;
+        }
+        return match segments.next() {
+            Some(id) if !id.is_empty() => id,
+            _ => reference,
+        };
+    }
+
+    reference
+}
+
+/// Reads document `document_id` both as it is now and as readers of share link `share_id` see
+/// it, in that order.
+pub fn document_and_shared<C>(
+    client: &C,
+    document_id: &str,
+    share_id: &str,
+) -> Result<(Document, Document), ConnectionError>
+where
+    C: DocumentReader + SharedDocumentReader,
+{
+    let latest = client.info(document_id)?;
+    let shared = client.shared(share_id, document_id)?;
+
+    Ok((latest, shared))
+}
@@ -0,0 +1,121 @@
+use serde::Deserialize;
+
+use super::documents::DocumentSummary;
+use super::{fetch_all, ConnectionError, Page};
+
+/// A document or collection the user has starred.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Star {
+    pub id: String,
+    /// The starred document, or `None` if this star is for a collection.
+    pub document_id: Option<String>,
+}
+
+/// One page of the user's stars, along with the documents they refer to.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StarPage {
+    pub stars: Vec<Star>,
+    pub documents: Vec<DocumentSummary>,
+}
+
+/// Something which can list the current user's stars.
+pub trait StarReader {
+    fn stars(&self, page: Page) -> Result<StarPage, ConnectionError>;
+}
+
+/// Something which can star and unstar documents for the current user.
+pub trait StarWriter {
+    fn star(&self, document_id: &str) -> Result<Star, ConnectionError>;
+    fn unstar(&self, star_id: &str) -> Result<(), ConnectionError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StarError {
+    #[error("document {0} isn't starred")]
+    NotStarred(String),
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+}
+
+/// Stars document `document_id`.
+pub fn star_document(writer: &impl StarWriter, document_id: &str) -> Result<(), ConnectionError> {
+    writer.star(document_id).map(|_| ())
+}
+
+/// Removes the star from document `document_id`, which can be its full or short ID.
+pub fn unstar_document<C>(client: &C, document_id: &str) -> Result<(), StarError>
+where
+    C: StarReader + StarWriter,
+{
+    let (stars, documents) = all_stars(client)?;
+    let id = documents
+        .iter()
+        .find(|document| document.is(document_id))
+        .map_or(document_id, |document| document.id.as_str());
+    let star = stars
+        .iter()
+        .find(|star| star.document_id.as_deref() == Some(id))
+        .ok_or_else(|| StarError::NotStarred(document_id.to_string()))?;
+
+    Ok(client.unstar(&star.id)?)
+}
+
+/// Lists every document the user has starred, in the order they appear in the sidebar.
+pub fn starred_documents(
+    reader: &impl StarReader,
+) -> Result<Vec<DocumentSummary>, ConnectionError> {
+    let (stars, documents) = all_stars(reader)?;
+
+    Ok(stars
+        .iter()
+        .filter_map(|star| star.document_id.as_deref())
+        .filter_map(|id| documents.iter().find(|document| document.id == id))
+        .cloned()
+        .collect())
+}
+
+fn all_stars(
+    reader: &impl StarReader,
+) -> Result<(Vec<Star>, Vec<DocumentSummary>), ConnectionError> {
+    let mut documents = Vec::new();
+    let stars = fetch_all(|page| {
+        let page = reader.stars(page)?;
+        documents.extend(page.documents);
+        Ok(page.stars)
+    })?;
+
+    Ok((stars, documents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::fake::{short_id, MemoryWorkspace};
+
+    #[test]
+    fn documents_are_unstarred_by_their_full_or_short_id() {
+        let workspace = MemoryWorkspace::default();
+        let document = workspace.add_document("Deploy runbook");
+
+        for reference in [document.id.clone(), short_id(&document.id)] {
+            star_document(&workspace, &document.id).unwrap();
+            unstar_document(&workspace, &reference).unwrap();
+            assert!(starred_documents(&workspace).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn unstarring_a_document_which_isnt_starred_fails() {
+        let workspace = MemoryWorkspace::default();
+        let starred = workspace.add_document("Deploy runbook");
+        let unstarred = short_id(&workspace.add_document("Rollback").id);
+        star_document(&workspace, &starred.id).unwrap();
+
+        assert!(matches!(
+            unstar_document(&workspace, &unstarred),
+            Err(StarError::NotStarred(id)) if id == unstarred
+        ));
+        assert_eq!(starred_documents(&workspace).unwrap().len(), 1);
+    }
+}
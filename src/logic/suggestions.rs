@@ -0,0 +1,92 @@
+//! Guesses at what was meant when a document ID doesn't match anything, for "did you mean"
+//! hints.
+
+use super::documents::{DocumentLister, DocumentSummary};
+use super::{fetch_all, ConnectionError};
+
+/// The most guesses worth showing at once.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Finds the documents whose ID or title is closest to `reference`, best match first.
+pub fn suggest_documents(
+    lister: &impl DocumentLister,
+    reference: &str,
+) -> Result<Vec<DocumentSummary>, ConnectionError> {
+    let documents = fetch_all(|page| lister.list(page))?;
+    Ok(closest_matches(reference, documents))
+}
+
+/// Picks the `candidates` which look like what `reference` was meant to be: a truncated or
+/// mistyped ID, or (part of) a title. The best matches come first.
+pub fn closest_matches(reference: &str, candidates: Vec<DocumentSummary>) -> Vec<DocumentSummary> {
+    let reference = reference.trim().to_lowercase();
+    if reference.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<_> = candidates
+        .into_iter()
+        .filter_map(|candidate| Some((score(&reference, &candidate)?, candidate)))
+        .collect();
+    scored.sort_by(|(a, a_doc), (b, b_doc)| a.cmp(b).then_with(|| a_doc.title.cmp(&b_doc.title)));
+
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// How far `candidate` is from `reference` (lower is closer), or `None` if it's not worth
+/// suggesting at all.
+fn score(reference: &str, candidate: &DocumentSummary) -> Option<usize> {
+    let ids = std::iter::once(candidate.id.as_str()).chain(candidate.url_id.as_deref());
+    let title = candidate.title.to_lowercase();
+
+    let mut best = None;
+    let mut consider =
+        |score: usize| best = Some(best.map_or(score, |best: usize| best.min(score)));
+
+    for id in ids {
+        let id = id.to_lowercase();
+        // A few characters are enough to tell IDs apart, but one or two would match everything
+        if reference.len() >= 4 && (id.starts_with(reference) || reference.starts_with(&id)) {
+            consider(0);
+        }
+        let distance = edit_distance(reference, &id);
+        if distance <= (id.len() / 8).max(2) {
+            consider(distance);
+        }
+    }
+
+    if title == reference {
+        consider(0);
+    } else if title.contains(reference) {
+        consider(1);
+    }
+    let distance = edit_distance(reference, &title);
+    if distance <= title.chars().count() / 3 {
+        consider(distance);
+    }
+
+    best
+}
+
+/// The Levenshtein distance between `a` and `b`: how many single-character insertions,
+/// deletions, or substitutions it takes to turn one into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
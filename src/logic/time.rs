@@ -0,0 +1,94 @@
+//! Converting between Unix times and the ISO 8601 timestamps getOutline uses, which are always in
+//! UTC and so compare the same way as the times they're for.
+//!
+//! Dates are converted with the algorithms from
+//! http://howardhinnant.github.io/date_algorithms.html, counting years from March so leap days
+//! fall at the end of each one.
+
+use std::time::Duration;
+
+/// The UTC timestamp for `ago` before the Unix time `now`, in seconds, in the same format getOutline
+/// uses: `2024-05-01T09:00:00Z`.
+pub fn timestamp_before(now: u64, ago: Duration) -> String {
+    utc_timestamp(now.saturating_sub(ago.as_secs()))
+}
+
+/// The Unix time `seconds` as an ISO 8601 timestamp in UTC, like `2024-05-01T09:00:00Z`.
+pub fn utc_timestamp(seconds: u64) -> String {
+    let (days, time) = (seconds / 86_400, seconds % 86_400);
+    let (hour, minute, second) = (time / 3600, time % 3600 / 60, time % 60);
+
+    // civil_from_days, counting days from 0000-03-01
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+/// The Unix time, in seconds, of an ISO 8601 timestamp in UTC like `2024-05-01T09:00:00Z` or
+/// `2024-05-01T09:00:00.000Z`, or of the start of a date like `2024-05-01`. Gives `None` for
+/// anything else, or times before 1970.
+pub fn unix_time(timestamp: &str) -> Option<u64> {
+    let number = |range: std::ops::Range<usize>| -> Option<u64> {
+        let digits = timestamp.get(range)?;
+        if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    };
+    let separated = |at: usize, separator: u8| timestamp.as_bytes().get(at) == Some(&separator);
+
+    if !separated(4, b'-') || !separated(7, b'-') {
+        return None;
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let time = match timestamp.get(10..)? {
+        "" => 0,
+        rest if rest.starts_with('T') && rest.ends_with('Z') => {
+            if !separated(13, b':') || !separated(16, b':') {
+                return None;
+            }
+            let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+            let fraction = &timestamp[19..timestamp.len() - 1];
+            let fraction_ok = fraction.is_empty()
+                || (fraction.len() > 1
+                    && fraction.starts_with('.')
+                    && fraction[1..].bytes().all(|byte| byte.is_ascii_digit()));
+            if hour > 23 || minute > 59 || second > 60 || !fraction_ok {
+                return None;
+            }
+            hour * 3600 + minute * 60 + second
+        }
+        _ => return None,
+    };
+
+    // days_from_civil, counting days from 0000-03-01
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let month_from_march = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+
+    Some(days * 86_400 + time)
+}
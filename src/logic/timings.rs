@@ -0,0 +1,62 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long the parts of one request to getOutline took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestTiming {
+    /// The API method called, like `documents.info`.
+    pub method: String,
+    /// Setting up the connection the request was sent over, or `None` if one which was already
+    /// open was reused.
+    pub setup: Option<ConnectionSetup>,
+    /// From sending the request until the response's headers arrived, not counting connection
+    /// setup: the time the server took, plus a round trip.
+    pub waiting: Duration,
+    /// Reading the response's body.
+    pub body: Duration,
+    /// The response's status, or `None` if no response came back.
+    pub status: Option<u16>,
+    /// The size of the response's body.
+    pub bytes: u64,
+}
+
+impl RequestTiming {
+    pub fn total(&self) -> Duration {
+        self.setup.map_or(Duration::ZERO, |setup| setup.total()) + self.waiting + self.body
+    }
+}
+
+/// How long opening a new connection to getOutline took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionSetup {
+    /// Looking up the server's address.
+    pub dns: Duration,
+    /// Connecting to the server.
+    pub connect: Duration,
+    /// The TLS handshake, or `None` for `http` URLs, which don't have one.
+    pub tls: Option<Duration>,
+}
+
+impl ConnectionSetup {
+    pub fn total(&self) -> Duration {
+        self.dns + self.connect + self.tls.unwrap_or_default()
+    }
+}
+
+/// Collects the timing of every request sent through the connections it's given to. One can be
+/// shared by several connections, so it covers everything a command does.
+#[derive(Debug, Default)]
+pub struct Timings {
+    requests: Mutex<Vec<RequestTiming>>,
+}
+
+impl Timings {
+    pub fn record(&self, timing: RequestTiming) {
+        self.requests.lock().unwrap().push(timing);
+    }
+
+    /// The requests recorded so far, in the order they finished.
+    pub fn requests(&self) -> Vec<RequestTiming> {
+        self.requests.lock().unwrap().clone()
+    }
+}
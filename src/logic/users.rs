@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use super::{fetch_all, ConnectionError, Page};
+
+/// Someone with an account in the workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    pub id: String,
+    pub name: String,
+    /// Only visible to admins, or for users who share their email with the workspace.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// What the user can do in the workspace, e.g. `admin`, `member`, or `viewer`.
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub is_suspended: bool,
+    /// When the user last did anything in the workspace, as an ISO 8601 timestamp, or `None` if
+    /// they never have.
+    #[serde(default)]
+    pub last_active_at: Option<String>,
+}
+
+/// What a user can do in the workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Member,
+    Viewer,
+}
+
+/// Someone to invite to the workspace.
+#[derive(Debug, Clone, Serialize)]
+pub struct Invite {
+    pub email: String,
+    pub name: String,
+    pub role: Role,
+}
+
+/// Which users to list.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UserQuery<'a> {
+    /// Only lists users whose name (or email) contains this.
+    pub name: Option<&'a str>,
+    /// Lists suspended users instead of active ones.
+    pub suspended: bool,
+    /// Only lists admins.
+    pub admins: bool,
+}
+
+/// Something which can look up the users in the workspace.
+pub trait UserReader {
+    fn info(&self, id: &str) -> Result<User, ConnectionError>;
+    fn list(&self, query: &UserQuery, page: Page) -> Result<Vec<User>, ConnectionError>;
+}
+
+/// Something which can invite people to the workspace.
+pub trait UserInviter {
+    /// Sends each of `invites` an email asking them to join, returning the users created for them.
+    fn invite(&self, invites: &[Invite]) -> Result<Vec<User>, ConnectionError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UserError {
+    #[error("no user has the email address {0}")]
+    UnknownEmail(String),
+    #[error(transparent)]
+    Connection(#[from] ConnectionError),
+}
+
+/// Lists one page of the users matching `query`, sorted by name.
+pub fn list_users(
+    reader: &impl UserReader,
+    query: &UserQuery,
+    page: Page,
+) -> Result<Vec<User>, ConnectionError> {
+    reader.list(query, page)
+}
+
+/// Looks up a user by their ID, or by their email address if `user` looks like one.
+///
+/// getOutline can only look users up by ID, so an email address is matched against the users
+/// listed for it, suspended or not. Only admins can see everyone's email address.
+pub fn user_info(reader: &impl UserReader, user: &str) -> Result<User, UserError> {
+    if !user.contains('@') {
+        return Ok(reader.info(user)?);
+    }
+
+    for suspended in [false, true] {
+        let query = UserQuery {
+            name: Some(user),
+            suspended,
+            admins: false,
+        };
+        let found = fetch_all(|page| reader.list(&query, page))?
+            .into_iter()
+            .find(|candidate| {
+                candidate
+                    .email
+                    .as_deref()
+                    .is_some_and(|email| email.eq_ignore_ascii_case(user))
+            });
+        if let Some(found) = found {
+            return Ok(found);
+        }
+    }
+
+    Err(UserError::UnknownEmail(user.to_string()))
+}
+
+/// Invites everyone in `invites` to the workspace in one request. getOutline skips anyone who
+/// already has an account, so the returned users may be fewer than the invites.
+pub fn invite_users(
+    inviter: &impl UserInviter,
+    invites: &[Invite],
+) -> Result<Vec<User>, ConnectionError> {
+    inviter.invite(invites)
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use super::users::User;
+use super::ConnectionError;
+
+/// Someone's visits to a document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct View {
+    pub user: User,
+    /// How many times they've opened the document.
+    pub count: u32,
+    /// When they first opened it, as an ISO 8601 timestamp.
+    #[serde(default)]
+    pub first_viewed_at: Option<String>,
+    /// When they last opened it, as an ISO 8601 timestamp.
+    #[serde(default)]
+    pub last_viewed_at: Option<String>,
+}
+
+/// Something which can look up who has viewed a document.
+pub trait ViewReader {
+    fn list(&self, document_id: &str) -> Result<Vec<View>, ConnectionError>;
+}
+
+/// Something which can record that the API key's user viewed a document.
+pub trait ViewRecorder {
+    fn create(&self, document_id: &str) -> Result<View, ConnectionError>;
+}
+
+/// Lists everyone who has viewed document `document_id`, most recent first.
+pub fn document_views(
+    reader: &impl ViewReader,
+    document_id: &str,
+) -> Result<Vec<View>, ConnectionError> {
+    let mut views = reader.list(document_id)?;
+    // ISO 8601 timestamps sort the same as the times they stand for
+    views.sort_by(|a, b| b.last_viewed_at.cmp(&a.last_viewed_at));
+    Ok(views)
+}
+
+/// Records a view of document `document_id`, the same as opening it in the web app would.
+pub fn mark_viewed(recorder: &impl ViewRecorder, document_id: &str) -> Result<(), ConnectionError> {
+    recorder.create(document_id).map(|_| ())
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use super::users::User;
+use super::ConnectionError;
+
+/// The workspace an API key belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+    pub id: String,
+    pub name: String,
+    /// The workspace's address in the browser.
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub subdomain: Option<String>,
+}
+
+/// Who an API key belongs to, and in which workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthInfo {
+    pub user: User,
+    pub team: Team,
+}
+
+/// A way of signing in to the workspace, like Google or email.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignInMethod {
+    pub id: String,
+    pub name: String,
+}
+
+/// How people sign in to the workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    #[serde(default)]
+    pub services: Vec<SignInMethod>,
+}
+
+/// Something which can describe the workspace an API key belongs to.
+pub trait WorkspaceReader {
+    fn auth_info(&self) -> Result<AuthInfo, ConnectionError>;
+    fn auth_config(&self) -> Result<AuthConfig, ConnectionError>;
+}
+
+/// The workspace an API key belongs to, who the key is for, and how people sign in.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceInfo {
+    pub team: Team,
+    pub user: User,
+    pub sign_in_methods: Vec<SignInMethod>,
+}
+
+/// Looks up the workspace an API key belongs to, and how people sign in to it.
+pub fn workspace_info(reader: &impl WorkspaceReader) -> Result<WorkspaceInfo, ConnectionError> {
+    let AuthInfo { user, team } = reader.auth_info()?;
+    let config = reader.auth_config()?;
+
+    Ok(WorkspaceInfo {
+        team,
+        user,
+        sign_in_methods: config.services,
+    })
+}
@@ -0,0 +1,224 @@
+//! Reflowing the paragraphs of a markdown document, for tools which prefer one line per paragraph
+//! or lines of a fixed width.
+
+use super::parsers::split_front_matter;
+
+/// How to lay out the lines of a document's paragraphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+    /// Keeps every line break as it is.
+    Preserve,
+    /// Joins each paragraph onto a single line.
+    Unwrap,
+    /// Reflows each paragraph into lines of at most this many characters, where its words allow.
+    Width(usize),
+}
+
+/// Reflows the paragraphs of `markdown` as `wrap` says.
+///
+/// Only plain paragraphs are touched. Code blocks, tables, headings, lists, quotes, HTML, and
+/// link reference definitions keep their lines exactly, as do hard line breaks within paragraphs.
+/// A line is never broken before a word which would turn it into something else, like a `-` which
+/// would start a list item.
+pub fn rewrap(markdown: &str, wrap: Wrap) -> String {
+    if wrap == Wrap::Preserve {
+        return markdown.to_string();
+    }
+
+    // Front matter isn't markdown, so it's kept as it is
+    let (_, body) = split_front_matter(markdown);
+    let mut output = markdown[..markdown.len() - body.len()].to_string();
+    output.reserve(body.len());
+
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    // HTML blocks run until the next blank line
+    let mut in_html = false;
+
+    for line in body.lines() {
+        if let Some((marker, length)) = fence {
+            if closes_fence(line, marker, length) {
+                fence = None;
+            }
+            push_line(&mut output, line);
+            continue;
+        }
+        if in_html {
+            in_html = !line.trim().is_empty();
+            push_line(&mut output, line);
+            continue;
+        }
+
+        if let Some(opened) = opens_fence(line) {
+            flush(&mut output, &mut paragraph, wrap);
+            fence = Some(opened);
+            push_line(&mut output, line);
+        } else if paragraph.is_empty() && line.trim_start().starts_with('<') {
+            in_html = true;
+            push_line(&mut output, line);
+        } else if is_paragraph_text(line, paragraph.is_empty()) {
+            paragraph.push(line);
+            if ends_with_hard_break(line) {
+                flush(&mut output, &mut paragraph, wrap);
+            }
+        } else {
+            flush(&mut output, &mut paragraph, wrap);
+            push_line(&mut output, line);
+        }
+    }
+    flush(&mut output, &mut paragraph, wrap);
+
+    if !body.is_empty() && !body.ends_with('\n') {
+        output.pop();
+    }
+    output
+}
+
+/// Breaks every line of `text` which is longer than `width` characters into shorter ones, at the
+/// last space which fits where there is one, for showing it in a window that wide.
+pub fn fit_lines(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let mut rest: &[char] = &line.chars().collect::<Vec<_>>();
+        while rest.len() > width {
+            match rest[..=width].iter().rposition(|&c| c == ' ') {
+                Some(space) if space > 0 => {
+                    lines.push(rest[..space].iter().collect());
+                    rest = &rest[space + 1..];
+                }
+                _ => {
+                    lines.push(rest[..width].iter().collect());
+                    rest = &rest[width..];
+                }
+            }
+        }
+        lines.push(rest.iter().collect());
+    }
+
+    lines
+}
+
+fn push_line(output: &mut String, line: &str) {
+    output.push_str(line);
+    output.push('\n');
+}
+
+/// Writes out the lines collected in `paragraph`, reflowed, and empties it.
+fn flush(output: &mut String, paragraph: &mut Vec<&str>, wrap: Wrap) {
+    let Some(first) = paragraph.first() else {
+        return;
+    };
+    let indent = &first[..first.len() - first.trim_start().len()];
+    let last = paragraph[paragraph.len() - 1];
+    let hard_break = if last.ends_with('\\') {
+        "\\"
+    } else if ends_with_hard_break(last) {
+        "  "
+    } else {
+        ""
+    };
+
+    let mut words: Vec<&str> = paragraph
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .collect();
+    // A trailing backslash is kept at the end of the last line rather than treated as a word
+    if hard_break == "\\" {
+        if let Some(word) = words.last_mut() {
+            *word = &word[..word.len() - 1];
+            if word.is_empty() {
+                words.pop();
+            }
+        }
+    }
+
+    write_words(output, indent, &words, wrap);
+    output.push_str(hard_break);
+    output.push('\n');
+    paragraph.clear();
+}
+
+/// Writes `words` as lines of at most `wrap`'s width, without a line break after the last one.
+fn write_words(output: &mut String, indent: &str, words: &[&str], wrap: Wrap) {
+    let width = match wrap {
+        Wrap::Width(width) => width,
+        Wrap::Preserve | Wrap::Unwrap => usize::MAX,
+    };
+
+    output.push_str(indent);
+    let mut line_length = indent.chars().count();
+    for (index, word) in words.iter().enumerate() {
+        let length = word.chars().count();
+        if index > 0 {
+            if line_length + 1 + length > width && !starts_block(word) {
+                output.push('\n');
+                output.push_str(indent);
+                line_length = indent.chars().count();
+            } else {
+                output.push(' ');
+                line_length += 1;
+            }
+        }
+        output.push_str(word);
+        line_length += length;
+    }
+}
+
+/// Whether `line` is ordinary paragraph text, which can be reflowed. `starts_paragraph` says
+/// whether it would be the first line of a paragraph, where indentation makes a code block.
+fn is_paragraph_text(line: &str, starts_paragraph: bool) -> bool {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+
+    !trimmed.is_empty()
+        && !(starts_paragraph && (indent >= 4 || line.starts_with('\t')))
+        && !starts_block(trimmed)
+        // Table rows don't need a leading pipe, so any pipe might be part of one
+        && !trimmed.contains('|')
+        && !is_reference_definition(trimmed)
+}
+
+/// Whether a line starting with `text` would be something other than paragraph text: a heading,
+/// quote, list item, thematic break, setext underline, HTML block, or the start of a table row.
+fn starts_block(text: &str) -> bool {
+    let Some(first) = text.chars().next() else {
+        return false;
+    };
+    if matches!(
+        first,
+        '#' | '>' | '-' | '+' | '*' | '=' | '|' | '<' | '`' | '~' | '_'
+    ) {
+        return true;
+    }
+
+    // Ordered list items, like `1.` or `2)`
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    digits > 0 && digits <= 9 && matches!(text[digits..].chars().next(), Some('.' | ')'))
+}
+
+/// Whether `text` looks like `[label]: destination`, which has to stay on its own line.
+fn is_reference_definition(text: &str) -> bool {
+    text.starts_with('[') && text.contains("]:")
+}
+
+fn ends_with_hard_break(line: &str) -> bool {
+    line.ends_with("  ") || line.ends_with('\\')
+}
+
+/// The marker character and length of the code fence `line` opens, if it opens one.
+fn opens_fence(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() >= 4 {
+        return None;
+    }
+
+    let marker = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let length = trimmed.len() - trimmed.trim_start_matches(marker).len();
+    (length >= 3).then_some((marker, length))
+}
+
+fn closes_fence(line: &str, marker: char, length: usize) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() >= length && trimmed.chars().all(|c| c == marker)
+}
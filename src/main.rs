@@ -1,3 +1,18 @@
+use clap::{CommandFactory, FromArgMatches};
+
+use get_outline::command::{self, CLIArgs};
+
 fn main() {
-    println!("Hello, world!");
+    let matches = CLIArgs::command().get_matches();
+    let args = CLIArgs::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+
+    let json = args.json;
+    if let Err(err) = command::exec_command(args, &command::command_path(&matches)) {
+        let code = command::errors::classify(&err);
+        // Nobody's reading stdout any more, so the command just stops
+        if code != command::errors::ErrorCode::StdoutClosed {
+            command::output::print_error(&err, code, json);
+        }
+        std::process::exit(code.exit_status());
+    }
 }
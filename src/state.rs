@@ -0,0 +1,95 @@
+//! Small files the CLI keeps between runs, like the list of recently used documents.
+//!
+//! They live in `GETOUTLINE_STATE_DIR` if it's set, or the platform's usual place for
+//! application state otherwise (`$XDG_STATE_HOME/get_outline`, falling back to
+//! `~/.local/state/get_outline`, or `%LOCALAPPDATA%\get_outline` on Windows). None of it is
+//! precious: a missing or unreadable file is treated as empty.
+//!
+//! Copies of documents are kept apart from the rest, in [cache_dir], since they can take up far
+//! more room and can always be fetched again.
+
+use std::io;
+use std::path::PathBuf;
+use std::{env, fs};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::config;
+
+const APP_DIR: &str = "get_outline";
+
+/// The directory state files are kept in, if one can be found.
+pub fn dir() -> Option<PathBuf> {
+    if let Some(dir) = config::get(None, "STATE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+
+    let base = env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+    Some(base.join(APP_DIR))
+}
+
+/// The directory copies of documents are cached in: `GETOUTLINE_CACHE_DIR` if it's set, or the
+/// platform's usual place for caches (`$XDG_CACHE_HOME/get_outline`, falling back to
+/// `~/.cache/get_outline`, or `%LOCALAPPDATA%\get_outline\cache` on Windows).
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = config::get(None, "CACHE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+
+    if let Some(base) = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    {
+        return Some(base.join(APP_DIR));
+    }
+    env::var_os("LOCALAPPDATA").map(|base| PathBuf::from(base).join(APP_DIR).join("cache"))
+}
+
+/// The name of the state file called `name` for `profile`, so each workspace keeps its own.
+pub fn file_name(profile: Option<&str>, name: &str) -> String {
+    match profile {
+        Some(profile) => format!("{name}-{profile}.json"),
+        None => format!("{name}.json"),
+    }
+}
+
+/// Reads the state file `file_name`, or the default value if it doesn't exist or can't be read.
+pub fn load<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    dir()
+        .and_then(|dir| fs::read(dir.join(file_name)).ok())
+        .and_then(|contents| serde_json::from_slice(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Replaces the state file `file_name` with `value`.
+pub fn save<T: Serialize>(file_name: &str, value: &T) -> io::Result<()> {
+    let dir = dir().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no directory to keep state in, set GETOUTLINE_STATE_DIR",
+        )
+    })?;
+    fs::create_dir_all(&dir)?;
+
+    let contents = serde_json::to_vec_pretty(value)?;
+    // Write to a temporary file first so an interrupted run can't leave a half-written file
+    let temp = dir.join(format!("{file_name}.tmp"));
+    fs::write(&temp, contents)?;
+    fs::rename(temp, dir.join(file_name))
+}
+
+/// Deletes the state file `file_name`, if there is one.
+pub fn remove(file_name: &str) -> io::Result<()> {
+    let Some(dir) = dir() else {
+        return Ok(());
+    };
+
+    match fs::remove_file(dir.join(file_name)) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
@@ -0,0 +1,21 @@
+//! Property tests for the candidates offered by shell completion.
+
+use get_outline::logic::completion::Candidate;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn completion_candidates_match_the_start_of_their_id_or_title_words(
+        id in "[A-Za-z0-9]{1,12}",
+        words in prop::collection::vec("[A-Za-z]{1,8}", 1..5),
+        cut in 1..8usize,
+    ) {
+        let candidate = Candidate { id: id.clone(), title: words.join(" ") };
+
+        prop_assert!(candidate.matches(""));
+        prop_assert!(candidate.matches(&id[..cut.min(id.len())].to_uppercase()));
+        for word in &words {
+            prop_assert!(candidate.matches(&word[..cut.min(word.len())].to_lowercase()));
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Property tests for reading and updating settings in a `.env` file.
+
+use get_outline::config::{env_file_value, set_env_file_value};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn env_file_settings_are_replaced_in_place(
+        before in "([a-z# =]*\n){0,3}",
+        after in "(\n[a-z# =]*){0,3}",
+        export in "(export )?",
+        old in "[A-Za-z0-9_]{1,40}",
+        new in "[A-Za-z0-9_]{1,40}",
+    ) {
+        let contents = format!("{before}{export}GETOUTLINE_API_KEY={old}{after}");
+        prop_assert_eq!(env_file_value(&contents, "GETOUTLINE_API_KEY"), Some(old));
+
+        let updated = set_env_file_value(&contents, "GETOUTLINE_API_KEY", &new);
+        prop_assert_eq!(&updated, &format!("{before}{export}GETOUTLINE_API_KEY={new}{after}"));
+        prop_assert_eq!(env_file_value(&updated, "GETOUTLINE_API_KEY"), Some(new));
+        prop_assert_eq!(env_file_value(&updated, "GETOUTLINE_API"), None);
+    }
+}
@@ -0,0 +1,37 @@
+//! Property tests for the CSV records printed by `--output csv`.
+
+use get_outline::logic::csv::csv_record;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn csv_records_read_back_as_their_fields(fields in prop::collection::vec(".*", 1..6)) {
+        prop_assert_eq!(read_csv_record(&csv_record(&fields)), fields);
+    }
+
+    #[test]
+    fn csv_fields_without_special_characters_are_left_alone(
+        fields in prop::collection::vec("[^,\"\r\n]*", 1..6),
+    ) {
+        prop_assert_eq!(csv_record(&fields), fields.join(","));
+    }
+}
+
+/// Splits one CSV record into its fields, the way spreadsheets read them.
+fn read_csv_record(record: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = record.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
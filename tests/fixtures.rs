@@ -0,0 +1,241 @@
+//! Checks the canned responses still decode into the types they stand in for.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use get_outline::fixtures::generate::FakeWorkspace;
+use get_outline::fixtures::{self, responses, DocumentBuilder, MockServer};
+use get_outline::logic::api_keys::{self, ApiKeyError};
+use get_outline::logic::cache::{self, CachedDocument, DocumentCache};
+use get_outline::logic::collections::{self, Collection};
+use get_outline::logic::documents::{self, ContentSource, Document, SearchResult};
+use get_outline::logic::ConnectionError;
+
+#[test]
+fn document_info_decodes_as_a_published_document() {
+    let document: Document = fixtures::decode(responses::DOCUMENT_INFO);
+
+    assert_eq!(document.title, "Deploy runbook");
+    assert!(document.text.starts_with("# Deploy runbook\n"));
+    assert!(document.published_at.is_some());
+    assert_eq!(document.revision, Some(7));
+}
+
+#[test]
+fn draft_document_info_has_no_publish_date() {
+    let document: Document = fixtures::decode(responses::DRAFT_DOCUMENT_INFO);
+
+    assert_eq!(document.published_at, None);
+}
+
+#[test]
+fn search_decodes_every_result() {
+    let results: Vec<SearchResult> = fixtures::decode(responses::DOCUMENT_SEARCH);
+
+    let titles: Vec<_> = results.iter().map(|r| r.document.title.as_str()).collect();
+    assert_eq!(titles, ["Deploy runbook", "On-call handbook"]);
+}
+
+#[test]
+fn search_results_link_to_the_web_app() {
+    let results: Vec<SearchResult> = fixtures::decode(responses::DOCUMENT_SEARCH);
+
+    let paths: Vec<_> = results.iter().map(|r| r.document.web_path()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/doc/deploy-runbook-Xk3f9aQ2",
+            "/doc/e2b6a9d4-1c3f-4a8e-b5d7-7f0c9e2a4b61"
+        ]
+    );
+}
+
+#[test]
+fn search_results_know_which_section_matched() {
+    let results: Vec<SearchResult> = fixtures::decode(responses::DOCUMENT_SEARCH);
+
+    let section = results[0]
+        .section
+        .as_ref()
+        .expect("the match is under a heading");
+    assert_eq!(section.heading, "After the deploy");
+    assert_eq!(section.anchor, "h-after-the-deploy");
+    assert!(results[1].section.is_none());
+}
+
+#[test]
+fn collection_info_decodes() {
+    let collection: Collection = fixtures::decode(responses::COLLECTION_INFO);
+
+    assert_eq!(collection.name, "Engineering");
+}
+
+#[test]
+fn error_responses_carry_a_message() {
+    for response in [responses::UNAUTHORIZED, responses::NOT_FOUND] {
+        let body: serde_json::Value = serde_json::from_str(response).unwrap();
+        assert!(body["message"].is_string(), "{response}");
+    }
+}
+
+#[test]
+fn builder_defaults_to_a_published_document() {
+    let document = DocumentBuilder::new("Notes").text("Hello").build();
+
+    assert_eq!(document.title, "Notes");
+    assert_eq!(document.text, "Hello");
+    assert!(document.published_at.is_some());
+    assert_eq!(
+        DocumentBuilder::new("Notes").draft().build().published_at,
+        None
+    );
+}
+
+#[test]
+fn client_builds_with_the_canned_key() {
+    assert!(fixtures::client("http://127.0.0.1:1").is_ok());
+}
+
+#[test]
+fn mock_server_answers_a_client() {
+    let server = MockServer::start(&[("documents.info", responses::DOCUMENT_INFO)]).unwrap();
+    let client = fixtures::client(server.url()).unwrap();
+
+    for _ in 0..2 {
+        let document = documents::fetch_document(&client, "any", ContentSource::Info).unwrap();
+        assert_eq!(document.title, "Deploy runbook");
+    }
+    assert!(matches!(
+        documents::fetch_document(&client, "any", ContentSource::Export),
+        Err(ConnectionError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn the_api_key_in_use_is_found_by_its_last_characters() {
+    let server = MockServer::start(&[("apiKeys.list", responses::API_KEYS_LIST)]).unwrap();
+    let client = fixtures::client(server.url()).unwrap();
+
+    let key = api_keys::find_api_key(&client, fixtures::API_KEY, None).unwrap();
+    assert_eq!(key.name, "CLI");
+    assert!(matches!(
+        api_keys::find_api_key(&client, "ol_api_someone_elses_key", None),
+        Err(ApiKeyError::NotFound { last4 }) if last4 == "_key"
+    ));
+    let by_id = api_keys::find_api_key(
+        &client,
+        fixtures::API_KEY,
+        Some("1b2c3d4e-5f60-4718-8a9b-0c1d2e3f4a5b"),
+    )
+    .unwrap();
+    assert_eq!(by_id.name, "Zapier");
+}
+
+#[test]
+fn generated_workspaces_only_depend_on_the_seed() {
+    let fake = FakeWorkspace::generate(50, 3, 7);
+    let titles = |fake: &FakeWorkspace| -> Vec<(String, String)> {
+        fake.collections
+            .iter()
+            .flat_map(|collection| &collection.documents)
+            .map(|document| (document.id.clone(), document.title.clone()))
+            .collect()
+    };
+
+    assert_eq!(fake.collections.len(), 3);
+    assert_eq!(fake.document_count(), 50);
+    assert_eq!(titles(&fake), titles(&FakeWorkspace::generate(50, 3, 7)));
+    assert_ne!(titles(&fake), titles(&FakeWorkspace::generate(50, 3, 8)));
+    for collection in &fake.collections {
+        for (index, document) in collection.documents.iter().enumerate() {
+            assert!(document.parent.is_none_or(|parent| parent < index));
+        }
+    }
+}
+
+#[test]
+fn mock_server_answers_from_a_generated_workspace() {
+    let fake = FakeWorkspace::generate(40, 4, 1);
+    let server = MockServer::start_workspace("127.0.0.1:0", fake.clone()).unwrap();
+    let client = fixtures::client(server.url()).unwrap();
+
+    let listed = collections::list_collections(&client).unwrap();
+    assert_eq!(listed.len(), 4);
+    for (stats, collection) in listed.iter().zip(&fake.collections) {
+        assert_eq!(stats.collection.name, collection.name);
+        assert_eq!(stats.document_count, collection.documents.len());
+    }
+    assert_eq!(documents::all_documents(&client).unwrap().len(), 40);
+
+    let expected = &fake.collections[1].documents[0];
+    let document = documents::fetch_document(&client, &expected.id, ContentSource::Info).unwrap();
+    assert_eq!(document.title, expected.title);
+    assert_eq!(document.text, expected.text);
+}
+
+/// A [DocumentCache] kept in memory.
+#[derive(Default)]
+struct MemoryCache(RefCell<HashMap<String, CachedDocument>>);
+
+impl DocumentCache for MemoryCache {
+    fn get(&self, id: &str, source: ContentSource) -> Option<CachedDocument> {
+        self.0.borrow().get(&format!("{id} {source:?}")).cloned()
+    }
+
+    fn put(&self, id: &str, source: ContentSource, cached: &CachedDocument) {
+        self.0
+            .borrow_mut()
+            .insert(format!("{id} {source:?}"), cached.clone());
+    }
+}
+
+#[test]
+fn fresh_cached_documents_are_read_without_a_connection() {
+    let cache = MemoryCache::default();
+    let copy = CachedDocument {
+        document: fixtures::decode(responses::DOCUMENT_INFO),
+        fetched_at: 1_000,
+    };
+    cache.put("any", ContentSource::Info, &copy);
+    let offline = fixtures::client("http://127.0.0.1:1").unwrap();
+
+    let ttl = Duration::from_secs(60);
+    let document =
+        cache::fetch_document_cached(&offline, &cache, "any", ContentSource::Info, ttl, 1_059)
+            .unwrap();
+    assert_eq!(document.title, "Deploy runbook");
+    assert!(
+        cache::fetch_document_cached(&offline, &cache, "any", ContentSource::Info, ttl, 1_060)
+            .is_err()
+    );
+}
+
+#[test]
+fn stale_exports_are_reused_while_the_revision_is_the_same() {
+    let server = MockServer::start(&[("documents.info", responses::DOCUMENT_INFO)]).unwrap();
+    let client = fixtures::client(server.url()).unwrap();
+    let cache = MemoryCache::default();
+    let mut copy = CachedDocument {
+        document: fixtures::decode(responses::DOCUMENT_INFO),
+        fetched_at: 0,
+    };
+    copy.document.text = "Rendered by the server".to_string();
+    cache.put("any", ContentSource::Export, &copy);
+
+    // The server can't export documents, so the text can only come from the cache
+    let document = cache::fetch_document_cached(
+        &client,
+        &cache,
+        "any",
+        ContentSource::Export,
+        Duration::ZERO,
+        1_000,
+    )
+    .unwrap();
+    assert_eq!(document.text, "Rendered by the server");
+    assert_eq!(
+        cache.get("any", ContentSource::Export).unwrap().fetched_at,
+        1_000
+    );
+}
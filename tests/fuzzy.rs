@@ -0,0 +1,50 @@
+//! Property tests for fuzzy matching typed queries against titles.
+
+use get_outline::logic::fuzzy::{fuzzy_match, fuzzy_rank};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn fuzzy_matches_point_at_the_typed_characters(
+        text in any::<String>(),
+        picks in prop::collection::vec(any::<prop::sample::Index>(), 1..6),
+    ) {
+        let characters: Vec<_> = text.chars().collect();
+        let candidates: Vec<_> = (0..characters.len())
+            .filter(|&index| !characters[index].is_whitespace())
+            .collect();
+        prop_assume!(!candidates.is_empty());
+        let mut picked: Vec<_> = picks.iter().map(|pick| *pick.get(&candidates)).collect();
+        picked.sort_unstable();
+        picked.dedup();
+        let query: String = picked.iter().map(|&index| characters[index]).collect();
+
+        let found = fuzzy_match(&query, &text).expect("a query taken from the text matches it");
+        prop_assert_eq!(found.positions.len(), picked.len());
+        prop_assert!(found.positions.windows(2).all(|pair| pair[0] < pair[1]));
+        let folded = |c: char| c.to_lowercase().next();
+        for (position, typed) in found.positions.iter().zip(query.chars()) {
+            prop_assert_eq!(folded(characters[*position]), folded(typed));
+        }
+    }
+
+    #[test]
+    fn fuzzy_rankings_put_the_best_matches_first(
+        query in "[a-c ]{0,4}",
+        candidates in prop::collection::vec("[a-d ]{0,12}", 0..12),
+    ) {
+        let ranked = fuzzy_rank(&query, candidates.iter().map(String::as_str));
+
+        let matching = candidates
+            .iter()
+            .filter(|candidate| fuzzy_match(&query, candidate).is_some())
+            .count();
+        prop_assert_eq!(ranked.len(), matching);
+        for pair in ranked.windows(2) {
+            prop_assert!(pair[0].1.score >= pair[1].1.score || query.trim().is_empty());
+        }
+        for (index, found) in &ranked {
+            prop_assert_eq!(Some(found.clone()), fuzzy_match(&query, &candidates[*index]));
+        }
+    }
+}
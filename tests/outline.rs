@@ -0,0 +1,43 @@
+//! Property tests for listing a collection's documents as an indented outline.
+
+use get_outline::logic::collections::{outline, DocumentNode};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn outlines_list_every_document_after_its_parent(tree in document_tree()) {
+        let entries = outline(&tree);
+
+        prop_assert_eq!(entries.len(), count_nodes(&tree));
+        prop_assert!(entries.first().is_none_or(|entry| entry.depth == 0));
+        for pair in entries.windows(2) {
+            prop_assert!(pair[1].depth <= pair[0].depth + 1);
+        }
+    }
+}
+
+/// Documents nested up to three levels deep.
+fn document_tree() -> impl Strategy<Value = Vec<DocumentNode>> {
+    let leaf = "[a-z]{1,8}".prop_map(|title| DocumentNode {
+        id: String::new(),
+        title,
+        children: Vec::new(),
+    });
+    let tree = leaf.prop_recursive(3, 24, 4, |children| {
+        ("[a-z]{1,8}", prop::collection::vec(children, 0..4)).prop_map(|(title, children)| {
+            DocumentNode {
+                id: String::new(),
+                title,
+                children,
+            }
+        })
+    });
+    prop::collection::vec(tree, 0..4)
+}
+
+fn count_nodes(nodes: &[DocumentNode]) -> usize {
+    nodes
+        .iter()
+        .map(|node| 1 + count_nodes(&node.children))
+        .sum()
+}
@@ -0,0 +1,218 @@
+//! Property tests for the functions which read untrusted document content and user input.
+
+use get_outline::logic::attachments::attachment_id;
+use get_outline::logic::parsers::{
+    document_id_from_url, file_name_for, fit_lines, is_uuid, rewrap, rewrite_links,
+    section_containing, share_id_from_url, split_front_matter, Wrap,
+};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn file_names_are_safe_on_every_platform(title in any::<String>()) {
+        let name = file_name_for(&title);
+
+        prop_assert!(!name.is_empty());
+        prop_assert!(!name.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']));
+        prop_assert!(!name.chars().any(char::is_control));
+        prop_assert!(!name.starts_with('.') && !name.ends_with('.'));
+        prop_assert!(!name.starts_with(char::is_whitespace) && !name.ends_with(char::is_whitespace));
+    }
+
+    #[test]
+    fn file_names_keep_ordinary_titles(title in "[A-Za-z0-9][A-Za-z0-9 _-]{0,40}[A-Za-z0-9]") {
+        prop_assert_eq!(file_name_for(&title), title);
+    }
+
+    #[test]
+    fn rewriting_links_only_changes_reported_links(markdown in any::<String>()) {
+        let (rewritten, links) = rewrite_links(&markdown, "old.example", "new.example");
+
+        if links.is_empty() {
+            prop_assert_eq!(&rewritten, &markdown);
+        }
+        for link in &links {
+            prop_assert!(link.new.contains("://new.example"));
+        }
+    }
+
+    #[test]
+    fn rewritten_links_point_at_the_new_host(
+        text in "[a-z ]{1,20}",
+        path in "(/[a-z0-9]{1,8}){0,3}",
+    ) {
+        let markdown = format!("[{text}](https://old.example{path})");
+        let (rewritten, links) = rewrite_links(&markdown, "old.example", "new.example");
+
+        prop_assert_eq!(links.len(), 1);
+        prop_assert_eq!(rewritten, format!("[{text}](https://new.example{path})"));
+    }
+
+    #[test]
+    fn front_matter_body_is_the_end_of_the_document(markdown in any::<String>()) {
+        let (front_matter, body) = split_front_matter(&markdown);
+
+        prop_assert!(markdown.ends_with(body));
+        if front_matter.is_none() {
+            prop_assert_eq!(body, markdown.as_str());
+        }
+    }
+
+    #[test]
+    fn front_matter_fields_are_read(title in "[A-Za-z0-9][A-Za-z0-9 ]{0,30}[A-Za-z0-9]", body in "[^-]{0,100}") {
+        let markdown = format!("---\ntitle: \"{title}\"\ntags: [a, b]\n---\n{body}");
+        let (front_matter, rest) = split_front_matter(&markdown);
+
+        prop_assert_eq!(front_matter.and_then(|fields| fields.get("title")), Some(title.as_str()));
+        prop_assert_eq!(rest, body.as_str());
+    }
+
+    #[test]
+    fn ids_pass_through_unchanged(id in "[A-Za-z0-9-]{1,40}") {
+        prop_assert_eq!(document_id_from_url(&id), id.as_str());
+    }
+
+    #[test]
+    fn document_links_yield_the_short_id(
+        slug in "[a-z0-9]{1,10}(-[a-z0-9]{1,10}){0,4}",
+        url_id in "[A-Za-z0-9]{10}",
+        suffix in "(/edit)?(\\?[a-z=]{0,10})?(#[a-z-]{0,10})?",
+    ) {
+        let url = format!("https://team.getoutline.com/doc/{slug}-{url_id}{suffix}");
+        prop_assert_eq!(document_id_from_url(&url), url_id.as_str());
+    }
+
+    #[test]
+    fn full_ids_are_told_apart_from_short_ones(bits in any::<u128>(), url_id in "[A-Za-z0-9]{10}") {
+        let hex = format!("{bits:032x}");
+        let id = format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..]);
+        prop_assert!(is_uuid(&id));
+        prop_assert!(!is_uuid(&url_id));
+    }
+
+    #[test]
+    fn arbitrary_references_never_panic(reference in any::<String>()) {
+        let id = document_id_from_url(&reference);
+        prop_assert!(reference.contains(id));
+    }
+
+    #[test]
+    fn share_links_yield_the_share_id(
+        share_id in "[A-Za-z0-9-]{10,36}",
+        suffix in "(/doc/[a-z]{1,10}-[A-Za-z0-9]{10})?(\\?[a-z=]{0,10})?(#[a-z-]{0,10})?",
+    ) {
+        let url = format!("https://team.getoutline.com/s/{share_id}{suffix}");
+        prop_assert_eq!(share_id_from_url(&url), share_id.as_str());
+        prop_assert_eq!(share_id_from_url(&share_id), share_id.as_str());
+    }
+
+    #[test]
+    fn attachment_ids_are_safe_file_names(destination in any::<String>()) {
+        if let Some(id) = attachment_id(&destination) {
+            prop_assert!(!id.is_empty());
+            prop_assert!(id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+        }
+    }
+
+    #[test]
+    fn attachment_links_yield_their_id(
+        origin in "(https://team\\.getoutline\\.com)?",
+        id in "[a-f0-9]{8}-[a-f0-9]{4}-[a-f0-9]{4}",
+    ) {
+        let destination = format!("{origin}/api/attachments.redirect?id={id}");
+        prop_assert_eq!(attachment_id(&destination), Some(id.as_str()));
+    }
+
+    #[test]
+    fn rewrapping_keeps_every_word(markdown in any::<String>(), width in 1usize..120) {
+        let words = |text: &str| text.split_whitespace().map(str::to_string).collect::<Vec<_>>();
+
+        prop_assert_eq!(rewrap(&markdown, Wrap::Preserve), markdown.clone());
+        for wrap in [Wrap::Unwrap, Wrap::Width(width)] {
+            let rewrapped = rewrap(&markdown, wrap);
+            // Only a trailing backslash can move, from the end of one word to the next
+            prop_assert_eq!(words(&rewrapped.replace('\\', "")), words(&markdown.replace('\\', "")));
+        }
+    }
+
+    #[test]
+    fn paragraphs_are_reflowed_to_the_width(
+        words in prop::collection::vec("[a-z]{1,12}", 1..60),
+        width in 20usize..100,
+    ) {
+        let paragraph = words.join(" ");
+        let rewrapped = rewrap(&format!("{paragraph}\n"), Wrap::Width(width));
+
+        for line in rewrapped.lines() {
+            prop_assert!(line.len() <= width, "{line:?} is longer than {width}");
+        }
+        prop_assert_eq!(rewrap(&rewrapped, Wrap::Unwrap), format!("{paragraph}\n"));
+    }
+
+    #[test]
+    fn code_blocks_are_left_alone(
+        code in prop::collection::vec("[a-z ]{0,30}", 1..8),
+        words in prop::collection::vec("[a-z]{1,8}", 1..30),
+    ) {
+        let code = code.join("\n");
+        let text = words.join("\n");
+        let fenced = format!("```rust\n{code}\n```");
+        let indented = code.lines().map(|line| format!("    x{line}")).collect::<Vec<_>>().join("\n");
+        let markdown = format!("{text}\n\n{fenced}\n\n{indented}\n\n{text}\n");
+
+        for wrap in [Wrap::Unwrap, Wrap::Width(20)] {
+            let rewrapped = rewrap(&markdown, wrap);
+            prop_assert!(rewrapped.contains(&fenced));
+            prop_assert!(rewrapped.contains(&indented));
+        }
+    }
+
+    #[test]
+    fn tables_are_left_alone(
+        cells in prop::collection::vec(("[a-z ]{1,20}", "[a-z ]{1,20}"), 1..6),
+        words in prop::collection::vec("[a-z]{1,8}", 1..30),
+    ) {
+        let rows = cells
+            .iter()
+            .map(|(left, right)| format!("| {left} | {right} |"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let table = format!("| A | B |\n|---|---|\n{rows}");
+        let markdown = format!("{}\n{table}\n", words.join("\n"));
+
+        for wrap in [Wrap::Unwrap, Wrap::Width(20)] {
+            prop_assert!(rewrap(&markdown, wrap).contains(&table));
+        }
+    }
+
+    #[test]
+    fn fitted_lines_fit_and_keep_every_character(text in any::<String>(), width in 1usize..80) {
+        let fitted = fit_lines(&text, width);
+
+        for line in &fitted {
+            prop_assert!(line.chars().count() <= width);
+        }
+        let visible = |text: &str| text.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        prop_assert_eq!(visible(&fitted.concat()), visible(&text));
+    }
+
+    #[test]
+    fn sections_are_found_under_their_heading(
+        heading in "[A-Za-z][A-Za-z0-9 ]{0,30}[A-Za-z0-9]",
+        before in "[a-z ]{0,40}",
+    ) {
+        let markdown = format!("# Intro\n\n{before}\n\n## {heading}\n\nThe needle is here.\n");
+        let section = section_containing(&markdown, "NEEDLE");
+
+        prop_assert_eq!(section.map(|section| section.heading), Some(heading));
+    }
+
+    #[test]
+    fn section_anchors_are_fragment_safe(markdown in any::<String>(), term in "[a-z]{1,3}") {
+        if let Some(section) = section_containing(&markdown, &term) {
+            prop_assert!(section.anchor.starts_with("h-"));
+            prop_assert!(!section.anchor.contains(['#', ' ', '?', '/']));
+            prop_assert!(!section.file_anchor.contains(['#', ' ', '?', '/']));
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Property tests for hiding secrets in text printed with `--no-secrets-output`.
+
+use get_outline::logic::secrets::hide_secrets;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn hidden_secrets_never_show(
+        before in "[a-z ]{0,20}",
+        user in "[a-z][a-z0-9.]{0,10}",
+        domain in "[a-z]{1,10}\\.[a-z]{2,5}",
+        key in "[A-Za-z0-9]{38}",
+        share in "[A-Za-z0-9-]{10,36}",
+    ) {
+        let email = format!("{user}@{domain}");
+        let key = format!("ol_api_{key}");
+        let share = format!("https://{domain}/s/{share}");
+        let text = format!("{before} {email} {key} ({share})");
+        let hidden = hide_secrets(&text);
+
+        prop_assert!(hidden.starts_with(&before));
+        prop_assert!(!hidden.contains(&email));
+        prop_assert!(!hidden.contains(&key));
+        prop_assert!(!hidden.contains(&share));
+        prop_assert_eq!(hide_secrets(&hidden), hidden.clone());
+    }
+
+    #[test]
+    fn text_without_secrets_is_printed_as_it_is(text in "[^@]*") {
+        prop_assume!(!text.contains("ol_api_") && !text.contains("/s/"));
+
+        prop_assert_eq!(hide_secrets(&text), text.as_str());
+    }
+}
@@ -0,0 +1,37 @@
+//! Property tests for converting between Unix times and the timestamps getOutline uses.
+
+use get_outline::logic::time::{unix_time, utc_timestamp};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn utc_timestamps_sort_in_time_order(a in 0..253_402_300_800u64, b in 0..253_402_300_800u64) {
+        let (earlier, later) = (utc_timestamp(a.min(b)), utc_timestamp(a.max(b)));
+
+        prop_assert_eq!(earlier.len(), "1970-01-01T00:00:00Z".len());
+        prop_assert_eq!(a == b, earlier == later);
+        prop_assert!(earlier <= later);
+    }
+
+    #[test]
+    fn utc_timestamps_count_whole_days(days in 0..2_932_896u64, seconds in 0..86_400u64) {
+        let midnight = utc_timestamp(days * 86_400);
+
+        prop_assert!(midnight.ends_with("T00:00:00Z"));
+        prop_assert_eq!(&utc_timestamp(days * 86_400 + seconds)[..10], &midnight[..10]);
+    }
+
+    #[test]
+    fn utc_timestamps_read_back_as_their_unix_time(seconds in 0..253_402_300_800u64) {
+        let timestamp = utc_timestamp(seconds);
+
+        prop_assert_eq!(unix_time(&timestamp), Some(seconds));
+        prop_assert_eq!(unix_time(&timestamp.replace('Z', ".123Z")), Some(seconds));
+        prop_assert_eq!(unix_time(&timestamp[..10]), Some(seconds - seconds % 86_400));
+    }
+
+    #[test]
+    fn arbitrary_timestamps_never_panic(timestamp in any::<String>()) {
+        unix_time(&timestamp);
+    }
+}